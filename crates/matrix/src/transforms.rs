@@ -62,3 +62,13 @@ pub fn scale_matrix(scl: f32) -> mat4 {
 		[0.0, 0.0, 0.0, 1.0],
 	])
 }
+
+/// Scale by a different factor along each axis.
+pub fn scale3_matrix(scl: vec3) -> mat4 {
+	mat4::from([
+		[scl.x(), 0.0, 0.0, 0.0], //
+		[0.0, scl.y(), 0.0, 0.0],
+		[0.0, 0.0, scl.z(), 0.0],
+		[0.0, 0.0, 0.0, 1.0],
+	])
+}
@@ -16,6 +16,11 @@ pub struct Flags {
 	#[arg(long, short)]
 	pub no_bake: bool,
 
+	/// Only rebuild the navigation graph for bots (keep everything else).
+	/// Useful when tuning the navigation graph.
+	#[arg(long)]
+	pub nav_only: bool,
+
 	//#[arg(long)]
 	//pub convergence_test: bool,
 	#[arg(long)]
@@ -58,6 +63,9 @@ fn main_result() -> Result<()> {
 
 fn bake_one(flags: &Flags, map_name: &str) -> Result<()> {
 	println!("map: {}", &map_name);
+	if flags.nav_only {
+		return shadowbake::convert_nav_graph(map_name);
+	}
 	let map_dir = assets_dir().find_map_dir(&map_name);
 	let bake_opts = load_bake_opts(&map_dir)?;
 	let bake_opts = override_bake_settings(flags, bake_opts);
@@ -22,10 +22,15 @@ pub fn convert_only(map_name: &str, opts: &BakeOpts) -> Result<ParsedGltf> {
 	print_scene_objects(&parsed_gltf);
 	let face_tree = convert_collider_mesh(&parsed_gltf.objects);
 	save_collider_mesh(&map_dir, &face_tree)?;
+	save_mover_meshes(&map_dir, &parsed_gltf.mover_objects)?;
+
+	// 🧭 navigation graph for bots (needs the above)
+	convert_nav_graph(map_name)?;
 
 	let parsed_gltf = ParsedGltf {
 		objects: parsed_gltf.objects, // TODO: repack lightmap?
 		metadata: parsed_gltf.metadata,
+		..default()
 	};
 	save_scene_mesh(&map_dir, &parsed_gltf.objects)?;
 
@@ -160,25 +165,35 @@ fn print_scene_objects(parsed_gltf: &ParsedGltf) {
 // Place GLTF triangles in a BVH tree for efficient collision detection.
 fn convert_collider_mesh(gltf_objects: &[GltfObject]) -> Node<ColliderFace> {
 	println!("\nConverting collider mesh");
-	let mut collider_faces = vec![];
-	for GltfObject { primitives: meshes, .. } in gltf_objects {
-		for Primitive { material, mesh } in meshes {
-			let material = *material;
-			for indices in mesh.iter_triangle_indices() {
-				let vert = indices.map(|i| mesh.positions[i as usize]);
-				collider_faces.push(ColliderFace { vert, material });
-			}
-		}
-	}
-	Node::build_tree(collider_faces)
+	build_collider_mesh(gltf_objects.iter().flat_map(|obj| &obj.primitives))
+}
+
+/// (Re-)build the navigation graph of an already converted map (needs its collider mesh and metadata).
+pub fn convert_nav_graph(map_name: &str) -> Result<()> {
+	println!("\nBuilding navigation graph");
+	let map = Map::load(map_name)?;
+	let graph = build_nav_graph(&map);
+	println!("  {} nodes, {} edges", graph.nodes.len(), graph.num_edges());
+	graph.save(&assets_dir().find_map_dir(map_name))
 }
 
 fn save_collider_mesh(map_dir: &MapDir, tree: &Node<ColliderFace>) -> Result<()> {
 	save_bincode_gz(tree, &map_dir.collider_mesh_file())
 }
 
+fn save_mover_meshes(map_dir: &MapDir, mover_objects: &Vec<GltfObject>) -> Result<()> {
+	save_bincode_gz(mover_objects, &map_dir.mover_mesh_file())
+}
+
 fn clean_map_dir(map_dir: &MapDir) -> Result<()> {
-	let paths = [map_dir.lightmap_dir(), map_dir.collider_mesh_file(), map_dir.metadata_file(), map_dir.scene_mesh_file()];
+	let paths = [
+		map_dir.lightmap_dir(),
+		map_dir.collider_mesh_file(),
+		map_dir.mover_mesh_file(),
+		map_dir.nav_graph_file(),
+		map_dir.metadata_file(),
+		map_dir.scene_mesh_file(),
+	];
 
 	for path in &paths {
 		match force_remove(path) {
@@ -11,6 +11,11 @@ pub(crate) struct CustomProperties {
 	/// (entering the box triggers the jump pad).
 	pub jump_pad_height: Option<f32>,
 
+	/// Convert a node into a jump pad that propels players towards the named node
+	/// (typically an Empty), passing `jump_pad_height` above the highest of both
+	/// (or `DEFAULT_JUMP_PAD_APEX` if not set).
+	pub jump_pad_target: Option<String>,
+
 	/// Convert a node into a spawn point for given team (0,1).
 	/// (games without teams ignore the team and use all spawn points).
 	pub spawn_point_team: Option<u8>,
@@ -25,6 +30,18 @@ pub(crate) struct CustomProperties {
 	/// Object is deadly lava.
 	pub lava: Option<bool>,
 
+	/// Turn object into a mover (door, lift, platform,...) that follows the node's
+	/// translation keyframes (Blender animation) back and forth. E.g.:
+	/// 	"mover_trigger": "proximity", // move out while players are near (doors)
+	/// 	"mover_trigger": "timer",     // move all the time (lifts)
+	pub mover_trigger: Option<String>,
+
+	/// How close players need to be to trigger a "proximity" mover (default `DEFAULT_MOVER_RADIUS`).
+	pub mover_radius: Option<f32>,
+
+	/// Seconds a mover waits at the end of its path before turning back (default `DEFAULT_MOVER_WAIT`).
+	pub mover_wait: Option<f32>,
+
 	/// blender hack: it's all to easy to accidentally add a custom property (defaults to "prop": 1.0).
 	/// Ignore it for convenience.
 	pub prop: Option<f32>,
@@ -47,8 +64,22 @@ impl CustomProperties {
 	}
 }
 
+/// Height above the highest of start and target that targeted jump pads
+/// launch players to, unless `jump_pad_height` is set.
+const DEFAULT_JUMP_PAD_APEX: f32 = 2.0;
+
+const DEFAULT_MOVER_RADIUS: f32 = 2.0;
+const DEFAULT_MOVER_WAIT: f32 = 2.0;
+
 // Apply custom properties to GLTF nodes (during parsing), modifying their meaning.
-pub(crate) fn apply_custom_properties(parsed: &mut ParsedGltf, node: &gltf::Node, meshes: &[Primitive], custom_properties: &CustomProperties, depth: u32) -> Result<()> {
+pub(crate) fn apply_custom_properties(
+	parsed: &mut ParsedGltf,
+	parent_transform: &mat4,
+	node: &gltf::Node,
+	meshes: &[Primitive],
+	custom_properties: &CustomProperties,
+	depth: u32,
+) -> Result<()> {
 	let raw = node.extras().as_ref().map(|v| v.get()).unwrap_or_default();
 	if !raw.is_empty() {
 		println!("{}↳🔧 custom_properties: {}", padding(depth + 1), raw.chars().filter(|&c| c != '\n' && c != '\t').collect::<String>())
@@ -63,11 +94,17 @@ pub(crate) fn apply_custom_properties(parsed: &mut ParsedGltf, node: &gltf::Node
 		println!("{}↳👤 spawn point @{}", padding(depth + 1), position);
 	}
 
-	if let Some(jump_height) = custom_properties.jump_pad_height {
+	if custom_properties.jump_pad_height.is_some() || custom_properties.jump_pad_target.is_some() {
+		let jump_height = custom_properties.jump_pad_height.unwrap_or(DEFAULT_JUMP_PAD_APEX);
 		println!("{}↳⏫ jump pad {}m", padding(depth + 1), jump_height);
+		if let Some(target) = &custom_properties.jump_pad_target {
+			println!("{}↳🎯 jump pad target: {}", padding(depth + 1), target);
+			parsed.jump_pad_targets.push((parsed.metadata.jump_pads.len(), target.clone()));
+		}
 		parsed.metadata.jump_pads.push(JumpPad {
 			bounds: bounding_box(meshes)?,
 			jump_height,
+			target: None, // resolved after parsing all nodes, see `resolve_jump_pad_targets`.
 		});
 	}
 
@@ -82,6 +119,35 @@ pub(crate) fn apply_custom_properties(parsed: &mut ParsedGltf, node: &gltf::Node
 		})
 	}
 
+	if let Some(trigger) = &custom_properties.mover_trigger {
+		let trigger = match trigger.as_str() {
+			"proximity" => MoverTrigger::Proximity {
+				radius: custom_properties.mover_radius.unwrap_or(DEFAULT_MOVER_RADIUS),
+			},
+			"timer" => MoverTrigger::Timer,
+			other => bail!("mover_trigger: unknown trigger {other:?}, options: proximity, timer"),
+		};
+
+		// keyframes are local translations, convert to offsets from the rest position in world space.
+		let (rest, _, _) = node.transform().decomposed();
+		let rest = vec3::from(rest);
+		let keyframes = parsed.translation_keyframes.get(&node.index()).ok_or_else(|| anyhow!("mover: node has no translation animation"))?;
+		let t0 = keyframes.first().map(|(t, _)| *t).unwrap_or_default();
+		let keyframes = keyframes.iter().map(|&(t, pos)| (t - t0, parent_transform.mul((pos - rest).append(0.0)).xyz())).collect_vec();
+
+		let name = Handle::from_str(node.name().unwrap_or_default())?;
+		let def = MoverDef {
+			name,
+			keyframes,
+			trigger,
+			wait: custom_properties.mover_wait.unwrap_or(DEFAULT_MOVER_WAIT),
+			bounds: bounding_box(meshes)?,
+		};
+		println!("{}↳🚪 mover: {:?}, {} keyframes, {}s", padding(depth + 1), def.trigger, def.keyframes.len(), def.duration());
+		parsed.metadata.movers.push(def);
+		parsed.mover_objects.push(GltfObject { name, primitives: meshes.to_vec() });
+	}
+
 	if let Some(sky_color) = custom_properties.sky_color {
 		println!("{}↳⛅ sky_color: {:?}", padding(depth + 1), sky_color);
 		parsed.metadata.sky_color = sky_color.into();
@@ -89,3 +155,13 @@ pub(crate) fn apply_custom_properties(parsed: &mut ParsedGltf, node: &gltf::Node
 
 	Ok(())
 }
+
+// Set the landing point of jump pads with a `jump_pad_target`,
+// now that the positions of all nodes are known.
+pub(crate) fn resolve_jump_pad_targets(parsed: &mut ParsedGltf) -> Result<()> {
+	for (i, target) in &parsed.jump_pad_targets {
+		let pos = *parsed.node_positions.get(target).ok_or_else(|| anyhow!("jump_pad_target: no such node: {target}"))?;
+		parsed.metadata.jump_pads[*i].target = Some(pos);
+	}
+	Ok(())
+}
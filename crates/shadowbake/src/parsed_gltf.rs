@@ -1,4 +1,6 @@
 use crate::prelude::*;
+use gltf::animation::util::ReadOutputs;
+use gltf::animation::Interpolation;
 use gltf::khr_lights_punctual::Light;
 
 /// See `parse_gltf`.
@@ -8,6 +10,19 @@ pub struct ParsedGltf {
 	pub objects: Vec<GltfObject>,
 
 	pub metadata: Metadata,
+
+	/// World position of every named node (incl. Empties), for resolving references between nodes.
+	pub(crate) node_positions: HashMap<String, vec3>,
+
+	/// Jump pads (index into `metadata.jump_pads`) whose target node still needs resolving.
+	pub(crate) jump_pad_targets: Vec<(usize, String)>,
+
+	/// Meshes of movers (see `metadata.movers`), kept out of `objects`:
+	/// they are not lightmapped and not part of the static collider mesh.
+	pub mover_objects: Vec<GltfObject>,
+
+	/// Animated node translations: (time, local translation), by node index. Used by movers.
+	pub(crate) translation_keyframes: HashMap<usize, Vec<(f32, vec3)>>,
 }
 
 /// Interpret and validate a GLTF (+BIN) file for use as game map.
@@ -23,12 +38,14 @@ pub(crate) fn parse_gltf(map_dir: &MapDir) -> Result<ParsedGltf> {
 
 	let mut parsed = ParsedGltf::default();
 	parsed.metadata.materials = parse_materials(&gltf)?;
+	parsed.translation_keyframes = parse_translation_keyframes(&gltf, &buffers)?;
 
 	// recursive add to `parsed`.
 	let scene = get_single_scene(&gltf)?;
 	for node in scene.nodes() {
 		parse_node(&buffers, &mut parsed, &mat4::UNIT, &node, 1).with_context(|| format!("Node {}", node.name().unwrap_or_default()))?;
 	}
+	resolve_jump_pad_targets(&mut parsed)?;
 	Ok(parsed)
 }
 
@@ -55,6 +72,26 @@ fn parse_materials(gltf: &gltf::Document) -> Result<MaterialPalette> {
 	Ok(materials)
 }
 
+/// Translation keyframes of all animated nodes, by node index.
+/// (Only translations are supported, rotation and scale animations are ignored).
+fn parse_translation_keyframes(gltf: &gltf::Document, buffers: &Buffers) -> Result<HashMap<usize, Vec<(f32, vec3)>>> {
+	let mut keyframes = HashMap::default();
+	for animation in gltf.animations() {
+		for channel in animation.channels() {
+			let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+			if let (Some(times), Some(ReadOutputs::Translations(translations))) = (reader.read_inputs(), reader.read_outputs()) {
+				if channel.sampler().interpolation() == Interpolation::CubicSpline {
+					bail!("animation {}: cubic spline interpolation not supported, use linear", animation.name().unwrap_or_default());
+				}
+				let node = channel.target().node();
+				println!(" 🎞️ animation {}: node {}", animation.name().unwrap_or_default(), node.name().unwrap_or_default());
+				keyframes.insert(node.index(), times.zip(translations.map(vec3::from)).collect_vec());
+			}
+		}
+	}
+	Ok(keyframes)
+}
+
 /// Recursively parse a GLTF node and its children. Add results to `parsed`.
 fn parse_node(buffers: &Buffers, parsed: &mut ParsedGltf, parent_transform: &mat4, node: &gltf::Node, depth: u32) -> Result<()> {
 	println!("{}↳🔷 node: {}", padding(depth), node.name().unwrap_or("<Unnamed>"),);
@@ -63,6 +100,7 @@ fn parse_node(buffers: &Buffers, parsed: &mut ParsedGltf, parent_transform: &mat
 
 	// chain transforms
 	let transform = parent_transform * &mat4::from(node.transform().matrix());
+	parsed.node_positions.insert(name.clone(), (&transform).mul(vec4(0.0, 0.0, 0.0, 1.0)).xyz());
 
 	parse_light(parsed, &transform, node, depth + 1)?;
 	let custom_properties = CustomProperties::parse(node.extras())?;
@@ -73,7 +111,7 @@ fn parse_node(buffers: &Buffers, parsed: &mut ParsedGltf, parent_transform: &mat
 		check_transform(node.transform().decomposed())?;
 		let meshes = parse_mesh(&buffers, &transform, mesh, depth + 1)?; // incl material
 
-		apply_custom_properties(parsed, node, &meshes, &custom_properties, depth + 1)?;
+		apply_custom_properties(parsed, parent_transform, node, &meshes, &custom_properties, depth + 1)?;
 
 		// only add mesh if not hidden by custom properties (e.g. spawn points are not rendered)
 		if !custom_properties.should_hide_mesh() && meshes.len() != 0 {
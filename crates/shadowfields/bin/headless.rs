@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Parser;
+use shadowfields::game::*;
+
+/// Headless client: join a server without window, GPU or audio,
+/// run a script of commands and print what the server says.
+/// See `HeadlessClient` for the script syntax.
+#[derive(Parser, Debug)]
+struct HeadlessFlags {
+	/// Server to connect to.
+	#[arg(long, default_value = "127.0.0.1:3344")]
+	server: String,
+
+	/// Player name.
+	#[arg(short, long, default_value = "headless")]
+	name: String,
+
+	/// Team (red|green|blue|auto).
+	#[arg(short, long, default_value = "auto")]
+	team: String,
+
+	/// Script to run (default: read from stdin).
+	script: Option<String>,
+}
+
+fn main() {
+	env_logger::init();
+	let args = HeadlessFlags::parse();
+	exit_on_error(main_result(args))
+}
+
+fn main_result(args: HeadlessFlags) -> Result<()> {
+	let script = match &args.script {
+		Some(file) => std::fs::read_to_string(file)?,
+		None => std::io::read_to_string(std::io::stdin())?,
+	};
+	let join_req = JoinRequest {
+		name: args.name,
+		avatar_id: 1,
+		team: parse_team_choice(&args.team)?,
+		identity_key: String::new(),
+	};
+	let mut client = HeadlessClient::connect(&args.server, join_req)?;
+	client.run_script(&script, &mut std::io::stdout())
+}
@@ -91,6 +91,14 @@ struct PlayFlags {
 	/// Player is a bot
 	#[arg(long)]
 	bot: Option<bool>,
+
+	/// Record the game to this demo file.
+	#[arg(long)]
+	record_demo: Option<String>,
+
+	/// Play back this demo file instead of connecting to a server.
+	#[arg(long)]
+	play_demo: Option<String>,
 }
 
 fn main() -> Result<()> {
@@ -130,5 +138,7 @@ fn override_play_settings(mut settings: Settings, flags: PlayFlags) -> Settings
 	flag_override(&mut settings.debug.profile, flags.profile);
 	flag_override(&mut settings.debug.monitor_mouse, flags.monitor_mouse);
 	flag_override(&mut settings.bot.enabled, flags.bot);
+	flag_override(&mut settings.demo.record, flags.record_demo.map(Some));
+	flag_override(&mut settings.demo.play, flags.play_demo.map(Some));
 	settings
 }
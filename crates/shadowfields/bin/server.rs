@@ -14,6 +14,11 @@ struct ServerFlags {
 
 	#[arg(long)]
 	settings: Option<String>,
+
+	/// Override the number of bots (`bots.count` in server.toml).
+	/// E.g. without any players, for a headless practice game to join later.
+	#[arg(long)]
+	bots: Option<u32>,
 }
 
 fn main() {
@@ -43,7 +48,10 @@ fn override_server_settings(opts: ServerOpts, flags: ServerFlags) -> ServerOpts
 		settings.addr = addr;
 	}
 	if let Some(maplist) = flags.maplist {
-		settings.maplist = maplist;
+		settings.maplist = maplist.into_iter().map(MapListEntry::named).collect();
+	}
+	if let Some(bots) = flags.bots {
+		settings.bots.count = bots;
 	}
 	settings
 }
@@ -5,19 +5,112 @@ use crate::prelude::*;
 pub struct BotSettings {
 	pub enabled: bool,
 	pub respawn_time: f32,
+	/// Seconds between seeing a new enemy and starting to engage it.
+	pub reaction_time: f32,
+	/// Seconds (on average) between having an enemy in the crosshairs and pulling the trigger.
 	pub response_time: f32,
 	pub aim_speed: f32, // radians / s.
+	/// Standard deviation of the aim error (radians), re-drawn every `AIM_ERROR_PERIOD`.
+	pub aim_error: f32,
+	/// Aim where a moving enemy will be this many seconds from now.
+	pub tracking_lead: f32,
+	/// How to dodge while fighting.
+	pub strafe: StrafePattern,
+	/// Seconds per strafe (on average, for `StrafePattern::Erratic`).
+	pub strafe_period: f32,
+	/// Fighting distance (m) the bot prefers. There's only one weapon (a rail gun),
+	/// so weapon preference comes down to sniping from afar vs. getting up close.
+	/// The bot does not chase enemies closer than this.
+	pub preferred_range: f32,
+	/// 0.0: only pick up what's in the way, 1.0: cross the map for any pickup.
+	pub pickup_greed: f32,
+	/// Fraction of full health below which the bot stops fighting and runs for pickups.
+	pub retreat_health: f32,
 	pub jump_period: f32,
 }
 
+/// Named skill levels, each with its own `BotSettings` (see `BotSettings::for_skill`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BotSkill {
+	Novice,
+	Easy,
+	#[default]
+	Medium,
+	Hard,
+	Expert,
+	Nightmare,
+}
+
+/// How a bot dodges while fighting.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrafePattern {
+	/// Stand still (well, keep walking towards the enemy).
+	#[default]
+	None,
+	/// Left, right, left, right... every `strafe_period`. Predictable.
+	Sideways,
+	/// Random direction and duration.
+	Erratic,
+}
+
 impl Default for BotSettings {
 	fn default() -> Self {
+		Self::for_skill(BotSkill::Medium).with(|s| s.enabled = false)
+	}
+}
+
+impl BotSettings {
+	/// Settings for a named skill level.
+	pub fn for_skill(skill: BotSkill) -> Self {
+		use BotSkill::*;
+		use StrafePattern::*;
+		#[rustfmt::skip]
+		let (reaction_time, response_time, aim_speed, aim_error_deg, tracking_lead, strafe, preferred_range, pickup_greed, retreat_health) = match skill {
+			Novice =>    (1.0, 0.9, 0.7, 8.0, 0.00, None,     4.0,  0.2, 0.0),
+			Easy =>      (0.7, 0.7, 1.0, 5.0, 0.00, Sideways, 6.0,  0.3, 0.1),
+			Medium =>    (0.5, 0.5, 1.5, 3.0, 0.05, Sideways, 8.0,  0.5, 0.2),
+			Hard =>      (0.3, 0.3, 2.5, 1.5, 0.10, Sideways, 12.0, 0.6, 0.3),
+			Expert =>    (0.2, 0.2, 3.5, 0.8, 0.15, Erratic,  16.0, 0.8, 0.35),
+			Nightmare => (0.1, 0.1, 5.0, 0.3, 0.20, Erratic,  20.0, 1.0, 0.4),
+		};
 		Self {
-			enabled: false,
+			enabled: true,
 			respawn_time: 1.0,
-			response_time: 0.5,
-			aim_speed: 1.5,
+			reaction_time,
+			response_time,
+			aim_speed,
+			aim_error: aim_error_deg * DEG,
+			tracking_lead,
+			strafe,
+			strafe_period: 1.0,
+			preferred_range,
+			pickup_greed,
+			retreat_health,
 			jump_period: 10.0,
 		}
 	}
 }
+
+impl BotSkill {
+	pub const ALL: [BotSkill; 6] = [BotSkill::Novice, BotSkill::Easy, BotSkill::Medium, BotSkill::Hard, BotSkill::Expert, BotSkill::Nightmare];
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Each skill level is at least as good as the one below, in every respect.
+	#[test]
+	fn skill_levels_are_ordered() {
+		for (lo, hi) in BotSkill::ALL.into_iter().map(BotSettings::for_skill).tuple_windows() {
+			assert!(hi.reaction_time < lo.reaction_time);
+			assert!(hi.response_time < lo.response_time);
+			assert!(hi.aim_speed > lo.aim_speed);
+			assert!(hi.aim_error < lo.aim_error);
+			assert!(hi.tracking_lead >= lo.tracking_lead);
+			assert!(hi.pickup_greed > lo.pickup_greed);
+		}
+	}
+}
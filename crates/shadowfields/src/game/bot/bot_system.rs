@@ -16,86 +16,241 @@ pub(crate) struct Bot {
 
 	// Strategic
 	engaging_enemy_id: Option<ID>, // ✅
-	waypoint: Option<vec3>,        // ✅ TODO: enemy leaves breadcrumbs on ground
-	waypoint_info: &'static str,   // for debug only
+	noticed_enemy_id: Option<ID>,  // ✅ closest visible enemy, engaged after `reaction_time`
+	#[serde(skip)]
+	reaction_timer: Timer,
+	retreating: bool, // ✅ low on health: run for pickups instead of fighting
+	memory: Vec<EnemyMemory>,      // ✅ where enemies were seen or heard, fading over time
+	danger_spots: Vec<DangerSpot>, // ✅ where we died recently
+	was_spawned: bool,
+	goal: Option<vec3>,      // ✅ where we're going, following `path`
+	goal_kind: GoalKind,     // ✅ if there's a goal: why we're going there
+	goal_info: &'static str, // for debug only
+	goal_pickup: Option<ID>, // if the goal is a pickup: which one
+	#[serde(skip)]
+	path: Vec<NavStep>, // waypoints to reach the goal, next first, goal last
+	#[serde(skip)]
+	visited_pickups: Set<ID>, // goals we reached, but did not pick up (e.g. shield while full health)
+	waypoint: Option<vec3>,      // ✅ where we're going right now (in a straight line)
+	waypoint_info: &'static str, // for debug only
+	waypoint_jump: bool,         // ✅ jump to reach the waypoint (see `NavEdgeKind::Jump`)
 	waypoint_ttl: Timer,
 	aimpoint: Option<vec3>,
 	aimpoint_info: &'static str, // for debug only
+	aimpoint_is_enemy: bool,     // ✅ aim errors only apply to shooting, not walking
 	randomize_timer: Timer,
 
 	// Executive
 	target_orientation: Option<Orientation>,
 	delta_orientation: Option<Orientation>,
+	aim_error: Orientation, // ✅ added to the target orientation while aiming at an enemy
+	#[serde(skip)]
+	aim_error_timer: Timer,
+	#[serde(skip)]
+	strafe: Option<Button>, // ✅ held down while fighting (see `StrafePattern`)
+	#[serde(skip)]
+	strafe_timer: Timer,
 	victim_in_crosshairs: bool,
 	shoot_timer: Timer,
 	jump_timer: Timer,
 }
 
-pub(crate) async fn run_bot_gameloop(state: &mut Client) -> Result<()> {
-	let mut bot = Bot::new(&state.settings.bot);
-	loop {
-		Client::bot_pre_tick(state, &bot).await?;
+/// Where we think an enemy is.
+#[derive(Serialize, Clone, Debug)]
+struct EnemyMemory {
+	enemy_id: ID,
+	pos: vec3,
+	/// 1.0 when just seen, less when heard, fades to 0.0 (forgotten) over `MEMORY_SPAN`.
+	certainty: f32,
+	source: &'static str, // for debug only
+}
+
+/// Seconds to forget where an enemy was seen.
+const MEMORY_SPAN: f32 = 10.0;
+/// Certainty of hearing an enemy (as opposed to seeing them).
+const HEARD_CERTAINTY: f32 = 0.7;
+/// Distance (m) at which a sound of unit volume is still heard (louder sounds carry further).
+const HEARING_DIST: f32 = 10.0;
+/// Enemies walking closer than this (m) can be heard.
+const FOOTSTEP_DIST: f32 = 8.0;
+
+/// Where we died recently: avoid going there (see `set_goal`).
+#[derive(Serialize, Clone, Debug)]
+struct DangerSpot {
+	pos: vec3,
+	ttl: f32,
+}
 
-		bot.tick(state).await;
+/// Seconds to remember where we died.
+const DANGER_TTL: f32 = 60.0;
+/// Nodes this close (m) to a danger spot are avoided...
+const DANGER_RADIUS: f32 = 6.0;
+/// ...as if each meter towards them were this many extra meters.
+/// Crossing a danger spot (2 * DANGER_RADIUS) costs ~36m extra, however dense the navigation graph.
+const DANGER_PENALTY: f32 = 3.0;
+
+/// Why a bot is going somewhere. Ordered by priority (see `find_remembered_enemy_goal`).
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+enum GoalKind {
+	#[default]
+	Wander,
+	Pickup,
+	Enemy,
+}
 
-		if state.settings.debug.bot_overlay {
-			state.hud.set_text(HUDPos::Debug, &fmt_bot_overlay(&bot, state), 1.0);
-		}
+/// What a bot is up to, shared with teammates (see `BotCtx::teammates`)
+/// so that they don't all chase the same enemy or go for the same pickup.
+#[derive(Serialize, Clone, Debug)]
+pub(crate) struct BotIntent {
+	pub player_id: ID,
+	pub enemy_id: Option<ID>,
+	pub goal: Option<vec3>,
+}
+
+/// Teammates chasing the same enemy make it look this much (m) further away.
+const TEAM_SPREAD: f32 = 10.0;
+/// Don't go to a pickup (point) if a teammate is already heading within this distance (m).
+const COVER_DIST: f32 = 3.0;
+
+/// What a `Bot` sees and controls.
+/// Free of rendering and audio, so that bots can drive a windowed `Client` (`run_bot_gameloop`)
+/// as well as a player inside the server (`tick_bots`).
+pub(crate) struct BotCtx<'a> {
+	pub map: &'a Map,
+	pub entities: &'a Entities,
+	/// Copy of the bot's own player, to be written back by the caller
+	/// (the bot turns it to aim, other movement goes through `inputs`).
+	pub me: Player,
+	/// Fraction of full health (1.0 if unknown, e.g. for a client's bot: only the server knows).
+	pub health: f32,
+	/// Sounds played since the previous tick (see `Bot::perceive`).
+	pub sounds: &'a [SoundEffect],
+	/// What other bots are up to (previous tick). Includes enemies and the bot itself, those get filtered out.
+	pub teammates: &'a [BotIntent],
+	pub dt: f32,
+	/// Buttons held down by the bot. To be used for `move_player` and `WeaponState::tick`.
+	pub inputs: &'a mut Inputs,
+	/// Messages for the server (e.g. `ReadyToSpawn`).
+	pub out: &'a mut ClientMsgs,
+}
+
+impl<'a> BotCtx<'a> {
+	pub fn world(&self) -> (&Map, &Entities) {
+		(self.map, self.entities)
 	}
 }
 
 impl Bot {
-	pub fn new(settings: &BotSettings) -> Self {
+	/// A bot with given skills. Bots with the same settings and `seed` make the same decisions
+	/// (given the same circumstances).
+	pub fn new(settings: &BotSettings, seed: u64) -> Self {
 		Self {
 			settings: settings.clone(),
-			rng: Xoshiro256PlusPlus::seed_from_u64(1),
+			rng: Xoshiro256PlusPlus::seed_from_u64(seed),
 			respawn_timer: Timer::one_off(settings.respawn_time),
 			shoot_timer: Timer::unarmed(),
 			victim_in_crosshairs: false,
 			engaging_enemy_id: None,
+			noticed_enemy_id: None,
+			reaction_timer: Timer::unarmed(),
+			retreating: false,
+			memory: vec![],
+			danger_spots: vec![],
+			was_spawned: false,
+			goal: None,
+			goal_kind: default(),
+			goal_info: "",
+			goal_pickup: None,
+			path: vec![],
+			visited_pickups: default(),
 			target_orientation: None,
 			delta_orientation: None,
+			aim_error: default(),
+			aim_error_timer: Timer::unarmed(),
+			strafe: None,
+			strafe_timer: Timer::unarmed(),
 			waypoint: None,
 			waypoint_info: "",
+			waypoint_jump: false,
 			waypoint_ttl: Timer::unarmed(),
 			aimpoint: None,
 			aimpoint_info: "",
+			aimpoint_is_enemy: false,
 			jump_timer: Timer::one_off(1.0),
 			randomize_timer: Timer::one_off(1.0),
 		}
 	}
 
-	pub async fn tick(&mut self, state: &mut Client) {
-		self.respawn(state);
-		if state.local_player().spawned {
+	/// Where the bot is going, if anywhere (for the debug overlay).
+	pub fn waypoint(&self) -> Option<vec3> {
+		self.waypoint
+	}
+
+	/// Where the bot is looking at, if anywhere (for the debug overlay).
+	pub fn aimpoint(&self) -> Option<vec3> {
+		self.aimpoint
+	}
+
+	/// What we're up to, for teammates (see `BotCtx::teammates`).
+	pub fn intent(&self, player_id: ID) -> BotIntent {
+		BotIntent {
+			player_id,
+			enemy_id: self.engaging_enemy_id,
+			goal: self.goal,
+		}
+	}
+
+	pub fn tick(&mut self, ctx: &mut BotCtx) {
+		self.respawn(ctx);
+		self.forget(ctx.dt);
+		if ctx.me.spawned {
 			// cleanup to start from consistent state
-			self.clear_dead_waypoint(state); // ✅
-			self.clear_reached_waypoint(state); // ✅
-			self.clear_expired_waypoint(state); //
+			self.clear_dead_waypoint(ctx); // ✅
+			self.clear_reached_waypoint(ctx); // ✅
+			self.clear_expired_waypoint(ctx); //
 
 			// strategic: what to do
-			self.find_enemy(state); // ✅
-			self.find_waypoint(state); // ❌ TODO: searching
-			self.find_aimpoint(state);
-			self.find_random_waypoint(state);
+			self.perceive(ctx); // ✅
+			self.find_enemy(ctx); // ✅
+			self.check_retreat(ctx); // ✅
+			self.find_waypoint(ctx); // ✅
+			self.find_aimpoint(ctx);
+			self.find_random_waypoint(ctx);
 
 			//cannot see waypoint: move sideways, then forget if not reachable
 			//runs in circles
 
 			// executive: try to do it
-			self.execute_aim(state);
-			self.execute_shooting(state);
-			self.execute_moving(state);
-			self.random_jump(state);
+			self.execute_aim(ctx);
+			self.execute_shooting(ctx);
+			self.execute_moving(ctx);
+			self.execute_strafing(ctx);
+			self.execute_jumping(ctx);
+			self.random_jump(ctx);
 		}
 	}
 
+	/// Waypoints left to reach the goal (for the debug overlay).
+	pub fn path(&self) -> &[NavStep] {
+		&self.path
+	}
+
+	/// Where we think enemies are (for the debug overlay).
+	pub fn remembered_enemies(&self) -> impl Iterator<Item = vec3> + '_ {
+		self.memory.iter().map(|m| m.pos)
+	}
+
+	/// Where we died recently (for the debug overlay).
+	pub fn danger_spots(&self) -> impl Iterator<Item = vec3> + '_ {
+		self.danger_spots.iter().map(|d| d.pos)
+	}
+
 	// -----------------------------  cleanup
 
 	/// Remove waypoint if enemy has just died.
 	/// Hack to avoid chasing ghosts.
-	fn clear_dead_waypoint(&mut self, state: &Client) -> Option<()> {
-		if state.entities.players.get(&self.engaging_enemy_id?)?.spawned == false {
+	fn clear_dead_waypoint(&mut self, ctx: &BotCtx) -> Option<()> {
+		if !ctx.entities.players.get(&self.engaging_enemy_id?)?.spawned {
 			self.waypoint = None;
 			self.waypoint_info = "dead enemy";
 			self.engaging_enemy_id = None;
@@ -103,30 +258,41 @@ impl Bot {
 		Some(())
 	}
 
+	/// Memories and danger spots fade over time.
+	fn forget(&mut self, dt: f32) {
+		self.memory.iter_mut().for_each(|m| m.certainty -= dt / MEMORY_SPAN);
+		self.memory.retain(|m| m.certainty > 0.0);
+		self.danger_spots.iter_mut().for_each(|d| d.ttl -= dt);
+		self.danger_spots.retain(|d| d.ttl > 0.0);
+	}
+
 	/// Clear current waypoint if reached (approximately),
 	/// so that we can move on to the next.
-	fn clear_reached_waypoint(&mut self, state: &Client) -> Option<()> {
+	fn clear_reached_waypoint(&mut self, ctx: &BotCtx) -> Option<()> {
 		let waypoint = self.waypoint?;
-		let my_pos = state.local_player().skeleton.filtered_position;
-		// 💀 Reach waypoint ignoring vertical distance.
-		// High-up waypoints (left by jumping target enemy) may be hard to reach.
-		// BUG: cannot pickup high up shields!
+		let my_pos = ctx.me.skeleton.filtered_position;
+		// Reach waypoint from a bit below (e.g. jumping enemies, floating pickups),
+		// but not from a floor below (e.g. pickups high up, reached via the navigation graph).
 		const WAYPOINT_TOLERANCE: f32 = 1.0; // m
-		if my_pos.xz().distance_to(waypoint.xz()) < WAYPOINT_TOLERANCE {
+		const WAYPOINT_MAX_HEIGHT: f32 = 2.0; // m
+		let height = waypoint.y() - my_pos.y();
+		if my_pos.xz().distance_to(waypoint.xz()) < WAYPOINT_TOLERANCE && height > -WAYPOINT_TOLERANCE && height < WAYPOINT_MAX_HEIGHT {
 			self.waypoint = None;
 			self.waypoint_info = "reached";
 		}
 		Some(())
 	}
 
-	fn clear_expired_waypoint(&mut self, state: &Client) -> Option<()> {
+	fn clear_expired_waypoint(&mut self, ctx: &BotCtx) -> Option<()> {
 		const WAYPOINT_TTL: f32 = 3.0; //s
-		match can_see(&state.map, state.local_player(), self.waypoint?) {
+		match can_see(ctx.map, &ctx.me, self.waypoint?) {
 			true => self.waypoint_ttl = Timer::one_off(WAYPOINT_TTL),
 			false => {
-				if self.waypoint_ttl.tick(state.dt()).just_finished() {
+				if self.waypoint_ttl.tick(ctx.dt).just_finished() {
+					// probably stuck: give up on the goal too, find a new one.
 					self.waypoint = None;
 					self.waypoint_info = "expired";
+					self.clear_goal("expired");
 				}
 			}
 		}
@@ -137,52 +303,164 @@ impl Bot {
 	// -----------------------------  strategic
 
 	/// Find an enemy to engage, set enemy_id.
-	fn find_enemy(&mut self, state: &Client) {
+	fn find_enemy(&mut self, ctx: &BotCtx) {
 		// Pick closest, visible, spawned enemy.
-		let me = state.local_player();
-		self.engaging_enemy_id = state
+		let me = &ctx.me;
+		let closest = ctx
 			.entities
 			.players
 			.values()
-			.filter(|p| p.spawned && p.id != me.id && p.team != me.team) // 👈 spawned enemy
-			.filter(|p| can_see(&state.map, me, p.center())) // 👈 visible
-			.min_by_key(|p| me.center().distance_to(p.center()) as i32) // 👈 closest
+			.filter(|p| p.spawned && ctx.map.mode.are_enemies(me, p)) // 👈 spawned enemy
+			.filter(|p| can_see(ctx.map, me, p.center())) // 👈 visible
+			.min_by_key(|p| (me.center().distance_to(p.center()) + TEAM_SPREAD * self.num_teammates_chasing(ctx, p.id) as f32) as i32) // 👈 closest, not yet chased
 			.map(|p| p.id);
+
+		// Takes `reaction_time` to respond to a new enemy (keep fighting the old one meanwhile).
+		if closest != self.noticed_enemy_id {
+			self.noticed_enemy_id = closest;
+			self.reaction_timer.set(self.settings.reaction_time);
+		}
+		if closest.is_none() || self.reaction_timer.tick(ctx.dt).is_idle() {
+			self.engaging_enemy_id = closest;
+		}
+	}
+
+	/// Remember enemies we can see, hear walking nearby, or hear making noise (e.g. shooting, jumping).
+	fn perceive(&mut self, ctx: &BotCtx) {
+		let me = &ctx.me;
+		self.memory.retain(|m| ctx.entities.players.get(&m.enemy_id).is_some_and(|p| p.spawned));
+
+		let enemies = || ctx.entities.players.values().filter(|p| p.spawned && ctx.map.mode.are_enemies(me, p));
+		for enemy in enemies() {
+			if can_see(ctx.map, me, enemy.center()) {
+				self.remember(enemy.id, enemy.center(), 1.0, "seen");
+			} else if enemy.center().distance_to(me.center()) < FOOTSTEP_DIST && enemy.skeleton.velocity.xz().len() > 1.0 && enemy.on_ground(ctx.map) {
+				self.remember(enemy.id, enemy.center(), HEARD_CERTAINTY, "footsteps");
+			}
+		}
+
+		for sound in ctx.sounds {
+			let Some(spatial) = &sound.spatial else { continue };
+			let pos = spatial.location;
+			if pos.distance_to(me.center()) > HEARING_DIST * sound.volume.sqrt() {
+				continue;
+			}
+			// Who made that noise? E.g. gunfire: the shooter, not where the bullet hit.
+			const NOISE_SOURCE_DIST: f32 = 2.0; // m
+			if let Some(enemy) = enemies().find(|p| p.center().distance_to(pos) < NOISE_SOURCE_DIST) {
+				self.remember(enemy.id, pos, HEARD_CERTAINTY, "heard");
+			}
+		}
+	}
+
+	/// Newer information replaces older.
+	fn remember(&mut self, enemy_id: ID, pos: vec3, certainty: f32, source: &'static str) {
+		let memory = EnemyMemory { enemy_id, pos, certainty, source };
+		match self.memory.iter_mut().find(|m| m.enemy_id == enemy_id) {
+			Some(m) => *m = memory,
+			None => self.memory.push(memory),
+		}
+	}
+
+	/// Bots on our team (not ourselves).
+	fn teammates<'c>(&self, ctx: &'c BotCtx) -> impl Iterator<Item = &'c BotIntent> {
+		let me = &ctx.me;
+		ctx.teammates
+			.iter()
+			.filter(move |t| t.player_id != me.id && ctx.entities.players.get(&t.player_id).is_some_and(|p| !ctx.map.mode.are_enemies(me, p)))
+	}
+
+	fn num_teammates_chasing(&self, ctx: &BotCtx, enemy_id: ID) -> usize {
+		self.teammates(ctx).filter(|t| t.enemy_id == Some(enemy_id)).count()
+	}
+
+	/// Is a teammate already heading there?
+	fn is_covered(&self, ctx: &BotCtx, pos: vec3) -> bool {
+		self.teammates(ctx).any(|t| t.goal.is_some_and(|goal| goal.distance_to(pos) < COVER_DIST))
+	}
+
+	fn is_dangerous(&self, pos: vec3) -> bool {
+		self.danger_spots.iter().any(|d| d.pos.distance_to(pos) < DANGER_RADIUS)
+	}
+
+	/// Stop fighting when low on health (see `BotSettings::retreat_health`), run for a pickup instead.
+	fn check_retreat(&mut self, ctx: &BotCtx) {
+		let retreating = ctx.health < self.settings.retreat_health;
+		if retreating && !self.retreating {
+			self.waypoint = None;
+			self.waypoint_info = "retreat";
+			self.clear_goal("retreat");
+		}
+		self.retreating = retreating;
 	}
 
-	fn find_waypoint(&mut self, state: &Client) {
+	fn find_waypoint(&mut self, ctx: &BotCtx) {
 		// Remove current waypoint if reached
-		self.clear_reached_waypoint(state);
+		self.clear_reached_waypoint(ctx);
 
-		// Priority 1: set waypoint to where an enemy was last seen
+		// Priority 1: set waypoint to a visible enemy
 		// ! even if there's already a waypoint: keep chasing the moving player
-		self.find_enemy_waypoint(state);
+		if !self.retreating {
+			self.find_enemy_waypoint(ctx);
+		}
 
-		// Priority 2: set waypoint to a nearby pickup
-		if self.waypoint.is_none() {
-			self.find_pickup_waypoint(state);
+		// Priority 2: go to where an enemy was last seen or heard
+		if self.waypoint.is_none() && !self.retreating {
+			self.find_remembered_enemy_goal(ctx);
 		}
+
+		// Priority 3: go to a nearby pickup
+		if self.waypoint.is_none() && self.goal.is_none() {
+			self.find_pickup_goal(ctx);
+		}
+
+		self.follow_path(ctx);
 	}
 
-	fn find_random_waypoint(&mut self, state: &mut Client) {
-		if self.waypoint.is_none() {
-			self.randomize_waypoint(state);
+	fn find_random_waypoint(&mut self, ctx: &mut BotCtx) {
+		if self.waypoint.is_none() && self.goal.is_none() {
+			self.randomize_waypoint(ctx);
 		}
-		if self.randomize_timer.tick(state.dt()).just_finished() {
+		if ctx.map.nav_graph.is_empty() && self.randomize_timer.tick(ctx.dt).just_finished() {
 			self.randomize_timer.set(10.0);
-			self.randomize_waypoint(state);
+			self.randomize_waypoint(ctx);
 		}
 	}
 
-	fn randomize_waypoint(&mut self, state: &mut Client) -> Option<()> {
-		let me = state.local_player();
+	fn randomize_waypoint(&mut self, ctx: &mut BotCtx) {
+		match ctx.map.nav_graph.is_empty() {
+			true => self.randomize_waypoint_by_raycast(ctx),
+			false => self.randomize_goal(ctx),
+		};
+	}
+
+	/// Go to a random place on the map (not where we died recently),
+	/// or half of the time: cover a pickup point no teammate is heading to yet.
+	fn randomize_goal(&mut self, ctx: &mut BotCtx) -> Option<()> {
+		let pickup_points = ctx.map.pickup_points.iter().map(|pp| pp.pos).filter(|&pos| !self.is_covered(ctx, pos) && !self.is_dangerous(pos)).collect_vec();
+		if !pickup_points.is_empty() && self.rng.gen() {
+			let goal = pickup_points[self.rng.gen_range(0..pickup_points.len())];
+			self.set_goal(ctx, goal, GoalKind::Wander, "cover pickup point");
+			return Some(());
+		}
+
+		let nodes = &ctx.map.nav_graph.nodes;
+		let candidates = (0..10).map(|_| nodes[self.rng.gen_range(0..nodes.len())]).collect_vec();
+		let goal = candidates.into_iter().find(|&pos| !self.is_dangerous(pos))?;
+		self.set_goal(ctx, goal, GoalKind::Wander, "random");
+		Some(())
+	}
+
+	/// Without navigation graph: walk towards a far-away wall we can see.
+	fn randomize_waypoint_by_raycast(&mut self, ctx: &mut BotCtx) -> Option<()> {
+		let me = &ctx.me;
 
 		let mut candidates = SmallVec::<[_; 5]>::new();
 
 		for _i in 0..5 {
 			let dir = me.skeleton.orientation.with(|o| o.pitch = 0.0).with(|o| o.yaw += self.rng.gen_range(-PI..PI)).look_dir();
 			let ray = Ray32::new(me.camera().position, dir);
-			let t = state.map.intersect_t(&ray)?;
+			let t = ctx.map.intersect_t(&ray)?;
 			if t > 2.0 {
 				let dst = ray.at(t / 2.0 - 0.5);
 				candidates.push((t, dst));
@@ -194,82 +472,188 @@ impl Bot {
 		Some(())
 	}
 
-	fn random_jump(&mut self, state: &mut Client) {
-		if self.jump_timer.tick(state.dt()).just_finished() {
-			state.inputs_mut().press_button(Button::Jump);
-			state.inputs_mut().release_button(Button::Jump);
+	/// Jump towards the waypoint if that's the way to get there (up a ledge, across a gap).
+	/// Only when facing it, else we'd jump in place.
+	fn execute_jumping(&mut self, ctx: &mut BotCtx) -> Option<()> {
+		if self.waypoint_jump && self.waypoint.is_some() && self.delta_orientation?.yaw.abs() < 30.0 * DEG {
+			ctx.inputs.press_button(Button::Jump);
+			ctx.inputs.release_button(Button::Jump);
+		}
+		Some(())
+	}
+
+	fn random_jump(&mut self, ctx: &mut BotCtx) {
+		if self.jump_timer.tick(ctx.dt).just_finished() {
+			ctx.inputs.press_button(Button::Jump);
+			ctx.inputs.release_button(Button::Jump);
 			self.jump_timer.set(self.settings.jump_period * 2.0 * self.rng.gen::<f32>());
 		}
 	}
 
-	fn find_enemy_waypoint(&mut self, state: &Client) -> Option<()> {
-		// TODO: clear if target player killed
-		let dst = state.entities.players.get(&self.engaging_enemy_id?)?.center();
-		const PERSONAL_SPACE: f32 = 2.0;
-		if state.local_player().center().distance_to(dst) > PERSONAL_SPACE {
+	fn find_enemy_waypoint(&mut self, ctx: &BotCtx) -> Option<()> {
+		let dst = ctx.entities.players.get(&self.engaging_enemy_id?)?.center();
+		self.clear_goal("chasing enemy");
+		if ctx.me.center().distance_to(dst) > self.settings.preferred_range {
 			self.waypoint = Some(dst);
 			self.waypoint_info = "target player";
 		}
 		Some(())
 	}
 
-	fn find_pickup_waypoint(&mut self, state: &Client) -> Option<()> {
-		let me = state.local_player();
-		self.waypoint = state
+	/// No enemy in sight: go where we saw or heard one last (the most certain memory).
+	/// Overrides going for pickups or wandering, but not another enemy goal.
+	fn find_remembered_enemy_goal(&mut self, ctx: &BotCtx) -> Option<()> {
+		if self.goal.is_some() && self.goal_kind >= GoalKind::Enemy {
+			return None;
+		}
+		let memory = self.memory.iter().max_by(|a, b| a.certainty.total_cmp(&b.certainty))?.clone();
+		self.set_goal(ctx, memory.pos, GoalKind::Enemy, memory.source);
+		Some(())
+	}
+
+	/// Go to the nearest pickup: any we can see, or any nearby if we know the way.
+	/// How far we're willing to go depends on `pickup_greed` (any distance when retreating).
+	fn find_pickup_goal(&mut self, ctx: &BotCtx) -> Option<()> {
+		const MAX_PICKUP_RANGE: f32 = 40.0; // m
+		let range = match self.retreating {
+			true => MAX_PICKUP_RANGE,
+			false => self.settings.pickup_greed * MAX_PICKUP_RANGE,
+		};
+		let me = &ctx.me;
+		let have_nav_graph = !ctx.map.nav_graph.is_empty();
+		let (&id, prop) = ctx
 			.entities
 			.props
-			.values()
-			.filter(|prop| can_see(&state.map, me, prop.center()))
-			.min_by_key(|prop| me.center().distance_to(prop.center()) as i32)
-			.map(|prop| prop.center());
+			.iter()
+			.filter(|(id, _)| !self.visited_pickups.contains(id))
+			.filter(|(_, prop)| !self.is_covered(ctx, prop.center()) && !self.is_dangerous(prop.center()))
+			.filter(|(_, prop)| me.center().distance_to(prop.center()) < range)
+			.filter(|(_, prop)| have_nav_graph || can_see(ctx.map, me, prop.center()))
+			.min_by_key(|(_, prop)| me.center().distance_to(prop.center()) as i32)?;
+		self.set_goal(ctx, prop.center(), GoalKind::Pickup, "pickup");
+		self.goal_pickup = Some(id);
+		Some(())
+	}
+
+	/// Head for `goal`: via the navigation graph if possible (avoiding danger spots), else in a straight line.
+	fn set_goal(&mut self, ctx: &BotCtx, goal: vec3, kind: GoalKind, info: &'static str) {
+		let penalty = |pos: vec3| select(self.is_dangerous(pos), DANGER_PENALTY, 0.0);
+		let path = ctx.map.find_path_avoiding(ctx.me.position(), goal, penalty).unwrap_or_default();
+		// waypoints slightly above the floor, so that they are not obstructed by it.
+		self.path = path.into_iter().map(|step| NavStep { pos: step.pos + NAV_EYE_HEIGHT * vec3::EY, ..step }).collect();
+		self.path.push(NavStep { pos: goal, kind: NavEdgeKind::Walk });
+		self.goal = Some(goal);
+		self.goal_kind = kind;
+		self.goal_info = info;
+		self.goal_pickup = None;
+		self.waypoint = None;
+		self.waypoint_jump = false;
+	}
+
+	fn clear_goal(&mut self, info: &'static str) {
+		if self.goal.is_some() {
+			self.goal = None;
+			self.goal_info = info;
+			self.goal_pickup = None;
+			self.path.clear();
+			self.waypoint_jump = false;
+		}
+	}
+
+	/// Once the waypoint is reached, set it to the next one along the path.
+	fn follow_path(&mut self, ctx: &BotCtx) -> Option<()> {
+		self.goal?;
 		if self.waypoint.is_some() {
-			self.waypoint_info = "pickup";
+			return Some(()); // not there yet
+		}
+		// Skip waypoints that we've passed already (e.g. after falling off a ledge).
+		let my_pos = ctx.me.position();
+		while self.path.len() > 1 && self.path[1].pos.distance_to(my_pos) < self.path[0].pos.distance_to(my_pos) {
+			self.path.remove(0);
+		}
+		if self.path.is_empty() {
+			// looked there: enemies we remembered there are gone.
+			let goal = self.goal?;
+			self.memory.retain(|m| m.pos.distance_to(goal) > COVER_DIST);
+			// Don't come back if the pickup is still there (e.g. shield while at full health).
+			// Pickups re-spawn with a new ID.
+			if let Some(id) = self.goal_pickup {
+				self.visited_pickups.insert(id);
+			}
+			self.clear_goal("reached");
+			return Some(());
 		}
+		let step = self.path.remove(0);
+		self.waypoint = Some(step.pos);
+		self.waypoint_info = "path";
+		self.waypoint_jump = step.kind == NavEdgeKind::Jump;
 		Some(())
 	}
 
-	fn find_aimpoint(&mut self, state: &Client) {
+	fn find_aimpoint(&mut self, ctx: &BotCtx) {
 		self.aimpoint = None;
 		self.aimpoint_info = "";
-		self.find_enemy_aimpoint(state);
+		self.find_enemy_aimpoint(ctx);
+		self.aimpoint_is_enemy = self.aimpoint.is_some();
 		if self.aimpoint.is_none() {
-			self.find_waypoint_aimpoint(state);
+			self.find_waypoint_aimpoint(ctx);
 		}
 	}
 
-	fn find_enemy_aimpoint(&mut self, state: &Client) {
+	/// Aim at the enemy (unless running away), a bit ahead if they're moving (see `tracking_lead`).
+	fn find_enemy_aimpoint(&mut self, ctx: &BotCtx) {
+		if self.retreating {
+			return;
+		}
 		if let Some(id) = self.engaging_enemy_id {
-			if let Some(enemy) = state.entities.players.get(&id) {
+			if let Some(enemy) = ctx.entities.players.get(&id) {
 				let enemy_pos = enemy.center();
-				if can_see(&state.map, state.local_player(), enemy_pos) {
-					self.aimpoint = Some(enemy_pos);
+				if can_see(ctx.map, &ctx.me, enemy_pos) {
+					self.aimpoint = Some(enemy_pos + self.settings.tracking_lead * enemy.skeleton.velocity);
 					self.aimpoint_info = "enemy";
 				}
 			}
 		}
 	}
 
-	fn find_waypoint_aimpoint(&mut self, state: &Client) {
+	fn find_waypoint_aimpoint(&mut self, _ctx: &BotCtx) {
 		// 💀 there should always be an aim point, even if we can't see it
 		// TODO: if we can't see it: move sideways or jump
 		self.aimpoint = self.waypoint;
 	}
 
-	fn execute_aim(&mut self, state: &mut Client) -> Option<()> {
-		self.aim_at(state, self.aimpoint?);
+	fn execute_aim(&mut self, ctx: &mut BotCtx) -> Option<()> {
+		const AIM_ERROR_PERIOD: f32 = 0.5; // s
+		if self.aim_error_timer.tick(ctx.dt).is_idle() {
+			let error = self.settings.aim_error;
+			self.aim_error = Orientation {
+				yaw: error * self.rng.sample::<f32, _>(rand_distr::StandardNormal),
+				pitch: error * self.rng.sample::<f32, _>(rand_distr::StandardNormal),
+			};
+			self.aim_error_timer.set(AIM_ERROR_PERIOD);
+		}
+		let error = match self.aimpoint_is_enemy {
+			true => self.aim_error,
+			false => default(),
+		};
+		self.aim_at(ctx, self.aimpoint?, error);
 		Some(())
 	}
 
-	fn aim_at(&mut self, state: &mut Client, target_pos: vec3) {
+	/// Turn towards `target_pos`, off by `error`.
+	fn aim_at(&mut self, ctx: &mut BotCtx, target_pos: vec3, error: Orientation) {
 		self.target_orientation = None;
 		self.delta_orientation = None;
 
-		let me = state.local_player();
+		let me = &ctx.me;
 		let my_camera = me.camera();
 		let my_orientation = my_camera.orientation;
 
 		let target_dir = (target_pos - my_camera.position).safe_normalized();
-		let target_orientation = Orientation::from_look_dir(target_dir);
+		let target_orientation = Orientation::from_look_dir(target_dir).with(|o| {
+			o.yaw += error.yaw;
+			o.pitch += error.pitch;
+		});
 		self.target_orientation = Some(target_orientation);
 		let mut delta_yaw = target_orientation.yaw - my_orientation.yaw;
 		if delta_yaw > PI {
@@ -280,33 +664,36 @@ impl Bot {
 		let delta_pitch = target_orientation.pitch - my_orientation.pitch;
 		self.delta_orientation = Some(Orientation { yaw: delta_yaw, pitch: delta_pitch });
 
-		let dt = state.dt();
+		let dt = ctx.dt;
 		let aim_speed = self.settings.aim_speed;
 
-		let me = state.local_player_mut();
+		let me = &mut ctx.me;
 		//                                     👇 TODO: proper aiming function with inertia
 		me.skeleton.orientation.pitch += dt * (4.0 * aim_speed * delta_pitch).clamp(-aim_speed, aim_speed);
 		me.skeleton.orientation.yaw += dt * (4.0 * aim_speed * delta_yaw).clamp(-aim_speed, aim_speed);
 	}
 
-	fn execute_shooting(&mut self, state: &mut Client) {
-		self.victim_in_crosshairs = self.has_victim_in_crosshairs(state);
+	fn execute_shooting(&mut self, ctx: &mut BotCtx) {
+		self.victim_in_crosshairs = self.has_victim_in_crosshairs(ctx);
 
 		if self.victim_in_crosshairs {
 			self.shoot_timer.set_if_idle(self.rng.gen_range(0.0..2.0 * self.settings.response_time));
 		}
 
-		if self.shoot_timer.tick(state.dt()).just_finished() {
-			state.inputs_mut().press_button(Button::Mouse1)
+		if self.shoot_timer.tick(ctx.dt).just_finished() {
+			ctx.inputs.press_button(Button::Mouse1)
+		} else {
+			// release, else the (semi-automatic) weapon does not fire again.
+			ctx.inputs.release_button(Button::Mouse1)
 		}
 	}
 
-	fn execute_moving(&mut self, state: &mut Client) -> Option<()> {
+	fn execute_moving(&mut self, ctx: &mut BotCtx) -> Option<()> {
 		if let Some(aimpoint) = self.aimpoint {
 			if self.delta_orientation?.yaw < 30.0 * DEG {
-				state.inputs_mut().press_button(Button::Forward);
+				ctx.inputs.press_button(Button::Forward);
 			} else {
-				state.inputs_mut().release_button(Button::Forward);
+				ctx.inputs.release_button(Button::Forward);
 			}
 
 			// move sideways if waypoint risks disappearing behind a corner.
@@ -321,51 +708,86 @@ impl Bot {
 			//   left       right
 			//   eye        eye
 			{
-				let cam_pos = state.local_player().camera().position;
-				let orientation = state.local_player().orientation();
+				let cam_pos = ctx.me.camera().position;
+				let orientation = ctx.me.orientation();
 				{
 					let left_eye = cam_pos - 0.5 * orientation.look_right();
-					if is_obstructed(&state.map, left_eye, aimpoint) {
+					if is_obstructed(ctx.map, left_eye, aimpoint) {
 						// TODO: f32 left/right neurons, added to
-						state.inputs_mut().press_button(Button::Right);
-						state.inputs_mut().release_button(Button::Forward);
+						ctx.inputs.press_button(Button::Right);
+						ctx.inputs.release_button(Button::Forward);
 					} else {
-						state.inputs_mut().release_button(Button::Right) // TODO: always release at beginning of tick?
+						ctx.inputs.release_button(Button::Right) // TODO: always release at beginning of tick?
 					}
 				}
 
 				{
 					let right_eye = cam_pos + 0.5 * orientation.look_right();
-					if is_obstructed(&state.map, right_eye, aimpoint) {
-						state.inputs_mut().press_button(Button::Left);
-						state.inputs_mut().release_button(Button::Forward);
+					if is_obstructed(ctx.map, right_eye, aimpoint) {
+						ctx.inputs.press_button(Button::Left);
+						ctx.inputs.release_button(Button::Forward);
 					} else {
-						state.inputs_mut().release_button(Button::Left) // TODO: always release at beginning of tick?
+						ctx.inputs.release_button(Button::Left) // TODO: always release at beginning of tick?
 					}
 				}
 			}
 		} else {
-			state.inputs_mut().release_button(Button::Forward);
+			ctx.inputs.release_button(Button::Forward);
 		}
 
 		Some(())
 	}
 
-	fn has_victim_in_crosshairs(&mut self, state: &Client) -> bool {
-		let ray = state.local_player().line_of_sight();
-		match state.intersect_except_player(state.local_player_id, &ray) {
-			Some((_, Some(id))) => match state.entities.players.get(&id) {
-				Some(player) => player.team != state.local_player().team,
+	/// Dodge sideways while fighting (see `StrafePattern`).
+	/// Runs after `execute_moving`, which releases Left and Right when not needed to get around corners.
+	fn execute_strafing(&mut self, ctx: &mut BotCtx) {
+		let fighting = self.engaging_enemy_id.is_some() && !self.retreating;
+		if !fighting || self.settings.strafe == StrafePattern::None {
+			if let Some(button) = self.strafe.take() {
+				ctx.inputs.release_button(button);
+			}
+			return;
+		}
+
+		if self.strafe_timer.tick(ctx.dt).is_idle() {
+			let period = self.settings.strafe_period;
+			let (button, duration) = match self.settings.strafe {
+				StrafePattern::Sideways => (select(self.strafe == Some(Button::Left), Button::Right, Button::Left), period),
+				_ => (select(self.rng.gen(), Button::Left, Button::Right), self.rng.gen_range(0.3..1.7) * period),
+			};
+			if let Some(old) = self.strafe.replace(button) {
+				ctx.inputs.release_button(old);
+			}
+			self.strafe_timer.set(duration);
+		}
+		if let Some(button) = self.strafe {
+			ctx.inputs.press_button(button);
+		}
+	}
+
+	fn has_victim_in_crosshairs(&mut self, ctx: &BotCtx) -> bool {
+		let ray = ctx.me.line_of_sight();
+		match _intersect_except_player(ctx.world(), ctx.me.id, &ray) {
+			Some((_, Some(id))) => match ctx.entities.players.get(&id) {
+				Some(player) => ctx.map.mode.are_enemies(&ctx.me, player),
 				None => false,
 			},
 			_ => return false,
 		}
 	}
 
-	fn respawn(&mut self, state: &mut Client) {
-		if !state.local_player().spawned {
-			if self.respawn_timer.ensure_running().tick(state.dt()).just_finished() {
-				state.pending_diffs.push(ReadyToSpawn)
+	fn respawn(&mut self, ctx: &mut BotCtx) {
+		if self.was_spawned && !ctx.me.spawned {
+			// just died
+			self.danger_spots.push(DangerSpot {
+				pos: ctx.me.position(),
+				ttl: DANGER_TTL,
+			});
+		}
+		self.was_spawned = ctx.me.spawned;
+		if !ctx.me.spawned {
+			if self.respawn_timer.ensure_running().tick(ctx.dt).just_finished() {
+				ctx.out.push(ReadyToSpawn)
 			}
 		}
 	}
@@ -377,43 +799,52 @@ fn can_see(map: &Map, player: &Player, target: vec3) -> bool {
 	camera.orientation.look_dir().dot(to_target) > 0.0 && !is_obstructed(map, camera.position, target)
 }
 
-pub(crate) fn draw_bot_overlay(sg: &mut SceneGraph, state: &Client, bot: &Bot) {
-	draw_waypoint(sg, state, bot);
-	draw_aimpoint(sg, state, bot);
-}
+#[cfg(test)]
+mod test {
+	use super::*;
 
-fn draw_waypoint(sg: &mut SceneGraph, state: &Client, bot: &Bot) -> Option<()> {
-	draw_prop(
-		sg,
-		state,
-		&Prop {
-			mesh: handle("shield"), // TODO
-			texture: handle("#FF3333"),
-			aabb_size: vec3::ONES, // <<<<<
-			on_collide: None,
-			transform: Transform {
-				translation: bot.waypoint?,
-				scale: 1.2,
-			},
-			pickup_point_id: None,
-		},
-	)
-}
+	/// Tick a bot once on `map`, with `me` and `others` as the only players.
+	fn tick(bot: &mut Bot, map: &Map, me: &Player, others: &[&Player], sounds: &[SoundEffect]) {
+		let mut entities = Entities::default();
+		for p in others.iter().copied().chain([me]) {
+			entities.players.insert(p.id, p.clone());
+		}
+		let mut ctx = BotCtx {
+			map,
+			entities: &entities,
+			me: me.clone(),
+			health: 1.0,
+			sounds,
+			teammates: &[],
+			dt: 0.01,
+			inputs: &mut default(),
+			out: &mut default(),
+		};
+		bot.tick(&mut ctx);
+	}
 
-fn draw_aimpoint(sg: &mut SceneGraph, state: &Client, bot: &Bot) -> Option<()> {
-	draw_prop(
-		sg,
-		state,
-		&Prop {
-			mesh: handle("shield"), // TODO
-			texture: handle("#3333FF"),
-			aabb_size: vec3::ONES, // <<<<<
-			on_collide: None,
-			transform: Transform {
-				translation: bot.aimpoint?,
-				scale: 1.3,
-			},
-			pickup_point_id: None,
-		},
-	)
+	#[test]
+	fn hear_and_remember() {
+		init_test_assets_dir();
+		let map = Map::load("castle").unwrap();
+		let spawn_point = &map.spawn_points[0];
+		let mut me = Player::new(ID::new(), spawn_point.position, spawn_point.orientation(), "me".into(), 1, Team::Red);
+		me.spawned = true;
+		// right behind me: can't see them.
+		let behind = me.position() - 3.0 * me.orientation().look_dir().with(|d| d[Y] = 0.0);
+		let mut enemy = Player::new(ID::new(), behind, default(), "enemy".into(), 1, Team::Blue);
+		enemy.spawned = true;
+		let mut bot = Bot::new(&BotSettings::default(), 1);
+
+		let sound = |volume| SoundEffect::spatial(handle("bang1"), enemy.center(), volume);
+		tick(&mut bot, &map, &me, &[&enemy], &[sound(0.001)]);
+		assert_eq!(bot.remembered_enemies().count(), 0, "too quiet to hear");
+		tick(&mut bot, &map, &me, &[&enemy], &[sound(30.0)]);
+		assert_eq!(bot.remembered_enemies().collect_vec(), vec![enemy.center()]);
+
+		// died: avoid this spot.
+		me.spawned = false;
+		tick(&mut bot, &map, &me, &[&enemy], &[]);
+		assert_eq!(bot.danger_spots().collect_vec(), vec![me.position()]);
+	}
 }
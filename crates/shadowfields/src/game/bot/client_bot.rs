@@ -0,0 +1,35 @@
+use crate::prelude::*;
+
+/// Let a `Bot` play as the local player of a windowed `Client`
+/// (as opposed to a bot hosted by the server, see `tick_bots`).
+pub(crate) async fn run_bot_gameloop(state: &mut Client) -> Result<()> {
+	let mut bot = Bot::new(&state.settings.bot, rand::random());
+	loop {
+		Client::bot_pre_tick(state, &bot).await?;
+
+		control_local_player(state, &mut bot);
+
+		if state.settings.debug.bot_overlay {
+			state.hud.set_text(HUDPos::Debug, fmt_bot_overlay(&bot, state), 1.0);
+		}
+	}
+}
+
+fn control_local_player(state: &mut Client, bot: &mut Bot) {
+	let dt = state.dt();
+	let sounds = mem::take(&mut state.heard_sounds);
+	let mut ctx = BotCtx {
+		map: &state.map,
+		entities: &state.entities,
+		me: state.entities.players[&state.local_player_id].clone(),
+		health: 1.0,
+		sounds: &sounds,
+		teammates: &[], // other bots run in other clients
+		dt,
+		inputs: &mut state._win.inputs,
+		out: &mut state.pending_diffs,
+	};
+	bot.tick(&mut ctx);
+	let me = ctx.me;
+	*state.local_player_mut() = me;
+}
@@ -14,3 +14,85 @@ pub(crate) fn fmt_bot_overlay(bot: &Bot, state: &Client) -> String {
 	//let s =ron::Serializer::new(&mut buf, Some(PrettyConfig::new())).unwrap();
 	//String::from_utf8(buf).unwrap()
 }
+
+pub(crate) fn draw_bot_overlay(sg: &mut SceneGraph, state: &Client, bot: &Bot) {
+	draw_waypoint(sg, state, bot);
+	draw_aimpoint(sg, state, bot);
+	draw_path(sg, state, bot);
+	draw_markers(sg, state, bot.remembered_enemies(), handle("#FF33FF"));
+	draw_markers(sg, state, bot.danger_spots(), handle("#000000"));
+}
+
+/// Remembered enemies, danger spots, ...
+fn draw_markers(sg: &mut SceneGraph, state: &Client, positions: impl Iterator<Item = vec3>, texture: Handle) {
+	for pos in positions {
+		draw_prop(
+			sg,
+			state,
+			&Prop {
+				mesh: handle("shield"), // TODO
+				texture,
+				aabb_size: vec3::ONES, // <<<<<
+				on_collide: None,
+				transform: Transform { translation: pos, scale: 0.8 },
+				pickup_point_id: None,
+			},
+		);
+	}
+}
+
+fn draw_path(sg: &mut SceneGraph, state: &Client, bot: &Bot) {
+	for step in bot.path() {
+		draw_prop(
+			sg,
+			state,
+			&Prop {
+				mesh: handle("shield"), // TODO
+				texture: handle("#FFAA33"),
+				aabb_size: vec3::ONES, // <<<<<
+				on_collide: None,
+				transform: Transform {
+					translation: step.pos,
+					scale: 0.4,
+				},
+				pickup_point_id: None,
+			},
+		);
+	}
+}
+
+fn draw_waypoint(sg: &mut SceneGraph, state: &Client, bot: &Bot) -> Option<()> {
+	draw_prop(
+		sg,
+		state,
+		&Prop {
+			mesh: handle("shield"), // TODO
+			texture: handle("#FF3333"),
+			aabb_size: vec3::ONES, // <<<<<
+			on_collide: None,
+			transform: Transform {
+				translation: bot.waypoint()?,
+				scale: 1.2,
+			},
+			pickup_point_id: None,
+		},
+	)
+}
+
+fn draw_aimpoint(sg: &mut SceneGraph, state: &Client, bot: &Bot) -> Option<()> {
+	draw_prop(
+		sg,
+		state,
+		&Prop {
+			mesh: handle("shield"), // TODO
+			texture: handle("#3333FF"),
+			aabb_size: vec3::ONES, // <<<<<
+			on_collide: None,
+			transform: Transform {
+				translation: bot.aimpoint()?,
+				scale: 1.3,
+			},
+			pickup_point_id: None,
+		},
+	)
+}
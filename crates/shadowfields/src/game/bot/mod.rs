@@ -1,8 +1,9 @@
-
+mod bot_settings;
 mod bot_system;
+mod client_bot;
 mod debug;
-mod bot_settings;
 
+pub use bot_settings::*;
 pub use bot_system::*;
+pub use client_bot::*;
 pub use debug::*;
-pub use bot_settings::*;
\ No newline at end of file
@@ -17,10 +17,18 @@ pub(crate) struct Client {
 	pub debug: DebugOpts,
 	pub hud: HUD,
 	pub zones: Vec<Object>,
+	pub mover_meshes: Vec<MoverMesh>,
 	pub effects: Vec<Effect>,
 
-	pub conn: NetPipe<ClientMsg, ServerMsg>,
+	/// None when playing back a demo.
+	pub conn: Option<NetPipe<ClientMsg, ServerMsg>>,
 	pub pending_diffs: Vec<ClientMsg>,
+	/// Sounds received from the server, for the bot to hear (see `run_bot_gameloop`).
+	pub heard_sounds: Vec<SoundEffect>,
+	/// Recording of the game, if `settings.demo.record` is set (see `record_demo`).
+	pub demo: Option<DemoRecorder>,
+	/// Last recorded position of the local player (to avoid recording it while standing still).
+	pub demo_frame: Option<Frame>,
 
 	pub map: Map,
 	pub entities: Entities,
@@ -33,12 +41,19 @@ pub(crate) struct Client {
 	pub mouse_filter: MouseFilter,
 
 	pub jump_sound_cooldown: Timer,
+	pub jump_pad_cooldown: Timer,
+
+	pub killcam: KillCam,
 
 	filtered_dt: f32,
 }
 
 impl Client {
 	pub async fn gameloop(settings: Settings, win: WinitWindow) -> Result<()> {
+		if let Some(file) = settings.demo.play.clone() {
+			return run_demo_gameloop(settings, win, Path::new(&file)).await;
+		}
+
 		let mut client = Self::connect(settings, win).await?;
 
 		match client.settings.bot.enabled {
@@ -54,15 +69,35 @@ impl Client {
 		let join_req = JoinRequest {
 			name: settings.player.name.clone(),
 			avatar_id: settings.player.avatar,
-			team: settings.player.team.parse()?,
+			team: parse_team_choice(&settings.player.team)?,
+			identity_key: identity_key(&settings.player)?,
 		};
 		let (conn, acc) = with_loading_screen(&mut win, move || connect(server.as_str(), join_req)).await?;
 
+		let demo = match &settings.demo.record {
+			Some(file) => Some(DemoRecorder::create(Path::new(file), Some(acc.player_id), acc.map_switch.clone())?),
+			None => None,
+		};
+
+		let mut client = Self::new(settings, win, Some(conn), acc.player_id, acc.map_switch).await?;
+		client.demo = demo;
+
+		if client.settings.player.advantage {
+			client.pending_diffs.push(ClientMsg::Command(format!("adv {}", client.local_player().name)))
+		}
+
+		Ok(client)
+	}
+
+	/// Load the map and resources for a game that's connected to `conn` (None: playing a demo).
+	pub(crate) async fn new(settings: Settings, win: WinitWindow, conn: Option<NetPipe<ClientMsg, ServerMsg>>, local_player_id: ID, map_switch: MapSwitch) -> Result<Client> {
+		let mut win = win;
+
 		let graphics = settings.graphics.clone();
-		let (res, map, zones, entities) = with_loading_screen(&mut win, move || -> Result<_> {
+		let (res, map, zones, mover_meshes, entities) = with_loading_screen(&mut win, move || -> Result<_> {
 			let mut res = Resources::new()?;
-			let (map, zones, entities) = load_state(&mut res, acc.map_switch, &graphics)?;
-			Ok((res, map, zones, entities))
+			let (map, zones, mover_meshes, entities) = load_state(&mut res, map_switch, &graphics)?;
+			Ok((res, map, zones, mover_meshes, entities))
 		})
 		.await?;
 
@@ -73,32 +108,32 @@ impl Client {
 
 		let mouse_filter = MouseFilter::from_settings(&settings)?;
 
-		let mut client = Client {
+		Ok(Client {
 			settings,
 			_win: win,
 			hud,
 			conn,
 			res,
-			local_player_id: acc.player_id,
+			local_player_id,
 			map,
 			entities,
 			effects: default(),
 			debug: default(),
 			pending_diffs: default(),
+			heard_sounds: default(),
+			demo: None,
+			demo_frame: None,
 			zones,
+			mover_meshes,
 			advantage: false,
 			weapon_state: default(),
 			sound_pack,
 			mouse_filter,
 			filtered_dt: 0.0,
 			jump_sound_cooldown: Timer::one_off_ready(0.25), // ensures we don't play footstep sound too often.
-		};
-
-		if client.settings.player.advantage {
-			client.pending_diffs.push(ClientMsg::Command(format!("adv {}", client.local_player().name)))
-		}
-
-		Ok(client)
+			jump_pad_cooldown: Timer::one_off_ready(0.5), // don't re-launch while still inside the jump pad's sensor.
+			killcam: default(),
+		})
 	}
 
 	async fn run_human_gameloop(client: &mut Client) -> Result<()> {
@@ -129,9 +164,11 @@ impl Client {
 			// but this only rarely matters, e.g., in case of a collision with the local player.
 			apply_all_server_messages(client)?;
 			extrapolate_other_players(client); // 👈 TODO: this is very inaccurate. should take into account time since last message
+			mover_system(client);
 
 			// Now update the local player's position, hud, etc.
 			control_player_movement(client);
+			record_local_frame(client);
 
 			smooth_players_movement(&mut client.entities.players);
 
@@ -141,6 +178,7 @@ impl Client {
 			animate_footsteps(client);
 			tick_effects(client);
 			client.hud.tick(client.dt());
+			killcam_system(client).await?;
 
 			client.apply_and_send_diffs()?;
 		}
@@ -156,9 +194,11 @@ impl Client {
 
 		apply_all_server_messages(client)?;
 		extrapolate_other_players(client);
+		mover_system(client);
 
 		control_respawn(client);
 		control_player_movement(client);
+		record_local_frame(client);
 		control_shooting(client);
 		smooth_players_movement(&mut client.entities.players);
 
@@ -189,15 +229,13 @@ impl Client {
 		&self._win.inputs
 	}
 
-	pub fn inputs_mut(&mut self) -> &mut Inputs {
-		&mut self._win.inputs
-	}
-
 	fn apply_and_send_diffs(&mut self) -> Result<()> {
 		let diffs = mem::take(&mut self.pending_diffs);
 		self.apply_self_msgs(&diffs);
-		for diff in diffs {
-			self.conn.send(diff)?;
+		if let Some(conn) = &mut self.conn {
+			for diff in diffs {
+				conn.send(diff)?;
+			}
 		}
 		Ok(())
 	}
@@ -218,6 +256,7 @@ impl Client {
 				AddEffect(effect) => handle_add_effect(self, effect.clone()),
 				PlaySound(sound) => play_sound(self, sound),
 				HitPlayer { .. } => (/* handled by server*/),
+				ShotFired => (/* handled by server*/),
 				ReadyToSpawn => (/*handled by server*/),
 				Command(_) => (/*handled by server*/),
 			}
@@ -233,12 +272,4 @@ impl Client {
 	pub fn local_player_mut(&mut self) -> &mut Player {
 		self.entities.players.get_mut(&self.local_player_id).unwrap()
 	}
-
-	pub fn world(&self) -> (&Map, &Entities) {
-		(&self.map, &self.entities)
-	}
-
-	pub fn intersect_except_player(&self, player_id: ID, ray: &Ray64) -> Option<(f64, Option<ID>)> {
-		_intersect_except_player((&self.map, &self.entities), player_id, ray)
-	}
 }
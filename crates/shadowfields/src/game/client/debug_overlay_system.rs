@@ -18,6 +18,9 @@ pub(crate) fn draw_debug_overlay(sg: &mut SceneGraph, state: &mut Client) {
 	if state.debug.bb_overlay {
 		draw_bb_overlay(sg, &state.entities);
 	}
+	if state.debug.jump_pad_overlay {
+		draw_jump_pad_overlay(sg, state);
+	}
 }
 
 fn fmt_ecs_overlay(state: &Client) -> String {
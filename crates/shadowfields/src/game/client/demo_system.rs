@@ -0,0 +1,248 @@
+//!
+//! Demo recording (`--record-demo`) and playback (`--play-demo`). See `Demo` for the file format.
+//!
+//! Playback controls:
+//!   * space: pause / resume
+//!   * mouse wheel: seek 5 seconds backward / forward
+//!   * right mouse button: free camera (fly with the movement keys) / follow the recorded player
+//!   * console: `pause`, `speed <factor>`, `seek <seconds>`
+//!
+
+use super::internal::*;
+
+/// Entity ID of the free camera. Chosen so that it does not clash with IDs in the recording.
+const CAMERA_ID: ID = ID::from_int(usize::MAX);
+
+/// Free camera speed (m/s).
+const CAMERA_SPEED: f32 = 20.0;
+
+/// Mouse wheel seek step (s).
+const SEEK_STEP: f32 = 5.0;
+
+/// Record an event if recording a demo (see `DemoOpts`). Stops recording on error.
+pub(crate) fn record_demo(state: &mut Client, event: impl FnOnce() -> DemoEvent) {
+	if let Some(demo) = &mut state.demo {
+		if let Err(e) = demo.record(event()) {
+			LOG.write(format!("{e:#}: stopped recording"));
+			state.demo = None;
+		}
+	}
+}
+
+/// Record the local player's movement, which the server does not send back to us.
+/// Nothing is recorded while the player stands still.
+pub(crate) fn record_local_frame(state: &mut Client) {
+	if state.demo.is_none() {
+		return;
+	}
+	let frame = state.local_player().skeleton.target_frame();
+	if state.demo_frame.as_ref() != Some(&frame) {
+		state.demo_frame = Some(frame.clone());
+		record_demo(state, || DemoEvent::LocalFrame(frame));
+	}
+}
+
+/// Playback position and controls.
+struct Playback {
+	demo: Demo,
+	/// Index of the next record to apply.
+	next: usize,
+	/// Seconds since the start of the recording.
+	time: f32,
+	speed: f32,
+	paused: bool,
+	/// The player who recorded the demo. None: recorded by the server.
+	recorded_id: Option<ID>,
+	/// Flying spectator, not part of the recording.
+	camera: Player,
+	free_camera: bool,
+}
+
+/// Play back a demo file instead of connecting to a server.
+pub(crate) async fn run_demo_gameloop(settings: Settings, win: WinitWindow, file: &Path) -> Result<()> {
+	let demo = Demo::load(file)?;
+	LOG.write(format!("playing {file:?} ({:.0}s)", demo.duration()));
+
+	let recorded_id = demo.header.player_id;
+	let mut client = Client::new(settings, win, None, recorded_id.unwrap_or(CAMERA_ID), demo.header.map_switch.clone()).await?;
+	client.settings.bot.enabled = false;
+	client.settings.player.advantage = false;
+
+	let spawn_point = client.map.spawn_points.first().cloned().unwrap_or_default();
+	let camera = Player::new(CAMERA_ID, spawn_point.position, spawn_point.orientation(), "camera".into(), 0, Team::Red);
+
+	let mut playback = Playback {
+		demo,
+		next: 0,
+		time: 0.0,
+		speed: 1.0,
+		paused: false,
+		recorded_id,
+		camera,
+		free_camera: recorded_id.is_none(),
+	};
+	place_camera(&mut client, &mut playback);
+
+	loop {
+		let mut sg = SceneGraph::new(client._win.viewport_size);
+		draw_gamestate(&mut sg, &client);
+		draw_debug_overlay(&mut sg, &mut client);
+		client.present_and_wait(sg).await;
+
+		control_playback(&mut client, &mut playback).await?;
+		playback.time = f32::min(playback.time + client.dt(), playback.demo.duration());
+		apply_records(&mut client, &mut playback, false)?;
+
+		extrapolate_other_players(&mut client);
+		mover_system(&mut client);
+		control_camera(&mut client, &mut playback);
+		smooth_players_movement(&mut client.entities.players);
+		animate_footsteps(&mut client);
+		tick_effects(&mut client);
+		client.hud.tick(client.dt());
+		client.hud.set_text(HUDPos::TopRight, fmt_playback(&playback), 1.0);
+
+		client.pending_diffs.clear(); // nobody to send them to
+	}
+}
+
+/// Keyboard, mouse and console controls (see module docs).
+async fn control_playback(state: &mut Client, playback: &mut Playback) -> Result<()> {
+	if state.inputs().just_pressed(Button::Jump) {
+		playback.paused = !playback.paused;
+	}
+	if state.inputs().just_pressed(Button::Mouse2) {
+		playback.free_camera = !playback.free_camera;
+		if let Some(player) = playback.recorded_id.and_then(|id| state.entities.players.get(&id)) {
+			playback.camera.skeleton.set_frame(player.skeleton.target_frame());
+		}
+		place_camera(state, playback);
+	}
+	if state.inputs().just_pressed(Button::MouseWheelUp) {
+		seek(state, playback, playback.time + SEEK_STEP)?;
+	}
+	if state.inputs().just_pressed(Button::MouseWheelDown) {
+		seek(state, playback, playback.time - SEEK_STEP)?;
+	}
+	if state.inputs().just_pressed(Button::Console) {
+		if let Some(cmd) = enter_text_input(state).await {
+			match exec_playback_command(state, playback, &cmd) {
+				Ok(()) => LOG.write("OK"),
+				Err(e) => LOG.write(format!("{e:#}")),
+			}
+		}
+	}
+
+	// Scales `state.dt()`, so that movers, effects, etc. run at playback speed.
+	state.settings.debug.time_passage = select(playback.paused, 0.0, playback.speed);
+	Ok(())
+}
+
+fn exec_playback_command(state: &mut Client, playback: &mut Playback, cmd: &str) -> Result<()> {
+	match &cmd.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+		["pause"] => playback.paused = !playback.paused,
+		["speed", v] => playback.speed = v.parse::<f32>()?.clamp(0.0, 16.0),
+		["seek", v] => seek(state, playback, v.parse()?)?,
+		_ => exec_command(state, cmd)?,
+	}
+	Ok(())
+}
+
+/// Jump to a time (seconds since the start of the recording).
+/// Seeking backwards replays the demo from the start.
+fn seek(state: &mut Client, playback: &mut Playback, time: f32) -> Result<()> {
+	let time = time.clamp(0.0, playback.demo.duration());
+	if time < playback.time {
+		switch_map(state, playback.demo.header.map_switch.clone())?;
+		playback.next = 0;
+	}
+	playback.time = time;
+	apply_records(state, playback, true)
+}
+
+/// Apply all records up to the current playback time.
+/// `seeking`: skip sounds and effects, we're fast-forwarding.
+fn apply_records(state: &mut Client, playback: &mut Playback, seeking: bool) -> Result<()> {
+	while let Some(record) = playback.demo.records.get(playback.next).filter(|r| r.time <= playback.time).cloned() {
+		playback.next += 1;
+		match record.event {
+			DemoEvent::Msg(msg) => apply_demo_msg(state, msg, seeking)?,
+			DemoEvent::LocalFrame(frame) => {
+				if let Some(player) = playback.recorded_id.and_then(|id| state.entities.players.get_mut(&id)) {
+					player.set_frame(frame)
+				}
+			}
+		}
+		// a map switch replaces all entities, including the camera.
+		if !state.entities.players.contains_key(&state.local_player_id) {
+			place_camera(state, playback);
+		}
+	}
+	Ok(())
+}
+
+fn apply_demo_msg(state: &mut Client, msg: ServerMsg, seeking: bool) -> Result<()> {
+	use ServerMsg::*;
+	match msg {
+		// already part of the recorded local player movement
+		ApplyImpulse(_) | ForceMovePlayer(_) => Ok(()),
+		PlaySound(_) | AddEffect(_) if seeking => Ok(()),
+		msg => apply_server_msg(state, msg),
+	}
+}
+
+/// Look through the eyes of the recorded player, or through the free camera.
+/// Falls back to the free camera if the recorded player is not in the game.
+fn place_camera(state: &mut Client, playback: &mut Playback) {
+	match playback.recorded_id.filter(|id| !playback.free_camera && state.entities.players.contains_key(id)) {
+		Some(id) => {
+			state.entities.players.remove(&CAMERA_ID);
+			state.local_player_id = id;
+		}
+		None => {
+			state.entities.players.insert(CAMERA_ID, playback.camera.clone());
+			state.local_player_id = CAMERA_ID;
+		}
+	}
+}
+
+/// Fly the free camera through walls, at real-time speed (even when paused).
+fn control_camera(state: &mut Client, playback: &mut Playback) {
+	if state.local_player_id != CAMERA_ID {
+		return;
+	}
+	let mut orientation = playback.camera.orientation();
+	mouse_look(state, &mut orientation);
+
+	let inputs = state.inputs();
+	let mut dir = vec3::ZERO;
+	if inputs.is_down(Button::Forward) {
+		dir += orientation.look_dir();
+	}
+	if inputs.is_down(Button::Backward) {
+		dir -= orientation.look_dir();
+	}
+	if inputs.is_down(Button::Right) {
+		dir += orientation.look_right();
+	}
+	if inputs.is_down(Button::Left) {
+		dir -= orientation.look_right();
+	}
+	let dt = inputs.tick_time.as_secs_f32();
+
+	let skel = &mut playback.camera.skeleton;
+	skel.orientation = orientation;
+	skel.target_position += (CAMERA_SPEED * dt) * dir.safe_normalized();
+	skel.filtered_position = skel.target_position;
+	skel.pre_filtered_position = skel.target_position;
+	state.entities.players.insert(CAMERA_ID, playback.camera.clone());
+}
+
+fn fmt_playback(playback: &Playback) -> String {
+	let fmt_time = |t: f32| format!("{}:{:02}", t as u32 / 60, t as u32 % 60);
+	let status = match playback.paused {
+		true => "paused".to_owned(),
+		false => format!("x{}", playback.speed),
+	};
+	format!("demo {} / {} {status}", fmt_time(playback.time), fmt_time(playback.demo.duration()))
+}
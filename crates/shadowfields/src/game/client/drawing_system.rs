@@ -9,6 +9,7 @@ pub(crate) fn draw_gamestate(sg: &mut SceneGraph, state: &Client) {
 
 	draw_skybox(sg, state);
 	sg.objects.extend_from_slice(&state.zones);
+	draw_movers(sg, state);
 
 	draw_players(sg, state);
 	draw_effects(sg, state);
@@ -65,6 +66,16 @@ fn draw_axes(sg: &mut SceneGraph, res: &Resources) -> Option<()> {
 	Some(())
 }
 
+fn draw_movers(sg: &mut SceneGraph, state: &Client) {
+	let ctx = ctx();
+	for MoverMesh { mover, vao, texture } in &state.mover_meshes {
+		let mover = &state.map.movers[*mover];
+		let bounds = mover.bounds();
+		let lightbox = state.map.volumetric_light_cache.lightbox_for(&state.map, &bounds);
+		sg.push(Object::new(vao.clone(), ctx.shader_pack.entity(texture, translation_matrix(mover.offset()), &bounds, &lightbox)));
+	}
+}
+
 fn draw_props(sg: &mut SceneGraph, state: &Client) {
 	for prop in state.entities.props.values() {
 		draw_prop(sg, state, prop);
@@ -198,7 +209,9 @@ fn draw_players(sg: &mut SceneGraph, state: &Client) {
 		} else {
 			if state.local_player().camera().can_see(player.position()) {
 				draw_player_3d_person(sg, state, player);
-				draw_shadow(sg, state, player);
+				if !player.powerups.has(PowerupKind::Invisibility) {
+					draw_shadow(sg, state, player);
+				}
 			}
 		}
 	}
@@ -215,6 +228,17 @@ fn draw_player_1st_person(rs: &Resources, sg: &mut SceneGraph, player: &Player)
 	//self.draw_line(sg, rs, line_of_fire.start.to_f32(), shoot_at);
 }
 
+/// How a player's powerups affect their looks: draw the avatar itself?, glow intensity (0: no glow).
+fn powerup_looks(player: &Player) -> (bool, f32) {
+	if player.powerups.has(PowerupKind::Invisibility) {
+		(false, 0.3) // just a faint shimmer
+	} else if !player.powerups.is_empty() {
+		(true, 1.0)
+	} else {
+		(true, 0.0)
+	}
+}
+
 fn draw_player_3d_person(sg: &mut SceneGraph, state: &Client, player: &Player) -> Option<()> {
 	match has_morph_model(player.avatar_id) {
 		true => draw_player_3d_person_morphed(sg, state, player),
@@ -230,28 +254,43 @@ fn draw_player_3d_person_morphed(sg: &mut SceneGraph, state: &Client, player: &P
 	let bounds = player.skeleton.filtered_bounds();
 	let lightbox = state.map.volumetric_light_cache.lightbox_for(&state.map, &bounds);
 	let avatar = player.avatar_id;
+	let (visible, glow) = powerup_looks(player);
+	let glow_tex = res.textures.load_sync(player.team.glow_texture())?;
 
 	// Body
 	{
 		let matrix = translation_matrix(player.position()) //.
+			* crouch_matrix(player)
 			* yaw_matrix(180.8*DEG /*BLENDER HACK*/-player.skeleton.filtered_frame().orientation.yaw) //.
 			* scale_matrix(player.torso_size.y()); // <<<< TODO
 		let feet_phase = entities.animation_state.get(&player.id).cloned().unwrap_or_default().feet_phase; // TODO: phase = 0..1.
 		let t = 0.5 * (feet_phase / PI) + 0.5;
 		let anim_vaos = &res.animations.load_sync(animation_for_avatar_id(avatar))?;
 		let tex = res.textures.load_sync(texture_for_avatar(avatar, player.team))?;
-		sg.push(anim_vaos.draw_animated(&tex, matrix, t, &bounds, &lightbox));
+		if visible {
+			sg.push(anim_vaos.draw_animated(&tex, matrix, t, &bounds, &lightbox));
+		}
+		if glow > 0.0 {
+			sg.push(anim_vaos.draw_highlight(&glow_tex, matrix, t, glow));
+		}
 	}
 
 	// Head
 	{
 		let head = res.vaos.load_sync(head_for_avatar_id(avatar))?;
-		let matrix = translation_matrix(player.position() + vec3::EY * (0.98 * player.torso_size.y() /*NECK HACK*/)) //.
+		let matrix = translation_matrix(player.position()) //.
+				* crouch_matrix(player)
+				* translation_matrix(vec3::EY * (0.98 * player.torso_size.y() /*NECK HACK*/))
 				* yaw_matrix(180.0*DEG /*BLENDER HACK*/-player.skeleton.filtered_frame().orientation.yaw) //.
 				* pitch_matrix(-0.4 * player.orientation().pitch)
 				* scale_matrix(player.head_size.y());
 		let tex = res.textures.load_sync(texture_for_avatar(avatar, player.team))?;
-		sg.push(Object::new(head, ctx.shader_pack.entity(&tex, matrix, &player.skeleton.filtered_bounds(), &lightbox)));
+		if visible {
+			sg.push(Object::new(head.clone(), ctx.shader_pack.entity(&tex, matrix, &player.skeleton.filtered_bounds(), &lightbox)));
+		}
+		if glow > 0.0 {
+			sg.push(Object::new(head, ctx.shader_pack.highlight(&glow_tex, matrix, glow)));
+		}
 	}
 	Some(())
 }
@@ -263,19 +302,32 @@ fn draw_player_3d_person_parts(sg: &mut SceneGraph, state: &Client, player: &Pla
 	let lightbox = state.map.volumetric_light_cache.lightbox_for(&state.map, &bounds);
 
 	let tex = state.res.textures.load_sync(texture_for_avatar(player.avatar_id, player.team))?;
+	let (visible, glow) = powerup_looks(player);
+	let glow_tex = state.res.textures.load_sync(player.team.glow_texture())?;
+	let mut push = |vao: Arc<VAO>, matrix: mat4| {
+		if visible {
+			sg.push(Object::new(vao.clone(), ctx.shader_pack.entity(&tex, matrix, &bounds, &lightbox)));
+		}
+		if glow > 0.0 {
+			sg.push(Object::new(vao, ctx.shader_pack.highlight(&glow_tex, matrix, glow)));
+		}
+	};
 
+	let crouch = crouch_matrix(player);
 	let yaw = yaw_matrix(180.0*DEG /*BLENDER HACK*/-player.skeleton.filtered_frame().orientation.yaw);
 
 	// Head
 	{
 		let head = state.res.vaos.load_sync(head_for_avatar_id(player.avatar_id))?;
 
-		let matrix = translation_matrix(player.position() + vec3::EY * player.torso_size.y()) //.
-				* &yaw //.
+		let matrix = translation_matrix(player.position()) //.
+				* &crouch
+				* &yaw
+				* translation_matrix(vec3::EY * player.torso_size.y())
 				* pitch_matrix(-0.4 * player.orientation().pitch)
 				* scale_matrix(player.head_size.y());
 
-		sg.push(Object::new(head, ctx.shader_pack.entity(&tex, matrix, &bounds, &lightbox)));
+		push(head, matrix);
 	}
 
 	// Feet
@@ -288,22 +340,29 @@ fn draw_player_3d_person_parts(sg: &mut SceneGraph, state: &Client, player: &Pla
 
 		let [l_pos, r_pos] = feet_pos_internal(player, feet_phase);
 		let l_matrix = translation_matrix(player.position()) //.
+				* &crouch
 				* &yaw
 				*translation_matrix(l_pos)
 				* scale_matrix(FOOT_SCALE);
 
 		let r_matrix = translation_matrix(player.position()) //.
+				* &crouch
 				* &yaw
 				*translation_matrix(r_pos)
 				* scale_matrix(FOOT_SCALE);
 
-		sg.push(Object::new(foot.clone(), ctx.shader_pack.entity(&tex, l_matrix, &bounds, &lightbox)));
-		sg.push(Object::new(foot, ctx.shader_pack.entity(&tex, r_matrix, &bounds, &lightbox)));
+		push(foot.clone(), l_matrix);
+		push(foot, r_matrix);
 	}
 
 	Some(())
 }
 
+/// Squash a crouching avatar to the size of its hitbox (there is no crouching animation).
+fn crouch_matrix(player: &Player) -> mat4 {
+	scale3_matrix(vec3(1.0, select(player.skeleton.crouched, CROUCH_HEIGHT, 1.0), 1.0))
+}
+
 fn feet_pos_internal(player: &Player, phase: f32) -> [vec3; 2] {
 	const SEP: f32 = 0.15;
 
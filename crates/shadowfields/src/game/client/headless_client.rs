@@ -0,0 +1,280 @@
+//!
+//! A client without window, GPU or audio: joins a server, sends `ClientMsg`s
+//! and records every `ServerMsg` it receives.
+//!
+//! Used by the `headless` binary (to run a script of commands against a server)
+//! and by tests (which start a `NetServer` on localhost and connect a few of these).
+//!
+//! Script syntax (one instruction per line, `#` starts a comment):
+//!
+//! ```text
+//! spawn            # ask the server to (re-)spawn, wait until spawned
+//! wait 2.5         # keep receiving for this many seconds
+//! expect alice     # wait until a log, chat or HUD announcement contains `alice` (fails after 10s)
+//! say hello        # anything else is sent as a command (see `server_command`)
+//! ```
+//!
+
+use super::internal::*;
+
+/// `expect` fails if the text did not arrive by then.
+const EXPECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct HeadlessClient {
+	conn: NetPipe<ClientMsg, ServerMsg>,
+	pub(crate) player_id: ID,
+	pub(crate) map_name: String,
+	/// Players and props, kept up-to-date with the server's messages.
+	pub(crate) entities: Entities,
+	/// Every message received from the server, in order.
+	pub(crate) received: Vec<ServerMsg>,
+	/// `received` before this index has already been matched by `wait_for`.
+	matched: usize,
+	/// `received` before this index has already been printed by `run_script`.
+	printed: usize,
+}
+
+impl HeadlessClient {
+	pub fn connect(server: &str, join_req: JoinRequest) -> Result<Self> {
+		let (conn, accepted) = connect(server, join_req)?;
+		Ok(Self {
+			conn,
+			player_id: accepted.player_id,
+			map_name: accepted.map_switch.map_name,
+			entities: accepted.map_switch.entities,
+			received: default(),
+			matched: 0,
+			printed: 0,
+		})
+	}
+
+	/// Execute a script (see module docs), printing received log, chat and HUD announcements to `out`.
+	pub fn run_script(&mut self, script: &str, out: &mut impl Write) -> Result<()> {
+		for (i, line) in script.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or_default().trim();
+			if line.is_empty() {
+				continue;
+			}
+			let result = self.exec(line);
+			self.print_received(out)?;
+			result.with_context(|| format!("line {}: {line}", i + 1))?;
+		}
+		Ok(())
+	}
+
+	fn exec(&mut self, line: &str) -> Result<()> {
+		match line.split_once(' ').unwrap_or((line, "")) {
+			("spawn", "") => self.spawn(EXPECT_TIMEOUT),
+			("wait", secs) => self.wait(Duration::from_secs_f32(secs.trim().parse()?)),
+			("expect", text) => self.expect_text(text.trim(), EXPECT_TIMEOUT).map(|_| ()),
+			_ => self.command(line),
+		}
+	}
+
+	fn print_received(&mut self, out: &mut impl Write) -> Result<()> {
+		for msg in &self.received[self.printed..] {
+			if let Some(text) = msg_text(msg) {
+				writeln!(out, "{text}")?;
+			}
+		}
+		self.printed = self.received.len();
+		Ok(())
+	}
+
+	pub(crate) fn send(&mut self, msg: ClientMsg) -> Result<()> {
+		self.conn.send(msg)
+	}
+
+	/// Send a command, like typed in the client's console (e.g. `say hello`).
+	pub(crate) fn command(&mut self, cmd: &str) -> Result<()> {
+		self.send(ClientMsg::Command(cmd.to_owned()))
+	}
+
+	/// Ask the server to (re-)spawn, wait until it did.
+	pub(crate) fn spawn(&mut self, timeout: Duration) -> Result<()> {
+		self.send(ClientMsg::ReadyToSpawn)?;
+		let id = self.player_id;
+		self.wait_for(timeout, |msg| matches!(msg, ServerMsg::UpdatePlayerFull(p) if p.id == id && p.spawned)).context("spawning")?;
+		Ok(())
+	}
+
+	/// Receive and apply all messages that have arrived so far (non-blocking).
+	pub(crate) fn poll(&mut self) -> Result<()> {
+		while let Some(msg) = self.conn.try_recv() {
+			let msg = msg?;
+			self.apply(&msg);
+			self.received.push(msg);
+		}
+		Ok(())
+	}
+
+	/// Keep receiving for a while.
+	pub(crate) fn wait(&mut self, duration: Duration) -> Result<()> {
+		let deadline = Instant::now() + duration;
+		while Instant::now() < deadline {
+			self.poll()?;
+			thread::sleep(Duration::from_millis(5));
+		}
+		Ok(())
+	}
+
+	/// Wait for the first message (not matched before) that satisfies `f`.
+	/// Messages that arrived earlier count too, so there is no race between sending and waiting.
+	pub(crate) fn wait_for(&mut self, timeout: Duration, f: impl Fn(&ServerMsg) -> bool) -> Result<ServerMsg> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			self.poll()?;
+			if let Some(i) = self.received[self.matched..].iter().position(&f) {
+				self.matched += i + 1;
+				return Ok(self.received[self.matched - 1].clone());
+			}
+			if Instant::now() > deadline {
+				bail!("timed out after {timeout:?}")
+			}
+			thread::sleep(Duration::from_millis(5));
+		}
+	}
+
+	/// Wait for a log, chat or HUD announcement containing `text`.
+	pub(crate) fn expect_text(&mut self, text: &str, timeout: Duration) -> Result<String> {
+		let msg = self.wait_for(timeout, |msg| msg_text(msg).is_some_and(|t| t.contains(text))).with_context(|| format!("expecting {text:?}"))?;
+		Ok(msg_text(&msg).unwrap_or_default())
+	}
+
+	/// Keep track of players and props (a small subset of `apply_server_msg`).
+	fn apply(&mut self, msg: &ServerMsg) {
+		use ServerMsg::*;
+		match msg {
+			AddPlayer(player) | UpdatePlayerPartial(player) | UpdatePlayerFull(player) => {
+				self.entities.players.insert(player.id, player.clone());
+			}
+			DropPlayer(player_id) => {
+				self.entities.players.remove(player_id);
+			}
+			MovePlayer(player_id, frame) => {
+				if let Some(p) = self.entities.players.get_mut(player_id) {
+					p.set_frame(frame.clone())
+				}
+			}
+			SwitchMap(map_switch) => {
+				self.map_name = map_switch.map_name.clone();
+				self.entities = map_switch.entities.clone();
+			}
+			InsertEntity(id, prop) => self.entities.insert_prop(*id, prop.clone()),
+			UpdateEntity(id, diff) => self.entities.apply_to_prop(*id, diff.clone()),
+			RemoveObject(id) => self.entities.remove_prop(*id),
+			_ => (),
+		}
+	}
+}
+
+/// Human-readable text of log, chat and HUD announcements (not e.g. health or ammo).
+fn msg_text(msg: &ServerMsg) -> Option<String> {
+	use ServerMsg::*;
+	match msg {
+		Log(text) => Some(text.clone()),
+		Chat(chat) => Some(chat.format()),
+		UpdateHUD(update) if matches!(update.pos, HUDPos::Center | HUDPos::TopCenter | HUDPos::TopCenter2) && !update.text.is_empty() => Some(update.text.clone()),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const TIMEOUT: Duration = Duration::from_secs(20);
+
+	/// Start a server on a free localhost port, return its address.
+	fn start_server(tweak: impl FnOnce(&mut ServerOpts)) -> String {
+		init_test_assets_dir();
+		let opts = ServerOpts {
+			addr: "127.0.0.1:0".into(),
+			maplist: vec![MapListEntry::named("castle"), MapListEntry::named("deck")],
+			vote_candidates: 0,
+			profiles_file: None,
+			bans_file: None,
+			..default()
+		}
+		.with(tweak);
+		NetServer::spawn(opts).unwrap().to_string()
+	}
+
+	fn join(server: &str, name: &str, team: Team) -> HeadlessClient {
+		let join_req = JoinRequest {
+			name: name.into(),
+			avatar_id: 1,
+			team: Some(team),
+			identity_key: String::new(),
+		};
+		HeadlessClient::connect(server, join_req).unwrap()
+	}
+
+	fn me(client: &HeadlessClient) -> &Player {
+		&client.entities.players[&client.player_id]
+	}
+
+	#[test]
+	fn kill() {
+		let server = start_server(|opts| {
+			opts.rules.spawn_shield = 0.0;
+			opts.rules.spawn_invulnerability = 0.0;
+		});
+		let mut alice = join(&server, "alice", Team::Red);
+		let mut bob = join(&server, "bob", Team::Blue);
+		alice.spawn(TIMEOUT).unwrap();
+		bob.spawn(TIMEOUT).unwrap();
+
+		alice.send(ClientMsg::HitPlayer(bob.player_id)).unwrap();
+		alice.expect_text("You confettied bob", TIMEOUT).unwrap();
+		bob.wait_for(TIMEOUT, |msg| matches!(msg, ServerMsg::KilledBy(id) if *id == alice.player_id)).unwrap(); // for the killcam
+		bob.expect_text("You got confettied by alice", TIMEOUT).unwrap();
+		assert!(!me(&bob).spawned);
+	}
+
+	#[test]
+	fn pickup() {
+		let server = start_server(|_| ());
+		let mut alice = join(&server, "alice", Team::Red);
+		alice.spawn(TIMEOUT).unwrap();
+
+		// pickup points are populated on the server's first tick, so they came with the map.
+		let (&prop_id, prop) = alice.entities.props.iter().find(|(_, prop)| prop.on_collide.is_some()).unwrap();
+		let frame = Frame {
+			position: prop.transform.translation,
+			..me(&alice).skeleton.target_frame()
+		};
+
+		alice.send(ClientMsg::MovePlayerIfSpawned(frame)).unwrap();
+		alice.wait_for(TIMEOUT, |msg| matches!(msg, ServerMsg::RemoveObject(id) if *id == prop_id)).unwrap();
+		assert!(!alice.entities.props.contains_key(&prop_id));
+	}
+
+	#[test]
+	fn map_switch() {
+		let server = start_server(|_| ());
+		let mut alice = join(&server, "alice", Team::Red);
+		let mut bob = join(&server, "bob", Team::Blue);
+		assert_eq!(alice.map_name, "castle");
+
+		alice.command("callvote map deck").unwrap();
+		alice.command("vote yes").unwrap();
+		// separate connections: make sure the vote was called before bob votes.
+		bob.expect_text("alice called a vote", TIMEOUT).unwrap();
+		bob.command("vote yes").unwrap();
+		for client in [&mut alice, &mut bob] {
+			client.wait_for(TIMEOUT, |msg| matches!(msg, ServerMsg::SwitchMap(_))).unwrap();
+			assert_eq!(client.map_name, "deck");
+		}
+	}
+
+	#[test]
+	fn script() {
+		let server = start_server(|_| ());
+		let mut alice = join(&server, "alice", Team::Red);
+		let mut out = vec![];
+		alice.run_script("# comment\nspawn\nsay hello  # chat\nexpect alice: hello\n", &mut out).unwrap();
+		assert!(String::from_utf8(out).unwrap().contains("alice: hello"));
+		assert!(alice.run_script("wait nonsense", &mut vec![]).is_err());
+	}
+}
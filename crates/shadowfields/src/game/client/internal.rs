@@ -5,20 +5,19 @@ pub use super::super::internal::*;
 pub(crate) use ClientMsg::*;
 pub(crate) use super::debug_overlay_system::*;
 pub(crate) use super::console_system::*;
+pub(crate) use super::demo_system::*;
 pub(crate) use super::drawing_system::*;
 pub(crate) use super::effects_system::*;
 pub(crate) use super::extrapolation_system::*;
 pub(crate) use super::footstep_system::*;
 pub(crate) use super::join_system::*;
 pub(crate) use super::jump_pad_system::*;
+pub(crate) use super::killcam_system::*;
 pub(crate) use super::volumetric_light::*;
 pub(crate) use super::mapswitch_system::*;
+pub(crate) use super::mover_system::*;
 pub(crate) use super::player_movement_system::*;
 pub(crate) use super::respawn_system::*;
 pub(crate) use super::server_message_system::*;
 pub(crate) use super::sound_system::*;
 pub(crate) use super::weapon_system::*;
-
-pub const X: usize = 0;
-pub const Y: usize = 1;
-pub const Z: usize = 2;
@@ -13,14 +13,33 @@ use super::internal::*;
 //	}
 //}
 
+/// The secret key that identifies this player to servers (see `JoinRequest::identity_key`).
+/// Taken from settings if present, else from the `identity.key` file, which is created on first use.
+pub(crate) fn identity_key(settings: &PlayerOpts) -> Result<String> {
+	if let Some(key) = &settings.identity_key {
+		return Ok(key.clone());
+	}
+	let file = assets_dir().settings_file("identity.key")?;
+	if file.exists() {
+		return Ok(read_to_string(&file)?.trim().to_owned());
+	}
+	let key = format!("{:032x}", rand::random::<u128>());
+	create(&file)?.write_all(key.as_bytes())?;
+	Ok(key)
+}
+
 // connect to server
 pub(crate) fn connect(server: &str, join_req: JoinRequest) -> Result<(NetPipe<ClientMsg, ServerMsg>, AcceptedMsg)> {
 	LOG.write(format!("Connecting to {server}..."));
 	let mut tcp_stream = TcpStream::connect(&server)?;
 	LOG.write(format!("Connected. Joining..."));
 	wireformat::serialize_into(&mut tcp_stream, &join_req)?;
-	let accepted_msg: AcceptedMsg = wireformat::deserialize_from(&mut tcp_stream) //
+	let response: JoinResponse = wireformat::deserialize_from(&mut tcp_stream) //
 		.map_err(|e| anyhow!("reading accept message: {e}"))?;
+	let accepted_msg = match response {
+		JoinResponse::Accepted(msg) => msg,
+		JoinResponse::Refused(reason) => bail!("server refused to join: {reason}"),
+	};
 	let player_id = accepted_msg.player_id;
 	LOG.write(format!("Accepted as player {player_id}"));
 	let conn = NetPipe::new(tcp_stream);
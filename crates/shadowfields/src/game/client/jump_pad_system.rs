@@ -1,17 +1,47 @@
 use super::internal::*;
 
 pub(crate) fn jump_pad_system(state: &mut Client) {
-	//let mut clone = state.local_player().clone();
-	//control(state, &mut clone);
-	//*state.local_player_mut() = clone;
+	state.jump_pad_cooldown.tick(state.dt());
+	if !state.jump_pad_cooldown.is_idle() || !state.local_player().spawned {
+		return;
+	}
+
+	let mut player = state.local_player().clone();
+	let pad = match launch_from_jump_pad(&state.map, &mut player) {
+		None => return,
+		Some(pad) => pad.clone(),
+	};
+	let team = player.team;
+	*state.local_player_mut() = player;
+
+	state.jump_pad_cooldown.reset();
+	state.pending_diffs.push(ClientMsg::PlaySound(SoundEffect::spatial(handle("fly"), pad.launch_point(), 1.0)));
+	state.pending_diffs.push(ClientMsg::AddEffect(Effect::particle_explosion(pad.launch_point(), team.star_texture())));
+}
 
-	let player_bounds = state.local_player().skeleton.filtered_bounds();
+/// Debug overlay: draw the trajectory of each jump pad (from the pad's center),
+/// so that map authors can check where players will land.
+pub(crate) fn draw_jump_pad_overlay(sg: &mut SceneGraph, state: &Client) {
+	const NUM_SEGMENTS: usize = 32;
+	const MAX_FLIGHT_TIME: f32 = 10.0; // s, in case the arc never comes back down.
 
+	let g = state.map.gravity;
 	for pad in &state.map.jump_pads {
-		if player_bounds.overlaps(&pad.bounds) {
-			state.local_player_mut().skeleton.velocity = vec3(0.0, jump_velocity_for_height(state.map.gravity, pad.jump_height), 0.0);
-			break;
+		let start = pad.launch_point();
+		let velocity = pad.launch_velocity(g, start, vec3::ZERO);
+		let landing_y = pad.target.map(|t| t.y()).unwrap_or(start.y());
+		let flight_time = ballistic_flight_time(g, start.y(), velocity.y(), landing_y).unwrap_or(MAX_FLIGHT_TIME).min(MAX_FLIGHT_TIME);
+
+		let points = (0..=NUM_SEGMENTS).map(|i| ballistic_position(g, start, velocity, flight_time * (i as f32) / (NUM_SEGMENTS as f32))).collect_vec();
+		for (a, b) in points.iter().tuple_windows() {
+			draw_line(sg, *a, *b);
 		}
 	}
 }
 
+/// Time until a ballistic trajectory comes down to height `landing_y`, if ever.
+fn ballistic_flight_time(g: f32, start_y: f32, vy: f32, landing_y: f32) -> Option<f32> {
+	// solve start_y + vy t - g t²/2 = landing_y for the latest t.
+	let discriminant = vy * vy - 2.0 * g * (landing_y - start_y);
+	(discriminant >= 0.0).then(|| (vy + discriminant.sqrt()) / g)
+}
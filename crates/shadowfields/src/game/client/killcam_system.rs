@@ -0,0 +1,115 @@
+//!
+//! Killcam: after getting fragged, replay the last few seconds through the killer's eyes.
+//!
+//! The client keeps a rolling buffer of snapshots (`Entities` and effects),
+//! played back at normal speed when the server tells us who killed us (`ServerMsg::KilledBy`).
+//! Skip with Mouse1. Disable with `killcam = false` in the `[graphics]` settings (or console command `killcam`).
+//!
+
+use super::internal::*;
+use std::collections::VecDeque;
+
+/// Seconds of history to replay.
+const KILLCAM_DURATION: f32 = 4.0;
+
+/// Seconds to watch our own death before the replay starts.
+/// (So the replay shows `KILLCAM_DURATION - KILLCAM_DELAY` seconds leading up to the kill).
+const KILLCAM_DELAY: f32 = 1.0;
+
+/// Seconds between snapshots.
+const SNAPSHOT_INTERVAL: f32 = 0.05;
+
+#[derive(Default)]
+pub(crate) struct KillCam {
+	snapshots: VecDeque<Snapshot>,
+	/// Seconds since the client started, to time-stamp snapshots.
+	time: f32,
+	/// Who killed us, and when to start the replay.
+	pending: Option<(ID, f32)>,
+}
+
+struct Snapshot {
+	time: f32,
+	entities: Entities,
+	effects: Vec<Effect>,
+}
+
+/// Server tells us who killed us: schedule the replay.
+pub(crate) fn handle_killed_by(state: &mut Client, killer: ID) {
+	if state.settings.graphics.killcam && killer != state.local_player_id {
+		state.killcam.pending = Some((killer, state.killcam.time + KILLCAM_DELAY));
+	}
+}
+
+/// Record a snapshot of the game, play the killcam when due.
+pub(crate) async fn killcam_system(state: &mut Client) -> Result<()> {
+	record_snapshot(state);
+
+	match state.killcam.pending {
+		Some((killer, start)) if state.killcam.time >= start => {
+			state.killcam.pending = None;
+			play_killcam(state, killer).await
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Add to the rolling buffer of snapshots, drop those older than `KILLCAM_DURATION`.
+fn record_snapshot(state: &mut Client) {
+	let dt = state.dt();
+	let kc = &mut state.killcam;
+	kc.time += dt;
+
+	if kc.snapshots.back().map(|s| kc.time - s.time >= SNAPSHOT_INTERVAL).unwrap_or(true) {
+		kc.snapshots.push_back(Snapshot {
+			time: kc.time,
+			entities: state.entities.clone(),
+			effects: state.effects.clone(),
+		});
+	}
+	while kc.snapshots.front().map(|s| kc.time - s.time > KILLCAM_DURATION).unwrap_or(false) {
+		kc.snapshots.pop_front();
+	}
+}
+
+/// Replay the buffered snapshots from the killer's point of view.
+/// Meanwhile, the live game state keeps receiving server messages.
+async fn play_killcam(state: &mut Client, killer: ID) -> Result<()> {
+	let snapshots = mem::take(&mut state.killcam.snapshots);
+	let Some(start) = snapshots.front().map(|s| s.time) else { return Ok(()) };
+	let map_name = state.map.name().to_owned();
+
+	let mut time = start;
+	// latest snapshot at or before `time`, as long as the killer is in it.
+	while let Some(snapshot) = snapshots.iter().take_while(|s| s.time <= time).last().filter(|s| s.entities.players.contains_key(&killer)) {
+		let mut sg = SceneGraph::new(state._win.viewport_size);
+		draw_snapshot(&mut sg, state, snapshot, killer);
+		state.present_and_wait(sg).await;
+
+		if state.inputs().just_pressed(Button::Mouse1) || time >= snapshots.back().map(|s| s.time).unwrap_or_default() {
+			break;
+		}
+
+		apply_all_server_messages(state)?;
+		if state.map.name() != map_name {
+			break; // snapshots are of the previous map
+		}
+		tick_effects(state);
+		state.hud.tick(state.dt());
+		time += state.dt();
+	}
+	Ok(())
+}
+
+/// Draw the game as it was at `snapshot`, through `killer`'s camera.
+fn draw_snapshot(sg: &mut SceneGraph, state: &mut Client, snapshot: &Snapshot, killer: ID) {
+	let entities = mem::replace(&mut state.entities, snapshot.entities.clone());
+	let effects = mem::replace(&mut state.effects, snapshot.effects.clone());
+	let local_player_id = mem::replace(&mut state.local_player_id, killer);
+
+	draw_gamestate(sg, state);
+
+	state.entities = entities;
+	state.effects = effects;
+	state.local_player_id = local_player_id;
+}
@@ -1,13 +1,15 @@
 use super::internal::*;
 
 pub(crate) fn switch_map(state: &mut Client, map_switch: MapSwitch) -> Result<()> {
-	let (map, zones, entities) = load_state(&mut state.res, map_switch, &state.settings.graphics)?;
+	let (map, zones, mover_meshes, entities) = load_state(&mut state.res, map_switch, &state.settings.graphics)?;
 
 	state.map = map;
 	state.zones = zones;
+	state.mover_meshes = mover_meshes;
 	state.entities = entities;
 	state.effects.clear();
 	state.pending_diffs.clear();
+	state.killcam = default();
 
 	// carry over advantages, e.g. to weapon system
 	if state.advantage {
@@ -21,12 +23,16 @@ pub(crate) fn switch_map(state: &mut Client, map_switch: MapSwitch) -> Result<()
 //	ui::loading_screen(win, move |_client| load_state(&mut res.lock().unwrap(), map_switch))
 //}
 
-pub(crate) fn load_state(res: &mut Resources, map_switch: MapSwitch, settings: &GraphicsOpts) -> Result<(Map, Vec<Object>, Entities)> {
+pub(crate) fn load_state(res: &mut Resources, map_switch: MapSwitch, settings: &GraphicsOpts) -> Result<(Map, Vec<Object>, Vec<MoverMesh>, Entities)> {
 	let map_name = &map_switch.map_name;
 	let map_dir = assets_dir().find_map_dir(map_name);
-	let map = Map::load(&map_name)?;
+	let map = Map::load(&map_name)?.with(|m| {
+		m.gravity = map_switch.gravity;
+		m.mode = map_switch.mode;
+	});
 
 	let zones = upload_scene_mesh(&map_dir, res, settings)?;
+	let mover_meshes = upload_mover_meshes(&map_dir, &map, res)?;
 
-	Ok((map, zones, map_switch.entities))
+	Ok((map, zones, mover_meshes, map_switch.entities))
 }
@@ -1,20 +1,24 @@
 //! Game logic specific to the client.
 
-pub mod internal;
+pub(crate) mod internal;
 
 mod advantage_system;
 mod client;
 mod client_command_system;
 mod console_system;
 mod debug_overlay_system;
+mod demo_system;
 mod drawing_system;
 mod effects_system;
 mod extrapolation_system;
 mod footstep_system;
+mod headless_client;
 mod join_system;
 mod jump_pad_system;
+mod killcam_system;
 mod loading_screen;
 mod mapswitch_system;
+mod mover_system;
 mod player_movement_system;
 mod respawn_system;
 mod server_message_system;
@@ -27,5 +31,6 @@ pub(crate) use client::*;
 pub(crate) use client_command_system::*;
 pub(crate) use debug_overlay_system::*;
 pub(crate) use drawing_system::*;
+pub use headless_client::*;
 pub(crate) use loading_screen::*;
 pub use volumetric_light::*;
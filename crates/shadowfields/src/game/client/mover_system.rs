@@ -0,0 +1,14 @@
+use super::internal::*;
+
+/// Move doors, lifts, platforms,... locally
+/// (the server only tells us when they change direction, see `UpdateMover`).
+/// Carry the local player along when standing on (or pushed by) a mover.
+pub(crate) fn mover_system(state: &mut Client) {
+	let dt = state.dt();
+	for mover in &mut state.map.movers {
+		mover.tick(dt);
+	}
+	let mut player = state.local_player().clone();
+	ride_movers(&state.map, &mut player);
+	*state.local_player_mut() = player;
+}
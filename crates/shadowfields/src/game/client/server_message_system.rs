@@ -1,14 +1,16 @@
 use super::internal::*;
 
 pub(crate) fn apply_all_server_messages(state: &mut Client) -> Result<()> {
-	while let Some(msg) = state.conn.try_recv() {
-		apply_server_msg(state, msg?)?;
+	while let Some(msg) = state.conn.as_mut().and_then(|conn| conn.try_recv()) {
+		let msg = msg?;
+		record_demo(state, || DemoEvent::Msg(msg.clone()));
+		apply_server_msg(state, msg)?;
 	}
 	Ok(())
 }
 
 /// Apply a diff to the game state.
-fn apply_server_msg(state: &mut Client, msg: ServerMsg) -> Result<()> {
+pub(crate) fn apply_server_msg(state: &mut Client, msg: ServerMsg) -> Result<()> {
 	use ServerMsg::*;
 	match msg {
 		AddPlayer(player) => handle_add_player(state, player),
@@ -19,20 +21,30 @@ fn apply_server_msg(state: &mut Client, msg: ServerMsg) -> Result<()> {
 		UpdatePlayerFull(player) => handle_update_player_full(state, player),
 		UpdateHUD(update) => handle_update_hud(state, update),
 		AddEffect(effect) => handle_add_effect(state, effect),
-		PlaySound(sound_effect) => play_sound(state, &sound_effect),
+		PlaySound(sound_effect) => handle_play_sound(state, sound_effect),
 		SwitchMap(map_switch) => switch_map(state, map_switch)?,
 		InsertEntity(id, gameobj) => handle_insert_object(state, id, gameobj),
 		UpdateEntity(id, diff) => handle_update_object(state, id, diff),
 		RemoveObject(id) => handle_remove_object(state, id),
 		Log(msg) => LOG.write(msg),
+		Chat(msg) => state.hud.add_chat(&msg),
 		Command(cmd) => exec_server_command(state, &cmd),
 		ApplyImpulse(delta_v) => handle_apply_impulse(state, delta_v),
+		UpdateMover(i, mover_state) => handle_update_mover(state, i, mover_state),
+		KilledBy(killer) => handle_killed_by(state, killer),
 	};
 	Ok(())
 }
 
 // todo: all "handle" methods here
 
+fn handle_play_sound(state: &mut Client, sound_effect: SoundEffect) {
+	play_sound(state, &sound_effect);
+	if state.settings.bot.enabled {
+		state.heard_sounds.push(sound_effect);
+	}
+}
+
 fn handle_add_player(state: &mut Client, player: Player) {
 	state.entities.players.insert(player.id, player);
 }
@@ -44,7 +56,7 @@ fn handle_drop_player(state: &mut Client, player_id: ID) {
 
 fn handle_move_player(state: &mut Client, player_id: ID, frame: Frame) {
 	if let Some(p) = state.entities.players.get_mut(&player_id) {
-		p.skeleton.set_frame(frame)
+		p.set_frame(frame)
 	}
 }
 
@@ -60,12 +72,13 @@ fn handle_force_move_player(state: &mut Client, position: vec3) {
 	state.local_player_mut().skeleton.target_position = position;
 }
 
-// Update part of player state controlled by server: everything except frame.
+// Update part of player state controlled by server: everything except frame (and jump pad flight).
 // Server sends frame anyway (for simplicity), but this is ignored here.
 fn handle_update_player_partial(state: &mut Client, new: Player) {
 	if let Some(old) = state.entities.players.get_mut(&new.id) {
 		let mut new = new;
-		new.skeleton.set_frame(old.skeleton.target_frame());
+		new.set_frame(old.skeleton.target_frame());
+		new.launched = old.launched;
 		*old = new;
 	}
 }
@@ -91,6 +104,12 @@ fn handle_remove_object(state: &mut Client, id: ID) {
 	state.entities.remove_prop(id);
 }
 
+fn handle_update_mover(state: &mut Client, i: usize, mover_state: MoverState) {
+	if let Some(mover) = state.map.movers.get_mut(i) {
+		mover.state = mover_state;
+	}
+}
+
 // TODO: private
 pub(crate) fn handle_add_effect(state: &mut Client, effect: Effect) {
 	state.effects.push(effect);
@@ -0,0 +1,150 @@
+//!
+//! Demo files: a recording of the `ServerMsg` stream, for playback without a server (see `run_demo_gameloop`).
+//!
+//! A demo is a gzipped stream of bincode values: one `DemoHeader`, followed by `DemoRecord`s until EOF.
+//! Records are streamed to disk as they come in, so a demo cut short by a crash still plays up to that point.
+//!
+//! Clients record what they receive plus their own player's movement (`--record-demo`),
+//! servers record what they broadcast during each match (`demo_dir`).
+//!
+
+use super::internal::*;
+
+/// Bumped on incompatible changes to the demo format (or to `ServerMsg`).
+pub(crate) const DEMO_VERSION: u32 = 2;
+
+/// Seconds between flushes to disk, bounding how much of the recording is lost in a crash.
+const FLUSH_INTERVAL: f32 = 1.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct DemoHeader {
+	pub version: u32,
+	/// The recording client's player. None: recorded by the server (spectator view).
+	pub player_id: Option<ID>,
+	/// The state at the start of the recording.
+	pub map_switch: MapSwitch,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct DemoRecord {
+	/// Seconds since the start of the recording.
+	pub time: f32,
+	pub event: DemoEvent,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) enum DemoEvent {
+	/// A message, as received by the client (or broadcast by the server).
+	Msg(ServerMsg),
+	/// Movement of the recording client's player, which is controlled locally and never sent by the server.
+	LocalFrame(Frame),
+}
+
+/// Writes a demo file, one record at a time.
+pub(crate) struct DemoRecorder {
+	file: PathBuf,
+	out: GzEncoder<BufWriter<File>>,
+	start: Instant,
+	last_flush: f32,
+}
+
+impl DemoRecorder {
+	pub fn create(file: &Path, player_id: Option<ID>, map_switch: MapSwitch) -> Result<Self> {
+		let mut out = GzEncoder::new(create(file)?, flate2::Compression::default());
+		let header = DemoHeader { version: DEMO_VERSION, player_id, map_switch };
+		bincode::serialize_into(&mut out, &header).with_context(|| format!("write demo {file:?}"))?;
+		Ok(Self {
+			file: file.to_owned(),
+			out,
+			start: Instant::now(),
+			last_flush: 0.0,
+		})
+	}
+
+	/// Append an event, time-stamped with the time since `create`.
+	pub fn record(&mut self, event: DemoEvent) -> Result<()> {
+		let time = self.start.elapsed().as_secs_f32();
+		bincode::serialize_into(&mut self.out, &DemoRecord { time, event }).with_context(|| format!("write demo {:?}", self.file))?;
+		if time - self.last_flush > FLUSH_INTERVAL {
+			self.last_flush = time;
+			self.out.flush().with_context(|| format!("write demo {:?}", self.file))?;
+		}
+		Ok(())
+	}
+
+	/// Flush and close the file, reporting errors. (Dropping the recorder also closes the file, but silently).
+	pub fn finish(self) -> Result<()> {
+		self.out.finish()?.flush()?;
+		info!("wrote demo {:?}", self.file);
+		Ok(())
+	}
+}
+
+/// A demo file loaded into memory.
+pub(crate) struct Demo {
+	pub header: DemoHeader,
+	/// In chronological order.
+	pub records: Vec<DemoRecord>,
+}
+
+impl Demo {
+	pub fn load(file: &Path) -> Result<Self> {
+		let mut input = GzDecoder::new(open(file)?);
+		let header: DemoHeader = bincode::deserialize_from(&mut input).with_context(|| format!("load demo {file:?}"))?;
+		if header.version != DEMO_VERSION {
+			bail!("load demo {file:?}: version {}, need {DEMO_VERSION}", header.version);
+		}
+
+		// Read until EOF. A truncated file is not an error, it just ends early.
+		let mut records = vec![];
+		while let Ok(record) = bincode::deserialize_from::<_, DemoRecord>(&mut input) {
+			records.push(record);
+		}
+
+		Ok(Self { header, records })
+	}
+
+	/// Time of the last record (seconds).
+	pub fn duration(&self) -> f32 {
+		self.records.last().map(|r| r.time).unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn record_and_load() {
+		let dir = std::env::temp_dir().join(format!("demo_test_{}", std::process::id()));
+		let file = dir.join("test.demo");
+		let map_switch = MapSwitch {
+			map_name: "castle".into(),
+			entities: default(),
+			gravity: 10.0,
+			mode: default(),
+		};
+		let player_id = ID::new();
+
+		let mut rec = DemoRecorder::create(&file, Some(player_id), map_switch).unwrap();
+		for i in 0..100 {
+			rec.record(DemoEvent::Msg(ServerMsg::Log(format!("message {i}")))).unwrap();
+		}
+		rec.finish().unwrap();
+
+		let demo = Demo::load(&file).unwrap();
+		assert_eq!(demo.header.player_id, Some(player_id));
+		assert_eq!(demo.header.map_switch.map_name, "castle");
+		assert_eq!(demo.records.len(), 100);
+		assert!(demo.records.iter().map(|r| r.time).tuple_windows().all(|(a, b)| a <= b));
+		assert!(matches!(&demo.records[99].event, DemoEvent::Msg(ServerMsg::Log(msg)) if msg == "message 99"));
+
+		// a truncated demo plays up to where it was cut off
+		let bytes = read_file(&file).unwrap();
+		std::fs::write(&file, &bytes[..bytes.len() / 2]).unwrap();
+		let demo = Demo::load(&file).unwrap();
+		assert!(demo.records.len() < 100);
+
+		force_remove(&dir).unwrap();
+	}
+}
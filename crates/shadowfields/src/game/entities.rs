@@ -36,9 +36,9 @@ impl Entities {
 }
 
 impl Entities {
-	pub fn join_new_player(&mut self, spawn_point: &SpawnPoint, req: JoinRequest) -> ID {
+	pub fn join_new_player(&mut self, spawn_point: &SpawnPoint, req: JoinRequest, team: Team) -> ID {
 		let player_id = ID::new();
-		let player = Player::new(player_id, spawn_point.position, spawn_point.orientation(), req.name, req.avatar_id, req.team);
+		let player = Player::new(player_id, spawn_point.position, spawn_point.orientation(), req.name, req.avatar_id, team);
 		self.players.insert(player_id, player);
 		player_id
 	}
@@ -7,6 +7,32 @@ pub(crate) struct ID(usize);
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+thread_local! {
+	/// Next ID on this thread while an `IdScope` is alive.
+	static SCOPED_NEXT_ID: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// While alive, `ID::new` on the current thread counts from 0 again, independently of other threads.
+/// Makes IDs reproducible, e.g. for tests running in parallel.
+#[cfg(test)]
+pub(crate) struct IdScope {
+	prev: Option<usize>,
+}
+
+#[cfg(test)]
+impl IdScope {
+	pub fn new() -> Self {
+		Self { prev: SCOPED_NEXT_ID.replace(Some(0)) }
+	}
+}
+
+#[cfg(test)]
+impl Drop for IdScope {
+	fn drop(&mut self) {
+		SCOPED_NEXT_ID.set(self.prev)
+	}
+}
+
 impl std::fmt::Display for ID {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		write!(f, "#{}", self.0)
@@ -14,8 +40,24 @@ impl std::fmt::Display for ID {
 }
 
 impl ID {
-	/// A fresh, unique entity ID.
+	/// A fresh, unique entity ID (unique within the current `IdScope`, if any).
 	pub fn new() -> Self {
-		Self(NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst))
+		match SCOPED_NEXT_ID.get() {
+			Some(next) => {
+				SCOPED_NEXT_ID.set(Some(next + 1));
+				Self(next)
+			}
+			None => Self(NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst)),
+		}
+	}
+
+	/// Numerical value, e.g. for scripts.
+	pub fn to_int(self) -> usize {
+		self.0
+	}
+
+	/// Inverse of `to_int`.
+	pub const fn from_int(v: usize) -> Self {
+		Self(v)
 	}
 }
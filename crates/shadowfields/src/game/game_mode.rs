@@ -0,0 +1,41 @@
+use super::internal::*;
+
+/// How players score (set per map by the server, see `MapListEntry`).
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+	/// Teams score together. Whether teammates can hurt each other depends on `Rules::friendly_fire`.
+	#[default]
+	TeamDeathmatch,
+	/// Free for all: everybody is an enemy and scores for themselves.
+	Deathmatch,
+}
+
+impl GameMode {
+	/// Can player `a` frag player `b`?
+	pub(crate) fn are_enemies(self, a: &Player, b: &Player) -> bool {
+		a.id != b.id && (self == GameMode::Deathmatch || a.team != b.team)
+	}
+}
+
+impl FromStr for GameMode {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		use GameMode::*;
+		match s {
+			"tdm" | "team_deathmatch" => Ok(TeamDeathmatch),
+			"dm" | "deathmatch" => Ok(Deathmatch),
+			bad => Err(anyhow!("unknown game mode `{}`, options: `team_deathmatch`, `deathmatch`", bad)),
+		}
+	}
+}
+
+impl fmt::Display for GameMode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			GameMode::TeamDeathmatch => f.write_str("Team Deathmatch"),
+			GameMode::Deathmatch => f.write_str("Deathmatch"),
+		}
+	}
+}
@@ -22,10 +22,19 @@ pub(crate) type ClientMsgs = Vec<ClientMsg>;
 pub struct JoinRequest {
 	pub name: String, // Player's nickname
 	pub avatar_id: u8,
-	pub team: Team,
+	pub team: Option<Team>, // Preferred team, None: let the server pick.
+	pub identity_key: String, // Secret that identifies the player across sessions (see `Profiles`).
 }
 
-/// Initial message sent by client when first joining a server.
+/// Server's answer to a `JoinRequest`.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum JoinResponse {
+	Accepted(AcceptedMsg),
+	/// Connection refused (e.g. banned), with the reason.
+	Refused(String),
+}
+
+/// Sent by the server when accepting a `JoinRequest`.
 #[derive(Serialize, Deserialize)]
 pub(crate) struct AcceptedMsg {
 	pub player_id: ID,
@@ -54,6 +63,9 @@ pub(crate) enum ClientMsg {
 	// I have shot player with ID `victim`.
 	HitPlayer(ID),
 
+	// I fired my weapon (hit or miss, for accuracy stats).
+	ShotFired,
+
 	// Send a CLI command to the server.
 	Command(String),
 }
@@ -93,11 +105,19 @@ pub(crate) enum ServerMsg {
 	PlaySound(SoundEffect),
 	UpdateHUD(HUDUpdate),
 	Log(String),
+	Chat(ChatMsg),
 
 	InsertEntity(ID, Prop),
 	UpdateEntity(ID, PropDiff),
 	RemoveObject(ID),
 	Command(String),
+
+	/// Server tells client that a mover (door, lift,...) changed direction.
+	/// (Client moves it locally in between).
+	UpdateMover(usize, MoverState),
+
+	/// Server tells the victim who fragged them (for the killcam).
+	KilledBy(ID),
 }
 
 // Message requesting that the client switches to a new map.
@@ -107,6 +127,9 @@ pub(crate) enum ServerMsg {
 pub(crate) struct MapSwitch {
 	pub map_name: String,
 	pub entities: Entities,
+	// per-map server settings (see `MapListEntry`)
+	pub gravity: f32,
+	pub mode: GameMode,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -116,6 +139,43 @@ pub struct HUDUpdate {
 	pub ttl_sec: f32,
 }
 
+/// A chat message, shown in the HUD's chat area (separate from the `Log`).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChatMsg {
+	/// Sender's name ("server" for the operator).
+	pub from: String,
+	/// Sender's team (team modes only), for team colouring.
+	pub team: Option<Team>,
+	pub channel: ChatChannel,
+	pub text: String,
+	/// When the server received the message (seconds since the UNIX epoch).
+	pub time: u64,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum ChatChannel {
+	/// `say`: everyone.
+	All,
+	/// `say_team`: teammates only.
+	Team,
+	/// `tell`: one player.
+	Private,
+}
+
+impl ChatMsg {
+	/// E.g. `[14:03] (team) alice: hi`. Timestamps are UTC.
+	pub fn format(&self) -> String {
+		let hours = (self.time / 3600) % 24;
+		let minutes = (self.time / 60) % 60;
+		let channel = match self.channel {
+			ChatChannel::All => "",
+			ChatChannel::Team => "(team) ",
+			ChatChannel::Private => "(private) ",
+		};
+		format!("[{hours:02}:{minutes:02}] {channel}{}: {}", self.from, self.text)
+	}
+}
+
 impl ServerMsg {
 	pub fn to_all(self) -> Envelope<Self> {
 		self.to(Addressee::All)
@@ -3,33 +3,43 @@
 mod animation_state;
 mod bot;
 mod client;
+mod demo;
 mod diff_entities;
 mod effect;
 mod entities;
 mod entity_id;
+mod game_mode;
 mod handle;
 mod internal;
 mod message;
+mod movement;
 mod player;
+mod powerup;
 mod prop;
 mod server;
 mod sound_effect;
 mod system_utils;
 mod team;
+mod weapon;
 
 pub use animation_state::*;
 pub use bot::*;
 pub use client::*;
+pub(crate) use demo::*;
 pub use diff_entities::*;
 pub use effect::*;
 pub use entities::*;
 pub use entity_id::*;
+pub use game_mode::*;
 pub use handle::*;
 pub use internal::*;
 pub use message::*;
+pub use movement::*;
 pub use player::*;
+pub use powerup::*;
 pub use prop::*;
 pub use server::*;
 pub use sound_effect::*;
 pub use system_utils::*;
 pub use team::*;
+pub use weapon::*;
@@ -0,0 +1,360 @@
+//!
+//! Player physics: walking, flying, jumping, gravity and collisions with the map.
+//! Driven by button `Inputs`, so that it can be used by the client (keyboard)
+//! as well as by bots (which may run inside the server, see `tick_bots`).
+//!
+
+use super::internal::*;
+
+/// Move a player according to the buttons held down, gravity and the map's geometry.
+/// Returns `true` if the player jumped (e.g. to play a sound).
+pub(crate) fn move_player(map: &Map, dt: f32, inputs: &Inputs, player: &mut Player) -> bool {
+	let jumped;
+	if player.flying {
+		player.launched = false;
+		tick_crouch(map, false, player); // crouch button flies down instead
+		tick_fly(map, dt, inputs, player);
+		jumped = tick_jump(map, inputs, player);
+	} else {
+		tick_crouch(map, inputs.is_down(Button::Crouch), player);
+		tick_walk(map, dt, inputs, player);
+		jumped = tick_jump(map, inputs, player);
+		tick_gravity(map, dt, player);
+	}
+
+	tick_move_skel(map, dt, player);
+	tick_rescue(map, dt, player);
+	jumped
+}
+
+/// Carry a player along with the movers that moved during their last tick:
+/// when they were standing on one (before it moved) or are pushed by it, and there is room.
+pub(crate) fn ride_movers(map: &Map, player: &mut Player) {
+	if !player.spawned {
+		return;
+	}
+	for (i, mover) in map.movers.iter().enumerate().filter(|(_, m)| m.last_delta != vec3::ZERO) {
+		let delta = mover.last_delta;
+		let skeleton = &player.skeleton;
+		let pos = skeleton.target_position;
+		// relative to where the mover was before, the player is at `pos + delta`.
+		let standing_on = mover.bumps(&skeleton.bounds_for(pos + delta - GROUND_PROBE_DIST * vec3::EY));
+		let pushed = mover.bumps(&skeleton.bounds_for(pos));
+		if (standing_on || pushed) && !map.bumps_except_mover(&skeleton.bounds_for(pos + delta), i) {
+			player.skeleton.target_position += delta;
+		}
+	}
+}
+
+/// Launch the player if they are standing in a jump pad.
+/// Returns the pad, if any (e.g. for sound and visual effects).
+/// Towards a target, the flight is purely ballistic until landing (see `Player::launched`):
+/// no walking, air control or damping, so that the player lands where `ballistic_velocity` aims.
+pub(crate) fn launch_from_jump_pad<'m>(map: &'m Map, player: &mut Player) -> Option<&'m JumpPad> {
+	let player_bounds = player.skeleton.filtered_bounds();
+	let pad = map.jump_pads.iter().find(|pad| player_bounds.overlaps(&pad.bounds))?;
+	player.skeleton.velocity = pad.launch_velocity(map.gravity, player.skeleton.target_position, player.skeleton.velocity);
+	player.launched = pad.target.is_some();
+	Some(pad)
+}
+
+/// Crouch while `crouch` is held, stand up when released (as soon as there is room).
+fn tick_crouch(map: &Map, crouch: bool, player: &mut Player) {
+	if crouch != player.skeleton.crouched && (crouch || player.can_stand(map)) {
+		player.set_crouched(crouch);
+	}
+}
+
+fn tick_walk(map: &Map, dt: f32, inputs: &Inputs, player: &mut Player) {
+	let crouch_speed = select(player.skeleton.crouched, CROUCH_SPEED, 1.0);
+	let walk_speed = player.walk_speed * crouch_speed * player.powerups.speed_multiplier() * walk_dir(player.orientation().yaw, inputs);
+	try_walk(player, dt, map, walk_speed);
+}
+
+fn tick_fly(map: &Map, dt: f32, inputs: &Inputs, player: &mut Player) {
+	let walk_speed = player.walk_speed * player.powerups.speed_multiplier() * fly_dir(player.orientation().yaw, inputs);
+	try_walk(player, dt, map, walk_speed);
+}
+
+// attempt to change velocity by walking / flying
+pub(crate) fn try_walk(player: &mut Player, dt: f32, map: &Map, walk_speed: vec3) {
+	let max_airctl_speed: f32 = player.walk_speed * player.powerups.speed_multiplier();
+	const AIRCTL_ACCEL: f32 = 2.0;
+
+	if player.launched {
+		// flying off a jump pad (even if still standing on it right after launch).
+		return;
+	}
+
+	if player.on_ground(map) {
+		player.skeleton.velocity[X] = walk_speed[X];
+		player.skeleton.velocity[Z] = walk_speed[Z];
+	} else {
+		// flying through the air
+
+		// always slightly damp movement
+		let damp = 0.1;
+		player.skeleton.velocity *= 1.0 - damp * dt;
+
+		// allow to control movement in the air a bit.
+		if player.skeleton.velocity.remove(1).len() > max_airctl_speed {
+			// flying too fast, damp aggressively
+			player.skeleton.velocity *= 1.0 - 4.0 * damp * dt;
+		} else {
+			// flying not too fast, allow some slow control
+			player.skeleton.velocity += (AIRCTL_ACCEL * dt) * walk_speed;
+		}
+	}
+}
+
+pub(crate) fn try_jump(player: &mut Player, map: &Map, jump_speed: f32) -> bool {
+	if player.on_ground(map) {
+		unconditional_jump(player, jump_speed);
+		true
+	} else {
+		false
+	}
+}
+
+pub(crate) fn unconditional_jump(player: &mut Player, jump_speed: f32) {
+	player.skeleton.velocity[Y] = jump_speed
+}
+
+// `was_pressed`: a tap shorter than a tick (e.g. by a bot) also jumps.
+fn tick_jump(map: &Map, inputs: &Inputs, player: &mut Player) -> bool {
+	inputs.was_pressed(Button::Jump) && try_jump(player, map, player.jump_speed)
+}
+
+fn tick_gravity(map: &Map, dt: f32, player: &mut Player) {
+	let g = map.gravity;
+	player.skeleton.velocity[Y] -= g * dt;
+	if !player.launched {
+		let damp = 0.05;
+		player.skeleton.velocity *= 1.0 - damp * dt;
+	}
+}
+
+fn tick_move_skel(map: &Map, dt: f32, player: &mut Player) {
+	// 👇 Hack because velocity can become NaN.
+	if !player.skeleton.velocity.is_finite() {
+		player.skeleton.velocity = vec3::ZERO;
+	}
+	debug_assert!(player.skeleton.velocity.is_finite());
+
+	debug_assert!(dt != 0.0);
+
+	let h_dir = player.skeleton.velocity.with(|v| v[Y] = 0.0).safe_normalized();
+
+	player.bump = Vector3::new(false, false, false);
+
+	slide(map, player, player.skeleton.velocity * dt);
+
+	if player.bump.x() || player.bump.z() {
+		let climb_speed = player.walk_speed;
+		const STAIR_W: f32 = 0.5;
+		const STAIR_H: f32 = 0.6;
+		let step_up = STAIR_W * h_dir + STAIR_H * vec3::EY;
+		let tiny = climb_speed * dt * vec3::EY;
+		if player.pos_ok(map, player.skeleton.target_position + step_up) && player.pos_ok(map, player.skeleton.target_position + tiny) {
+			player.skeleton.target_position += tiny
+		}
+	}
+
+	if player.on_ground(map) {
+		player.skeleton.velocity[Y] = 0.0; // <<<<<<<<<<<<<<<<<<<<< ????
+		player.launched = false;
+	}
+}
+
+/// Move the player by `delta`, sliding along whatever they bump into:
+/// on contact, the part of the movement (and velocity) into the surface is removed, the rest continues along it.
+fn slide(map: &Map, player: &mut Player, delta: vec3) {
+	/// Number of contacts handled per tick (e.g. 2 for a corner, 3 for a corner on the floor).
+	const MAX_CONTACTS: usize = 4;
+	/// Distance kept from surfaces, so that the player does not get stuck in them due to round-off.
+	const SKIN: f32 = 0.01;
+
+	let mut delta = delta;
+	for _ in 0..MAX_CONTACTS {
+		if delta == vec3::ZERO {
+			return;
+		}
+		let skel = &mut player.skeleton;
+		let Some((t, normal)) = map.sweep(&skel.bounds_for(skel.target_position), delta) else {
+			skel.target_position += delta;
+			return;
+		};
+
+		// stop short, `SKIN` away from the surface (measured along the normal).
+		let t = f32::max(0.0, t - SKIN / -delta.dot(normal));
+		skel.target_position += t * delta;
+		delta = (1.0 - t) * delta;
+		delta -= delta.dot(normal) * normal;
+		skel.velocity -= f32::min(0.0, skel.velocity.dot(normal)) * normal;
+		for i in [X, Y, Z] {
+			player.bump[i] |= normal[i].abs() > 0.5;
+		}
+	}
+}
+
+fn tick_rescue(map: &Map, dt: f32, player: &mut Player) {
+	let stairclimb_speed = player.walk_speed * 1.2; // TODO
+	if !player.pos_ok(map, player.skeleton.target_position) {
+		player.skeleton.target_position[Y] += stairclimb_speed * dt;
+	}
+}
+
+/// Direction an entity wants to move in,
+/// based on the currently pressed keys and look direction.
+fn walk_dir(yaw: f32, inputs: &Inputs) -> vec3 {
+	let mut dir = vec3::ZERO;
+	if inputs.is_down(Button::Left) {
+		dir[X] -= 1.0;
+	}
+	if inputs.is_down(Button::Right) {
+		dir[X] += 1.0;
+	}
+	if inputs.is_down(Button::Forward) {
+		dir[Z] -= 1.0;
+	}
+	if inputs.is_down(Button::Backward) {
+		dir[Z] += 1.0;
+	}
+	if dir == vec3::ZERO {
+		return vec3::ZERO;
+	}
+	let dir = -yaw_matrix(-yaw).transform_point_ignore_w(dir);
+	dir.safe_normalized()
+}
+
+/// Direction an entity wants to fly in,
+/// based on the currently pressed keys and look direction.
+fn fly_dir(yaw: f32, inputs: &Inputs) -> vec3 {
+	let mut fly_dir = walk_dir(yaw, inputs);
+	if inputs.is_down(Button::Jump) {
+		fly_dir[Y] += 1.0;
+	}
+	if inputs.is_down(Button::Crouch) {
+		fly_dir[Y] -= 1.0;
+	}
+	fly_dir.safe_normalized()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const DT: f32 = 1.0 / 60.0;
+
+	/// Floor at y = 0, plus extra faces.
+	fn test_map(faces: &[[vec3; 3]]) -> Map {
+		Map::test_floor(default(), faces)
+	}
+
+	fn test_player(velocity: vec3) -> Player {
+		Player::new(ID::new(), vec3(0.0, 0.01, 0.0), default(), "test".into(), 0, Team::Red).with(|p| p.skeleton.velocity = velocity)
+	}
+
+	#[test]
+	fn slide_along_wall() {
+		// wall in the plane x = 2
+		let map = test_map(&[[vec3(2.0, -10.0, -50.0), vec3(2.0, 10.0, -50.0), vec3(2.0, 0.0, 50.0)]]);
+		let mut player = test_player(vec3(6.0, 0.0, -6.0));
+
+		for _ in 0..60 {
+			tick_move_skel(&map, DT, &mut player);
+		}
+
+		let pos = player.skeleton.target_position;
+		let hsize = player.skeleton.hsize;
+		assert!(pos.x() < 2.0 - hsize / 2.0 && pos.x() > 1.9 - hsize / 2.0, "{pos}");
+		assert!((pos.z() - -6.0).abs() < 1e-3, "{pos}"); // kept moving along the wall at full speed
+		assert!(player.skeleton.velocity.x() == 0.0);
+		assert!(player.pos_ok(&map, pos));
+	}
+
+	#[test]
+	fn thin_geometry() {
+		// thin bar at waist height, across the path
+		let bar = [vec3(2.0, 0.5, -10.0), vec3(2.0, 0.51, -10.0), vec3(2.0, 0.5, 10.0)];
+		let map = test_map(&[bar]);
+		let mut player = test_player(vec3(6.0, 0.0, 0.0));
+
+		for _ in 0..60 {
+			tick_move_skel(&map, DT, &mut player);
+		}
+
+		assert!(player.skeleton.target_position.x() < 2.0, "{}", player.skeleton.target_position);
+		assert!(map.bumps(&player.skeleton.bounds_for(vec3(2.0, 0.01, 0.0))));
+	}
+
+	#[test]
+	fn crouch_under_ceiling() {
+		// ceiling at y = 1.2 for x > 2: too low to stand (1.6m), room to crouch (0.96m).
+		let (a, b, c, d) = (vec3(2.0, 1.2, -50.0), vec3(2.0, 1.2, 50.0), vec3(50.0, 1.2, 50.0), vec3(50.0, 1.2, -50.0));
+		let map = test_map(&[[a, b, d], [b, c, d]]);
+		let mut player = Player::new(ID::new(), vec3(0.0, 0.01, 0.0), default(), "test".into(), 10, Team::Red);
+		let standing = player.skeleton.vsize;
+		let walk = |player: &mut Player, vx: f32| {
+			for _ in 0..60 {
+				player.skeleton.velocity = vec3(vx, 0.0, 0.0);
+				tick_move_skel(&map, DT, player);
+			}
+		};
+
+		walk(&mut player, 3.0);
+		assert!(player.skeleton.target_position.x() < 2.0);
+
+		tick_crouch(&map, true, &mut player);
+		assert!(player.skeleton.vsize < 1.2 && player.cam_height < player.skeleton.vsize);
+		walk(&mut player, 3.0);
+		assert!(player.skeleton.target_position.x() > 2.5);
+
+		// no room to stand up
+		tick_crouch(&map, false, &mut player);
+		assert!(player.skeleton.crouched);
+
+		// other clients see the crouched hitbox
+		let mut remote = Player::new(player.id, default(), default(), "test".into(), 10, Team::Red);
+		remote.set_frame(player.skeleton.target_frame());
+		assert_eq!(remote.skeleton.vsize, player.skeleton.vsize);
+
+		walk(&mut player, -3.0);
+		tick_crouch(&map, false, &mut player);
+		assert!(!player.skeleton.crouched);
+		assert_eq!(player.skeleton.vsize, standing);
+	}
+
+	/// Launch from a jump pad at the origin towards `target` and fly (no buttons held), return where we land.
+	fn fly_from_jump_pad(target: vec3) -> vec3 {
+		let pad = JumpPad {
+			bounds: BoundingBox::new(vec3(-1.0, 0.0, -1.0), vec3(1.0, 0.5, 1.0)),
+			jump_height: 2.0,
+			target: Some(target),
+		};
+		let map = Map::test_floor(Metadata { jump_pads: vec![pad], ..default() }, &[]);
+		let mut player = test_player(vec3::ZERO);
+		let inputs = Inputs::default();
+
+		// like the client: move, launch, keep moving.
+		move_player(&map, DT, &inputs, &mut player);
+		player.skeleton.filtered_position = player.skeleton.target_position;
+		assert!(launch_from_jump_pad(&map, &mut player).is_some());
+		for _ in 0..600 {
+			move_player(&map, DT, &inputs, &mut player);
+			if !player.launched {
+				break;
+			}
+		}
+		assert!(!player.launched, "still flying");
+		player.skeleton.target_position
+	}
+
+	#[test]
+	fn jump_pad_launches_towards_target() {
+		for x in [10.0, 20.0, 40.0] {
+			let landing = fly_from_jump_pad(vec3(x, 0.0, 0.0));
+			assert!((landing - vec3(x, 0.0, 0.0)).len() < 0.5, "target {x}: landed at {landing}");
+		}
+	}
+}
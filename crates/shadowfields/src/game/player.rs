@@ -12,6 +12,8 @@ pub(crate) struct Player {
 	pub avatar_id: u8, // determines which avatar model is drawn (gl_client.rs).
 	pub team: Team,
 	pub spawned: bool, // playing or waiting for respawn?
+	pub frozen: bool,  // cannot move or shoot (e.g. during intermission).
+	pub powerups: Powerups,
 
 	pub flying: bool,
 
@@ -19,6 +21,8 @@ pub(crate) struct Player {
 	pub skeleton: Skeleton, // fully determines player position
 
 	pub bump: Vector3<bool>,
+	/// Flying towards a jump pad's target, until landing: no walking or air drag (see `launch_from_jump_pad`).
+	pub launched: bool,
 
 	pub torso_size: vec3,
 	pub head_size: vec3,
@@ -33,6 +37,12 @@ const ANIMAL_HEAD_SIZE: vec3 = vec3(0.6, 0.6, 0.6);
 const HUMAN_TORSO_SIZE: vec3 = vec3(0.5, 0.9, 0.5);
 const HUMAN_HEAD_SIZE: vec3 = vec3(0.5, 0.7, 0.5);
 
+/// Height of the hitbox and camera while crouching, relative to standing.
+pub(crate) const CROUCH_HEIGHT: f32 = 0.6;
+
+/// Walk speed while crouching, relative to standing.
+pub(crate) const CROUCH_SPEED: f32 = 0.4;
+
 impl Player {
 	pub fn new(id: ID, position: vec3, orientation: Orientation, name: String, avatar_id: u8, team: Team) -> Self {
 		let (torso_size, head_size) = match avatar_id {
@@ -52,15 +62,18 @@ impl Player {
 			name,
 			avatar_id,
 			spawned: false,
+			frozen: false,
+			powerups: default(),
 			team,
 			skeleton: Skeleton::new(position, orientation, max_width, total_height),
 			flying: false,
 			torso_size,
 			head_size,
-			cam_height: torso_size.y() + head_size.y() / 3.0,
+			cam_height: standing_cam_height(torso_size, head_size),
 			walk_speed: 6.0,
 			jump_speed,
 			bump: default(),
+			launched: false,
 		}
 	}
 
@@ -70,6 +83,31 @@ impl Player {
 		!self.pos_ok(map, self.skeleton.target_position - vec3(0.0, GROUND_PROBE_DIST, 0.0))
 	}
 
+	/// Crouch or stand up: shrinks or restores the hitbox (`Skeleton::vsize`) and camera height.
+	/// Does not check for room to stand up, see `can_stand`.
+	pub fn set_crouched(&mut self, crouched: bool) {
+		let scale = select(crouched, CROUCH_HEIGHT, 1.0);
+		self.skeleton.crouched = crouched;
+		self.skeleton.vsize = scale * (self.torso_size.y() + self.head_size.y());
+		self.cam_height = scale * standing_cam_height(self.torso_size, self.head_size);
+	}
+
+	/// Is there room to stand up? (E.g. not crouching under a low ceiling).
+	pub fn can_stand(&self, map: &Map) -> bool {
+		let pos = self.skeleton.target_position;
+		let standing = self.skeleton.bounds_for(pos).with(|bb| bb.max[Y] = pos.y() + self.torso_size.y() + self.head_size.y());
+		!map.bumps(&standing)
+	}
+
+	/// Set the position, orientation, etc. (e.g. received from another player's client),
+	/// including the hitbox if they crouched or stood up.
+	pub fn set_frame(&mut self, frame: Frame) {
+		if frame.crouched != self.skeleton.crouched {
+			self.set_crouched(frame.crouched);
+		}
+		self.skeleton.set_frame(frame);
+	}
+
 	/// TODO
 	//pub fn set_orientation(&mut self, inputs: &Inputs, sens: f32) {
 	//	let mouse_sens = 0.00001 * sens;
@@ -131,3 +169,8 @@ impl Player {
 		!map.bumps(&self.skeleton.bounds_for(pos))
 	}
 }
+
+/// Camera height above the feet, while standing.
+fn standing_cam_height(torso_size: vec3, head_size: vec3) -> f32 {
+	torso_size.y() + head_size.y() / 3.0
+}
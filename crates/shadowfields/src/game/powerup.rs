@@ -0,0 +1,192 @@
+use super::internal::*;
+
+/// Timed powerups, picked up from pickup points (see `Prop`),
+/// except for `Advantage`, which is granted by the operator.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum PowerupKind {
+	/// Deal `QUAD_DAMAGE_MULTIPLIER` times more damage.
+	QuadDamage,
+	/// Walk `HASTE_SPEED_MULTIPLIER` times faster.
+	Haste,
+	/// Regenerate health and shield.
+	Regeneration,
+	/// Barely visible to other players.
+	Invisibility,
+	/// Handicap for weaker players (`adv` command): shield recharges up to `Rules::advantage_shield`.
+	/// Lasts until the player leaves, even across deaths.
+	Advantage,
+}
+
+pub const QUAD_DAMAGE_MULTIPLIER: f32 = 4.0;
+pub const HASTE_SPEED_MULTIPLIER: f32 = 1.5;
+/// Health/shield points per second.
+pub const REGENERATION_RATE: f32 = 15.0;
+
+/// What happens when picking up a powerup that is already active.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Stacking {
+	/// Reset the remaining time to the full duration.
+	Refresh,
+	/// Add the full duration to the remaining time (up to `max_duration`).
+	Extend,
+}
+
+impl PowerupKind {
+	/// Powerups that can be picked up (e.g. from gift boxes).
+	pub const PICKUPS: [PowerupKind; 4] = [PowerupKind::QuadDamage, PowerupKind::Haste, PowerupKind::Regeneration, PowerupKind::Invisibility];
+
+	/// Seconds.
+	pub fn duration(self) -> f32 {
+		match self {
+			PowerupKind::QuadDamage => 30.0,
+			PowerupKind::Haste => 30.0,
+			PowerupKind::Regeneration => 30.0,
+			PowerupKind::Invisibility => 20.0,
+			PowerupKind::Advantage => f32::INFINITY,
+		}
+	}
+
+	/// Kept when dying (see `Powerups::remove_on_death`).
+	pub fn survives_death(self) -> bool {
+		self == PowerupKind::Advantage
+	}
+
+	/// Remaining time can never exceed this (see `Stacking::Extend`).
+	pub fn max_duration(self) -> f32 {
+		2.0 * self.duration()
+	}
+
+	pub fn stacking(self) -> Stacking {
+		match self {
+			PowerupKind::QuadDamage | PowerupKind::Invisibility | PowerupKind::Advantage => Stacking::Refresh,
+			PowerupKind::Haste | PowerupKind::Regeneration => Stacking::Extend,
+		}
+	}
+
+	/// Pickup props (see `Prop::from_str`) and commands use this name.
+	pub fn handle(self) -> Handle {
+		match self {
+			PowerupKind::QuadDamage => handle("quad_damage"),
+			PowerupKind::Haste => handle("haste"),
+			PowerupKind::Regeneration => handle("regeneration"),
+			PowerupKind::Invisibility => handle("invisibility"),
+			PowerupKind::Advantage => handle("advantage"),
+		}
+	}
+}
+
+impl FromStr for PowerupKind {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		PowerupKind::PICKUPS
+			.into_iter()
+			.find(|kind| kind.handle().as_str() == s)
+			.ok_or_else(|| anyhow!("unknown powerup `{s}`, options: {}", PowerupKind::PICKUPS.map(|k| format!("`{}`", k.handle())).join(", ")))
+	}
+}
+
+impl fmt::Display for PowerupKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PowerupKind::QuadDamage => f.write_str("Quad Damage"),
+			PowerupKind::Haste => f.write_str("Haste"),
+			PowerupKind::Regeneration => f.write_str("Regeneration"),
+			PowerupKind::Invisibility => f.write_str("Invisibility"),
+			PowerupKind::Advantage => f.write_str("Advantage"),
+		}
+	}
+}
+
+/// A player's active powerups, with remaining time (seconds).
+///
+/// Part of `Player`, so that clients know about them (for movement and rendering).
+/// Only the server counts down the remaining time, clients may have outdated `ttl`s.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Powerups {
+	active: Vec<(PowerupKind, f32)>,
+}
+
+impl Powerups {
+	pub fn has(&self, kind: PowerupKind) -> bool {
+		self.active.iter().any(|(k, _)| *k == kind)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.active.is_empty()
+	}
+
+	/// Active powerups and their remaining time.
+	pub fn iter(&self) -> impl Iterator<Item = (PowerupKind, f32)> + '_ {
+		self.active.iter().copied()
+	}
+
+	/// Activate a powerup, following its stacking rule if it is already active.
+	pub fn add(&mut self, kind: PowerupKind) {
+		match self.active.iter_mut().find(|(k, _)| *k == kind) {
+			None => self.active.push((kind, kind.duration())),
+			Some((_, ttl)) => match kind.stacking() {
+				Stacking::Refresh => *ttl = f32::max(*ttl, kind.duration()),
+				Stacking::Extend => *ttl = f32::min(*ttl + kind.duration(), kind.max_duration()),
+			},
+		}
+	}
+
+	/// Count down remaining times, remove and return the powerups that expired.
+	pub fn tick(&mut self, dt: f32) -> SmallVec<[PowerupKind; 2]> {
+		for (_, ttl) in &mut self.active {
+			*ttl -= dt;
+		}
+		let expired = self.active.iter().filter(|(_, ttl)| *ttl <= 0.0).map(|(k, _)| *k).collect();
+		self.active.retain(|(_, ttl)| *ttl > 0.0);
+		expired
+	}
+
+	/// Lose all powerups, except those that `survive_death`.
+	pub fn remove_on_death(&mut self) {
+		self.active.retain(|(k, _)| k.survives_death())
+	}
+
+	/// Multiplies damage dealt.
+	pub fn damage_multiplier(&self) -> f32 {
+		select(self.has(PowerupKind::QuadDamage), QUAD_DAMAGE_MULTIPLIER, 1.0)
+	}
+
+	/// Multiplies walk speed.
+	pub fn speed_multiplier(&self) -> f32 {
+		select(self.has(PowerupKind::Haste), HASTE_SPEED_MULTIPLIER, 1.0)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn stacking() {
+		let mut p = Powerups::default();
+		p.add(PowerupKind::QuadDamage);
+		p.add(PowerupKind::Haste);
+		p.tick(10.0);
+		p.add(PowerupKind::QuadDamage); // refresh
+		p.add(PowerupKind::Haste); // extend
+		p.add(PowerupKind::Haste); // extend, capped
+		assert_eq!(p.iter().collect_vec(), vec![(PowerupKind::QuadDamage, 30.0), (PowerupKind::Haste, 60.0)]);
+
+		let expired = p.tick(45.0);
+		assert_eq!(expired.to_vec(), vec![PowerupKind::QuadDamage]);
+		assert!(p.has(PowerupKind::Haste));
+		assert!(!p.has(PowerupKind::QuadDamage));
+	}
+
+	#[test]
+	fn advantage() {
+		let mut p = Powerups::default();
+		p.add(PowerupKind::Advantage);
+		p.add(PowerupKind::Haste);
+		assert_eq!(p.tick(1e6).to_vec(), vec![PowerupKind::Haste]);
+		p.add(PowerupKind::Haste);
+		p.remove_on_death();
+		assert_eq!(p.iter().map(|(k, _)| k).collect_vec(), vec![PowerupKind::Advantage]);
+	}
+}
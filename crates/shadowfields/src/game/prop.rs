@@ -65,7 +65,16 @@ impl Prop {
 				on_collide: Some(handle("gift_box")),
 				..default
 			},
-			name => bail!("unknown prop: {name}"),
+			name => match PowerupKind::from_str(name) {
+				Ok(kind) => Self {
+					mesh: handle("gift_box"),
+					texture: powerup_texture(kind),
+					aabb_size: vec3::ONES,
+					on_collide: Some(kind.handle()),
+					..default
+				},
+				Err(_) => bail!("unknown prop: {name}"),
+			},
 		})
 	}
 
@@ -86,3 +95,13 @@ impl Prop {
 		}
 	}
 }
+
+fn powerup_texture(kind: PowerupKind) -> Handle {
+	match kind {
+		PowerupKind::QuadDamage => handle("#AA44FF"),
+		PowerupKind::Haste => handle("#FFCC22"),
+		PowerupKind::Regeneration => handle("#44FF66"),
+		PowerupKind::Invisibility => handle("#CCCCCC"),
+		PowerupKind::Advantage => handle("#FF8800"),
+	}
+}
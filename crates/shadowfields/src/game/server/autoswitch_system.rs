@@ -1,5 +1,6 @@
 //!
-//! Automatically switch maps when a frag or time limit is reached.
+//! Automatically end the match when a frag or time limit is reached
+//! (the intermission then switches to the next map, see `match_system`).
 //!
 
 use super::internal::*;
@@ -45,17 +46,13 @@ fn tick_time_remaining(state: &mut ServerState) {
 }
 
 fn tick_next_game(state: &mut ServerState) {
-	if state.autoswitch.time_playing > state.autoswitch.time_limit {
-		switch_next_map(state);
-	}
-
-	if state.scores.max() >= state.autoswitch.frag_limit {
-		switch_next_map(state);
+	if state.autoswitch.time_playing > state.autoswitch.time_limit || state.scores.max(state.map.mode) >= state.autoswitch.frag_limit {
+		end_match(state);
 	}
 }
 
 pub(crate) fn announce_remaining_frags(state: &mut ServerState) {
-	let top_score = state.scores.max();
+	let top_score = state.scores.max(state.map.mode);
 	if top_score == (state.autoswitch.frag_limit) - 1 {}
 
 	let remaining = state.autoswitch.frag_limit - top_score;
@@ -0,0 +1,160 @@
+//!
+//! Ban players by IP address. Bans are persisted next to `server.toml`,
+//! and checked by the NetServer before a new connection joins.
+//!
+
+use super::internal::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct Ban {
+	pub ip: IpAddr,
+	/// Player name at the time of the ban (informational).
+	pub name: String,
+	/// Seconds since the UNIX epoch when the ban expires (None: permanent).
+	pub until: Option<u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct BanList {
+	/// Where bans are persisted (None: keep in memory only).
+	file: Option<PathBuf>,
+	bans: Vec<Ban>,
+}
+
+impl BanList {
+	/// Load bans from `file`, if it exists.
+	pub fn load(file: Option<PathBuf>) -> Result<Self> {
+		let bans = match &file {
+			Some(file) if file.exists() => load_ron(file)?,
+			_ => default(),
+		};
+		Ok(Self { file, bans })
+	}
+
+	fn save(&self) {
+		if let Some(file) = &self.file {
+			if let Err(e) = save_ron(file, &self.bans) {
+				error!("save bans: {e:#}");
+			}
+		}
+	}
+
+	/// Forget bans that have expired.
+	fn expire(&mut self, now: u64) {
+		let n = self.bans.len();
+		self.bans.retain(|ban| ban.until.map(|until| until > now).unwrap_or(true));
+		if self.bans.len() != n {
+			self.save();
+		}
+	}
+
+	/// The ban in effect for `ip`, if any.
+	pub fn find(&mut self, ip: IpAddr) -> Option<&Ban> {
+		self.expire(unix_time());
+		self.bans.iter().find(|ban| ban.ip == ip)
+	}
+}
+
+/// Called by the NetServer before a client from `ip` joins.
+/// Returns the reason to refuse the connection, if banned.
+pub(crate) fn check_ban(state: &mut ServerState, ip: IpAddr) -> Option<String> {
+	let ban = state.bans.find(ip)?;
+	Some(match ban.until {
+		None => "you are banned from this server".into(),
+		Some(until) => format!("you are banned from this server for another {}", format_duration(until.saturating_sub(unix_time()))),
+	})
+}
+
+/// `ban <name|ip> [duration]`: ban a player (by name, if connected, or by IP address)
+/// for a duration like `30m`, `2h`, `7d` (default: permanently). Kicks connected players with that IP.
+pub(crate) fn ban_cmd(state: &mut ServerState, caller: Caller, target: &str, duration: Option<&str>) -> Result<()> {
+	let (ip, name) = match player_by_name(state, target) {
+		Ok(id) => (*state.addresses.get(&id).ok_or_else(|| anyhow!("{target} has no known address"))?, must_name(state, id).to_owned()),
+		Err(_) => (target.parse::<IpAddr>().map_err(|_| anyhow!("no such player or IP address: {target}"))?, String::new()),
+	};
+	let duration = duration.map(parse_duration).transpose()?;
+	let until = duration.map(|secs| unix_time().checked_add(secs).ok_or_else(|| anyhow!("ban duration too long"))).transpose()?;
+
+	state.bans.bans.retain(|ban| ban.ip != ip);
+	state.bans.bans.push(Ban { ip, name, until });
+	state.bans.save();
+
+	let reason = match duration {
+		None => "banned".to_owned(),
+		Some(secs) => format!("banned for {}", format_duration(secs)),
+	};
+	for id in state.addresses.iter().filter(|(_, &addr)| addr == ip).map(|(id, _)| *id).collect_vec() {
+		kick(state, id, &reason);
+	}
+	reply(state, caller, format!("{ip} {reason}"));
+	Ok(())
+}
+
+/// `unban <ip|name>`: lift a ban.
+pub(crate) fn unban_cmd(state: &mut ServerState, caller: Caller, target: &str) -> Result<()> {
+	let n = state.bans.bans.len();
+	state.bans.bans.retain(|ban| ban.ip.to_string() != target && !ban.name.eq_ignore_ascii_case(target));
+	if state.bans.bans.len() == n {
+		bail!("no ban for {target}");
+	}
+	state.bans.save();
+	reply(state, caller, format!("unbanned {target}"));
+	Ok(())
+}
+
+/// `bans`: list bans in effect.
+pub(crate) fn bans_cmd(state: &mut ServerState, caller: Caller) {
+	let now = unix_time();
+	state.bans.expire(now);
+	let lines = state
+		.bans
+		.bans
+		.iter()
+		.map(|ban| {
+			let remaining = ban.until.map(|until| format_duration(until.saturating_sub(now))).unwrap_or("permanent".into());
+			format!("{:<40} {:20} {remaining}", ban.ip.to_string(), ban.name)
+		})
+		.collect_vec();
+	match lines.len() {
+		0 => reply(state, caller, "no bans"),
+		_ => reply(state, caller, lines.join("\n")),
+	}
+}
+
+/// Parse a duration like `90s`, `30m`, `2h`, `7d` into seconds.
+/// A plain number means minutes.
+fn parse_duration(s: &str) -> Result<u64> {
+	let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+	let number: u64 = number.parse().map_err(|_| anyhow!("invalid duration: {s}"))?;
+	let unit = match unit {
+		"s" => 1,
+		"" | "m" => 60,
+		"h" => 60 * 60,
+		"d" => 24 * 60 * 60,
+		_ => bail!("invalid duration: {s} (use e.g. 30m, 2h, 7d)"),
+	};
+	number.checked_mul(unit).ok_or_else(|| anyhow!("duration too long: {s}"))
+}
+
+fn format_duration(secs: u64) -> String {
+	match secs {
+		0..=59 => format!("{secs}s"),
+		60..=3599 => format!("{}m", secs / 60),
+		3600..=86399 => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+		_ => format!("{}d{}h", secs / 86400, (secs % 86400) / 3600),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	#[test]
+	fn parse_duration() {
+		assert_eq!(super::parse_duration("90s").unwrap(), 90);
+		assert_eq!(super::parse_duration("30").unwrap(), 30 * 60);
+		assert_eq!(super::parse_duration("2h").unwrap(), 2 * 3600);
+		assert_eq!(super::parse_duration("7d").unwrap(), 7 * 86400);
+		assert!(super::parse_duration("h").is_err());
+		assert!(super::parse_duration("3w").is_err());
+		assert!(super::parse_duration("999999999999999d").is_err());
+	}
+}
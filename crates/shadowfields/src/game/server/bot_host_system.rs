@@ -0,0 +1,329 @@
+//!
+//! Bots hosted by the server: no window, GPU or network connection needed.
+//! They join as regular players, and their `Bot` brain drives them through the same
+//! `ClientMsg`s a remote client would send (see `tick_bots`).
+//!
+//! Configured by the `[bots]` section in `server.toml` (see `BotOpts`).
+//!
+
+use super::internal::*;
+
+/// `[bots]` section in `server.toml`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotOpts {
+	/// Number of bots that are always there.
+	pub count: u32,
+	/// Add bots while there are fewer players than this (humans + bots).
+	/// They leave again as humans join.
+	pub min_players: u32,
+	/// Teams for bots, taken in turn (default: the smallest team, like human players).
+	pub teams: Vec<Team>,
+	/// Skill of bots not listed in `profiles`.
+	pub skill: BotSkill,
+	/// Individual bots, taken in turn (then bots with `skill` and a random name and avatar).
+	pub profiles: Vec<BotProfile>,
+	/// Makes bots (their names, avatars and decisions) the same every time.
+	/// If not set, derived from the server's `seed` (random if that is not set either).
+	pub seed: Option<u64>,
+}
+
+/// A bot in `BotOpts::profiles`, e.g. `{ name = "Rex", skill = "nightmare" }`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotProfile {
+	/// Random if not set (see `BOT_NAMES`).
+	pub name: Option<String>,
+	/// `BotOpts::skill` if not set.
+	pub skill: Option<BotSkill>,
+	/// Random if not set.
+	pub avatar_id: Option<u8>,
+}
+
+impl Default for BotOpts {
+	fn default() -> Self {
+		Self {
+			count: 0,
+			min_players: 0,
+			teams: vec![],
+			skill: default(),
+			profiles: vec![],
+			seed: None,
+		}
+	}
+}
+
+/// Random bot names.
+const BOT_NAMES: &[&str] = &[
+	"Biscuit", "Bramble", "Clover", "Dumpling", "Fizz", "Gizmo", "Hazel", "Juniper", "Kiwi", "Maple", "Mochi", "Nibbles", "Noodle", "Pebble", "Pickle", "Pip", "Pudding", "Quill", "Sprout", "Tofu", "Truffle", "Waffle", "Widget", "Ziggy",
+];
+
+/// Avatars that bots pick from (little animals, witch and wizard).
+const BOT_AVATARS: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 11, 12];
+
+pub(crate) struct ServerBots {
+	opts: BotOpts,
+	/// Picks names, avatars and seeds for new bots, and their weapon sounds.
+	rng: Xoshiro256PlusPlus,
+	/// In order of joining (newest last).
+	hosted: Vec<HostedBot>,
+	/// Sounds played since the bots' last tick (see `bots_hear`).
+	sounds: Vec<SoundEffect>,
+}
+
+impl Default for ServerBots {
+	fn default() -> Self {
+		Self::from_opts(default(), rand::random())
+	}
+}
+
+struct HostedBot {
+	player_id: ID,
+	settings: BotSettings,
+	bot: Bot,
+	/// Buttons held down by the bot, like a client's keyboard.
+	inputs: Inputs,
+	weapon: WeaponState,
+	jump_pad_cooldown: Timer,
+	/// `Player::launched`, which is not part of the frame sent to the server.
+	launched: bool,
+}
+
+impl ServerBots {
+	/// `rng` is the server's, for when `BotOpts::seed` is not set.
+	pub fn from_settings(opts: &ServerOpts, rng: &mut impl Rng) -> Self {
+		Self::from_opts(opts.bots.clone(), rng.gen())
+	}
+
+	fn from_opts(opts: BotOpts, default_seed: u64) -> Self {
+		Self {
+			rng: Xoshiro256PlusPlus::seed_from_u64(opts.seed.unwrap_or(default_seed)),
+			opts,
+			hosted: default(),
+			sounds: default(),
+		}
+	}
+
+	pub fn contains(&self, player_id: ID) -> bool {
+		self.hosted.iter().any(|b| b.player_id == player_id)
+	}
+
+	/// Can there be bots (see `fill_bots`)?
+	/// Unlike checking `hosted`, also true while `tick_bots` has taken them out.
+	fn enabled(&self) -> bool {
+		self.opts.count != 0 || self.opts.min_players != 0
+	}
+}
+
+impl HostedBot {
+	fn new(player_id: ID, settings: BotSettings, seed: u64) -> Self {
+		Self {
+			player_id,
+			bot: Bot::new(&settings, seed),
+			settings,
+			inputs: default(),
+			weapon: default(),
+			jump_pad_cooldown: Timer::one_off_ready(0.5),
+			launched: false,
+		}
+	}
+}
+
+/// Is this player a bot hosted by the server?
+pub(crate) fn is_bot(state: &ServerState, player_id: ID) -> bool {
+	state.bots.contains(player_id)
+}
+
+/// Number of players that are not bots (e.g. for counting votes).
+pub(crate) fn num_humans(state: &ServerState) -> usize {
+	state.entities.players.keys().filter(|&&id| !is_bot(state, id)).count()
+}
+
+pub(crate) fn tick_bots(state: &mut ServerState) {
+	fill_bots(state);
+
+	let sounds = mem::take(&mut state.bots.sounds);
+	let intents = state.bots.hosted.iter().map(|b| b.bot.intent(b.player_id)).collect_vec();
+	let mut hosted = mem::take(&mut state.bots.hosted);
+	for bot in &mut hosted {
+		tick_bot(state, bot, &sounds, &intents);
+	}
+	state.bots.hosted = hosted;
+}
+
+/// Let bots hear a sound on their next tick (see `Bot::perceive`).
+pub(crate) fn bots_hear(state: &mut ServerState, sound: &SoundEffect) {
+	// Called from within `tick_bots` too, e.g. for a bot's gunfire.
+	if state.bots.enabled() && sound.spatial.is_some() {
+		state.bots.sounds.push(sound.clone());
+	}
+}
+
+/// Add or remove a bot (one per tick) until we have as many as `BotOpts` asks for.
+fn fill_bots(state: &mut ServerState) {
+	let opts = &state.bots.opts;
+	let wanted = u32::max(opts.count, opts.min_players.saturating_sub(num_humans(state) as u32)) as usize;
+	let have = state.bots.hosted.len();
+	if have < wanted {
+		add_bot(state);
+	} else if have > wanted {
+		let newest = state.bots.hosted.last().expect("have > 0").player_id;
+		drop_player(state, newest);
+	}
+}
+
+fn add_bot(state: &mut ServerState) {
+	let n = state.bots.hosted.len();
+	let profile = state.bots.opts.profiles.get(n).cloned().unwrap_or_default();
+	let name = profile.name.unwrap_or_else(|| random_bot_name(state));
+	let rng = &mut state.bots.rng;
+	let avatar_id = profile.avatar_id.unwrap_or_else(|| BOT_AVATARS[rng.gen_range(0..BOT_AVATARS.len())]);
+	let seed = rng.gen();
+	let skill = profile.skill.unwrap_or(state.bots.opts.skill);
+	let teams = &state.bots.opts.teams;
+	let join_msg = JoinRequest {
+		name,
+		avatar_id,
+		team: (!teams.is_empty()).then(|| teams[n % teams.len()]),
+		identity_key: String::new(), // no profile
+	};
+	let (player_id, _) = join_new_player(state, join_msg);
+	state.bots.hosted.push(HostedBot::new(player_id, BotSettings::for_skill(skill), seed));
+}
+
+/// A random name from `BOT_NAMES` that is not taken yet (else `Bot1`, `Bot2`, ...).
+fn random_bot_name(state: &mut ServerState) -> String {
+	let free = BOT_NAMES.iter().filter(|name| player_by_name(state, name).is_err()).collect_vec();
+	match free.is_empty() {
+		false => free[state.bots.rng.gen_range(0..free.len())].to_string(),
+		true => (1..).map(|i| format!("Bot{i}")).find(|name| player_by_name(state, name).is_err()).expect("unreachable"),
+	}
+}
+
+/// Called when a player leaves (bots leave via `fill_bots` or get kicked like anyone else).
+pub(crate) fn bot_leave(state: &mut ServerState, player_id: ID) {
+	state.bots.hosted.retain(|b| b.player_id != player_id);
+}
+
+/// Let the bot think, then move and shoot like a client would (see `Client::bot_pre_tick`).
+/// The result goes through `handle_client_msg`, so bots play by the same rules as humans.
+fn tick_bot(state: &mut ServerState, bot: &mut HostedBot, sounds: &[SoundEffect], teammates: &[BotIntent]) -> Option<()> {
+	let dt = state.dt;
+	let mut out = ClientMsgs::new();
+	let mut ctx = BotCtx {
+		map: &state.map,
+		entities: &state.entities,
+		me: player(state, bot.player_id)?.clone(),
+		health: state.health.get(&bot.player_id).map(|h| h.health / state.rules.health).unwrap_or(1.0),
+		sounds,
+		teammates,
+		dt,
+		inputs: &mut bot.inputs,
+		out: &mut out,
+	};
+	bot.bot.tick(&mut ctx);
+	let mut me = ctx.me;
+
+	let mut launched_from = None;
+	if me.spawned && !me.frozen {
+		// shoot based on what the bot saw, before moving.
+		if bot.weapon.tick(dt, &bot.inputs) {
+			shoot((&state.map, &state.entities), &me, &mut state.bots.rng, &mut out);
+		}
+		ride_movers(&state.map, &mut me);
+		me.launched = bot.launched;
+		move_player(&state.map, dt, &bot.inputs, &mut me);
+		me.skeleton.filtered_position = me.skeleton.target_position;
+		if bot.jump_pad_cooldown.tick(dt).is_idle() {
+			launched_from = launch_from_jump_pad(&state.map, &mut me).map(|pad| pad.launch_point());
+			if launched_from.is_some() {
+				bot.jump_pad_cooldown.reset();
+			}
+		}
+		bot.launched = me.launched;
+		out.push(ClientMsg::MovePlayerIfSpawned(me.skeleton.target_frame()));
+	}
+	bot.inputs.tick();
+
+	if let Some(pos) = launched_from {
+		sound_effect(state, handle("fly"), pos, 1.0);
+		add_effect(state, Effect::particle_explosion(pos, me.team.star_texture()));
+	}
+	for msg in out {
+		state.handle_client_msg(bot.player_id, msg);
+	}
+	Some(())
+}
+
+/// Bots forget what they were doing on the previous map.
+pub(crate) fn bot_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::MapSwitched { .. } = event {
+		for bot in &mut state.bots.hosted {
+			bot.bot = Bot::new(&bot.settings, state.bots.rng.gen());
+			bot.inputs.clear();
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::game::server::server_sim::ServerSim;
+
+	fn bots_joined(seed: u64) -> Vec<(String, u8)> {
+		init_test_assets_dir();
+		let opts = ServerOpts {
+			maplist: vec![MapListEntry::named("castle")],
+			bots: BotOpts {
+				count: 3,
+				profiles: vec![BotProfile {
+					name: Some("Rex".into()),
+					skill: Some(BotSkill::Nightmare),
+					avatar_id: Some(11),
+				}],
+				seed: Some(seed),
+				..default()
+			},
+			profiles_file: None,
+			bans_file: None,
+			..default()
+		};
+		let mut state = ServerState::new(opts).unwrap();
+		for _ in 0..5 {
+			fill_bots(&mut state);
+		}
+		state.bots.hosted.iter().map(|b| player(&state, b.player_id).map(|p| (p.name.clone(), p.avatar_id)).unwrap()).collect()
+	}
+
+	#[test]
+	fn profiles() {
+		let bots = bots_joined(1);
+		assert_eq!(bots.len(), 3);
+		assert_eq!(bots[0], ("Rex".into(), 11));
+		for (name, avatar_id) in &bots[1..] {
+			assert!(BOT_NAMES.contains(&name.as_str()));
+			assert!(BOT_AVATARS.contains(avatar_id));
+		}
+		assert_ne!(bots[1].0, bots[2].0);
+		assert_eq!(bots, bots_joined(1), "same seed, same bots");
+	}
+
+	#[test]
+	fn bots_hear_bots() {
+		let mut sim = ServerSim::with_settings(|s| s.bots.count = 2);
+		let is_gunfire = |s: &SoundEffect| ["bang1", "bang2", "bang3", "bang4"].map(handle).contains(&s.clip_name);
+
+		// gunfire happens during `tick_bots`: it must still reach the bots' next tick.
+		let mut heard = vec![];
+		for _ in 0..1200 {
+			sim.tick(1);
+			heard.extend(sim.state.bots.sounds.iter().filter(|s| is_gunfire(s)).cloned());
+			if !heard.is_empty() {
+				break;
+			}
+		}
+		assert_eq!(sim.state.bots.hosted.len(), 2);
+		let shot_from = heard.first().expect("bots heard no gunfire").spatial.as_ref().unwrap().location;
+		assert!(sim.state.bots.hosted.iter().any(|b| sim.player(b.player_id).center().distance_to(shot_from) < 2.0), "shot by a bot");
+	}
+}
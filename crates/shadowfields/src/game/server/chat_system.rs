@@ -0,0 +1,209 @@
+//!
+//! Chat: `say`, `say_team`, `tell` and per-player `mute`.
+//! Messages are rate limited per player and passed through a word filter.
+//! Clients show them in the HUD's chat area (see `ChatMsg`).
+//!
+
+use super::internal::*;
+
+/// Longer messages are truncated.
+const MAX_CHAT_LEN: usize = 200;
+
+pub(crate) struct ChatState {
+	/// Per player: who they don't want to hear from.
+	muted: HashMap<ID, Set<ID>>,
+	/// Per player: number of messages they may send right now (token bucket).
+	/// Missing means a full bucket.
+	budget: HashMap<ID, f32>,
+	/// Bucket size.
+	burst: f32,
+	/// Seconds to earn one more message.
+	interval: f32,
+	pub filter: ChatFilter,
+}
+
+impl ChatState {
+	pub fn from_settings(opts: &ServerOpts) -> Self {
+		Self {
+			muted: default(),
+			budget: default(),
+			burst: opts.chat_burst as f32,
+			interval: opts.chat_interval,
+			filter: ChatFilter::new(&opts.chat_filter),
+		}
+	}
+
+	/// Take one message from the player's budget, if any left.
+	fn try_spend(&mut self, player_id: ID) -> bool {
+		let budget = self.budget.entry(player_id).or_insert(self.burst);
+		if *budget >= 1.0 {
+			*budget -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn refill(&mut self, dt: f32) {
+		let (burst, interval) = (self.burst, self.interval);
+		for budget in self.budget.values_mut() {
+			*budget = f32::min(*budget + dt / interval, burst);
+		}
+		self.budget.retain(|_, budget| *budget < burst);
+	}
+
+	fn is_muted(&self, listener: ID, speaker: ID) -> bool {
+		self.muted.get(&listener).is_some_and(|muted| muted.contains(&speaker))
+	}
+
+	fn forget(&mut self, player_id: ID) {
+		self.muted.remove(&player_id);
+		for muted in self.muted.values_mut() {
+			muted.remove(&player_id);
+		}
+		self.budget.remove(&player_id);
+	}
+}
+
+/// Replaces unwanted words in chat by asterisks.
+/// All chat goes through `ChatFilter::apply`, so this is the place to hook in fancier filtering.
+#[derive(Default)]
+pub(crate) struct ChatFilter {
+	/// Lowercase.
+	words: Set<String>,
+}
+
+impl ChatFilter {
+	pub fn new(words: &[String]) -> Self {
+		Self {
+			words: words.iter().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()).collect(),
+		}
+	}
+
+	/// Replace filtered words (whole words, case-insensitive) by asterisks.
+	pub fn apply(&self, text: &str) -> String {
+		let mut out = String::with_capacity(text.len());
+		for piece in text.split_inclusive(|c: char| !c.is_alphanumeric()) {
+			let word = piece.trim_end_matches(|c: char| !c.is_alphanumeric());
+			match self.words.contains(&word.to_lowercase()) {
+				true => out.extend(word.chars().map(|_| '*')),
+				false => out.push_str(word),
+			}
+			out.push_str(&piece[word.len()..]);
+		}
+		out
+	}
+}
+
+pub(crate) fn tick_chat(state: &mut ServerState) {
+	state.chat.refill(state.dt)
+}
+
+pub(crate) fn chat_leave(state: &mut ServerState, player_id: ID) {
+	state.chat.forget(player_id)
+}
+
+/// `say <message>`: chat to everyone.
+pub(crate) fn say_cmd(state: &mut ServerState, caller: Caller, text: &str) -> Result<()> {
+	let recipients = state.entities.players.keys().copied().collect_vec();
+	send_chat(state, caller, ChatChannel::All, text, recipients)
+}
+
+/// `say_team <message>`: chat to teammates.
+pub(crate) fn say_team_cmd(state: &mut ServerState, player_id: ID, text: &str) -> Result<()> {
+	if state.map.mode != GameMode::TeamDeathmatch {
+		bail!("there are no teams in {:?}", state.map.mode);
+	}
+	let team = player(state, player_id).map(|p| p.team).ok_or(anyhow!("no such player"))?;
+	let recipients = state.entities.players.values().filter(|p| p.team == team).map(|p| p.id).collect_vec();
+	send_chat(state, Caller::Player(player_id), ChatChannel::Team, text, recipients)
+}
+
+/// `tell <name> <message>`: private message.
+pub(crate) fn tell_cmd(state: &mut ServerState, caller: Caller, name: &str, text: &str) -> Result<()> {
+	let to = player_by_name(state, name)?;
+	let recipients = match caller {
+		Caller::Player(from) if from != to => vec![to, from], // echo to the sender
+		_ => vec![to],
+	};
+	send_chat(state, caller, ChatChannel::Private, text, recipients)
+}
+
+/// `mute <name>`: don't show chat from this player (just for the caller).
+pub(crate) fn mute_cmd(state: &mut ServerState, player_id: ID, name: &str) -> Result<()> {
+	let other = player_by_name(state, name)?;
+	if other == player_id {
+		bail!("you can't mute yourself");
+	}
+	state.chat.muted.entry(player_id).or_default().insert(other);
+	reply(state, Caller::Player(player_id), format!("muted {}", must_name(state, other)));
+	Ok(())
+}
+
+/// `unmute <name>`: undo `mute`.
+pub(crate) fn unmute_cmd(state: &mut ServerState, player_id: ID, name: &str) -> Result<()> {
+	let other = player_by_name(state, name)?;
+	if !state.chat.muted.get_mut(&player_id).is_some_and(|muted| muted.remove(&other)) {
+		bail!("{} is not muted", must_name(state, other));
+	}
+	reply(state, Caller::Player(player_id), format!("unmuted {}", must_name(state, other)));
+	Ok(())
+}
+
+/// `mute`: list muted players.
+pub(crate) fn muted_cmd(state: &mut ServerState, player_id: ID) {
+	let muted = state.chat.muted.get(&player_id).into_iter().flatten().map(|&id| must_name(state, id)).join(", ");
+	let msg = match muted.as_str() {
+		"" => "nobody is muted".to_owned(),
+		_ => format!("muted: {muted}"),
+	};
+	reply(state, Caller::Player(player_id), msg);
+}
+
+fn send_chat(state: &mut ServerState, caller: Caller, channel: ChatChannel, text: &str, recipients: Vec<ID>) -> Result<()> {
+	let text = text.trim();
+	if text.is_empty() {
+		bail!("nothing to say");
+	}
+
+	let (from, team, speaker) = match caller {
+		Caller::Player(id) => {
+			if !state.chat.try_spend(id) {
+				bail!("you are sending messages too fast");
+			}
+			let team = (state.map.mode == GameMode::TeamDeathmatch).then(|| player(state, id).map(|p| p.team)).flatten();
+			(must_name(state, id).to_owned(), team, Some(id))
+		}
+		Caller::Operator(_) => ("server".to_owned(), None, None),
+	};
+
+	let text = state.chat.filter.apply(&text.chars().take(MAX_CHAT_LEN).collect::<String>());
+	info!("chat ({channel:?}) {from}: {text}");
+	let msg = ChatMsg { from, team, channel, text, time: unix_time() };
+
+	for id in recipients {
+		if speaker.is_some_and(|speaker| state.chat.is_muted(id, speaker)) {
+			continue;
+		}
+		state.diffs.push(Chat(msg.clone()).to_just(id));
+	}
+
+	// let the operator see what they said (they are not a player, so don't get chat messages).
+	if let Caller::Operator(_) = caller {
+		reply(state, caller, msg.format());
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn filter() {
+		let filter = ChatFilter::new(&["darn".to_owned(), "Heck".to_owned()]);
+		assert_eq!(filter.apply("Darn it, what the heck!"), "**** it, what the ****!");
+		assert_eq!(filter.apply("darned hecks"), "darned hecks");
+		assert_eq!(ChatFilter::default().apply("darn"), "darn");
+	}
+}
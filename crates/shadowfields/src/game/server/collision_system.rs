@@ -1,8 +1,8 @@
 ///!
 ///! Collision system.
 ///!
-///! Tests which players collide with which objects and execute `on_collide` scripts.
-///! TODO: generate collision events, let other systems handle pickups etc.
+///! Tests which players collide with which objects and publishes `GameEvent::EnteredTrigger`.
+///! Other systems handle pickups etc.
 ///!
 use super::internal::*;
 
@@ -31,20 +31,21 @@ pub(crate) fn tick_collisions(state: &mut ServerState) {
 }
 
 fn collide_player(state: &mut ServerState, player_id: ID, collider_id: ID) -> Option<()> {
-	let collider = state.entities.props.get(&collider_id)?.clone();
-
-	let handle = collider.on_collide?;
+	let handle = state.entities.props.get(&collider_id)?.on_collide?;
+	publish(state, GameEvent::EnteredTrigger { player: player_id, prop: collider_id, handle });
+	Some(())
+}
 
-	// TODO: `handle!` macro so we can match on the numerical representation.
-	match handle.as_str() {
-		"shield" => pickup_shield(state, player_id, collider_id),
-		"gift_box" => pickup_gift_box(state, player_id, collider_id),
-		_ => Some(error!("on_collide: no such handle: {handle}")),
+/// Remove a prop that `player_id` picked up (see `GameEvent::PickedUp`).
+/// `None` if someone else was first.
+pub(crate) fn pickup(state: &mut ServerState, player_id: ID, prop_id: ID) -> Option<()> {
+	let prop = state.entities.props.get(&prop_id)?;
+	let event = GameEvent::PickedUp {
+		player: player_id,
+		location: prop.transform.translation,
+		pickup_point: prop.pickup_point_id,
 	};
-
-	if let Some(pickup_point_id) = collider.pickup_point_id {
-		state.map.pickup_points.get_mut(pickup_point_id)?.timer.reset();
-	}
-
+	state.remove_game_object(prop_id);
+	publish(state, event);
 	Some(())
 }
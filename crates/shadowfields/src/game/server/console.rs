@@ -0,0 +1,186 @@
+use super::internal::*;
+use std::io::BufRead;
+use std::sync::mpsc::TrySendError;
+
+/// Maximum number of replies queued for an RCON client that does not read them.
+/// Beyond this, the client is disconnected (rather than blocking the game).
+const RCON_SEND_QUEUE: usize = 1024;
+
+/// After a bad password, further connections from the same IP address are refused for this long.
+const RCON_LOCKOUT: Duration = Duration::from_secs(5);
+
+/// Identifies an operator console: the server's stdin, or a remote console (RCON) connection.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub(crate) enum ConsoleID {
+	Stdin,
+	Rcon(u32),
+}
+
+#[derive(Debug)]
+enum ConsoleEvent {
+	Connected(u32, SyncSender<String>),
+	Command(ConsoleID, String),
+	Disconnected(u32),
+}
+
+/// Server operator consoles: commands typed on stdin, or sent over a password-protected TCP port (RCON).
+/// Commands are executed like player commands (see `server_command`), but with operator privileges.
+///
+/// RCON protocol (line-based text, e.g. `nc localhost 3345`):
+///   * client sends the password, server answers `ok` or closes the connection.
+///   * client sends one command per line, server answers with the command output.
+///
+/// After a bad password, the client's IP address is locked out for `RCON_LOCKOUT`.
+///
+pub(crate) struct Consoles {
+	events: Receiver<ConsoleEvent>,
+	/// Replies are queued for a writer thread per client, so a slow client cannot stall the game.
+	rcon_clients: HashMap<u32, SyncSender<String>>,
+}
+
+impl Consoles {
+	pub fn spawn(opts: &ServerOpts) -> Result<Self> {
+		let (send, events) = channel();
+		spawn_stdin_loop(send.clone());
+		if let Some(addr) = &opts.rcon_addr {
+			let password = opts.rcon_password.clone().filter(|p| !p.is_empty()).ok_or(anyhow!("rcon_addr requires an rcon_password"))?;
+			spawn_rcon_listen_loop(addr, password, send)?;
+		}
+		Ok(Self {
+			events,
+			rcon_clients: default(),
+		})
+	}
+
+	/// Execute pending operator commands (non-blocking) and send back their output.
+	pub fn tick(&mut self, state: &mut ServerState) {
+		while let Ok(event) = self.events.try_recv() {
+			match event {
+				ConsoleEvent::Connected(id, replies) => {
+					self.rcon_clients.insert(id, replies);
+				}
+				ConsoleEvent::Disconnected(id) => {
+					self.rcon_clients.remove(&id);
+				}
+				ConsoleEvent::Command(console, cmd) => state.handle_console_command(console, cmd),
+			}
+		}
+
+		for (console, msg) in mem::take(&mut state.console_replies) {
+			self.send(console, &msg)
+		}
+
+		let consoles = [ConsoleID::Stdin].into_iter().chain(self.rcon_clients.keys().map(|&id| ConsoleID::Rcon(id))).collect_vec();
+		for msg in mem::take(&mut state.operator_alerts) {
+			for &console in &consoles {
+				self.send(console, &msg)
+			}
+		}
+	}
+
+	fn send(&mut self, console: ConsoleID, msg: &str) {
+		match console {
+			ConsoleID::Stdin => println!("{msg}"),
+			ConsoleID::Rcon(id) => {
+				if let Some(replies) = self.rcon_clients.get(&id) {
+					match replies.try_send(msg.to_owned()) {
+						Ok(()) => (),
+						Err(TrySendError::Full(_)) => {
+							error!("rcon {id}: not reading replies, disconnecting");
+							self.rcon_clients.remove(&id); // writer thread closes the connection
+						}
+						Err(TrySendError::Disconnected(_)) => {
+							self.rcon_clients.remove(&id);
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+// Read commands from stdin, one per line.
+// Returns silently if there is no stdin (e.g. running as a service).
+fn spawn_stdin_loop(send: Sender<ConsoleEvent>) {
+	thread::spawn(move || {
+		for line in std::io::stdin().lines().map_while(|line| line.ok()) {
+			let line = line.trim();
+			if !line.is_empty() && send.send(ConsoleEvent::Command(ConsoleID::Stdin, line.to_owned())).is_err() {
+				return; // server quit
+			}
+		}
+	});
+}
+
+fn spawn_rcon_listen_loop(address: &str, password: String, send: Sender<ConsoleEvent>) -> Result<()> {
+	let listener = TcpListener::bind(address)?;
+	println!(" RCON on {address}");
+	let lockouts = Arc::new(Mutex::new(HashMap::<IpAddr, Instant>::default()));
+	thread::spawn(move || {
+		for (id, stream) in (1..).zip(listener.incoming()) {
+			match stream {
+				Err(e) => error!("rcon: accept: {e}"),
+				Ok(stream) => {
+					let (send, password, lockouts) = (send.clone(), password.clone(), lockouts.clone());
+					thread::spawn(move || {
+						if let Err(e) = serve_rcon(id, stream, &password, &lockouts, &send) {
+							info!("rcon {id}: {e:#}")
+						}
+						let _ = send.send(ConsoleEvent::Disconnected(id));
+					});
+				}
+			}
+		}
+	});
+	Ok(())
+}
+
+fn serve_rcon(id: u32, mut stream: TcpStream, password: &str, lockouts: &Mutex<HashMap<IpAddr, Instant>>, send: &Sender<ConsoleEvent>) -> Result<()> {
+	let ip = stream.peer_addr()?.ip();
+	info!("rcon {id}: connection from {ip}");
+	let locked_until = lockouts.lock().expect("poisoned").get(&ip).copied();
+	if locked_until.is_some_and(|t| Instant::now() < t) {
+		writeln!(stream, "locked out, try again later")?;
+		bail!("{ip} is locked out");
+	}
+
+	let mut lines = BufReader::new(stream.try_clone()?).lines();
+	let attempt = lines.next().transpose()?.unwrap_or_default();
+	if attempt.trim() != password {
+		let mut lockouts = lockouts.lock().expect("poisoned");
+		let now = Instant::now();
+		lockouts.retain(|_, &mut until| until > now);
+		lockouts.insert(ip, now + RCON_LOCKOUT);
+		drop(lockouts);
+		writeln!(stream, "bad password")?;
+		bail!("bad password");
+	}
+	writeln!(stream, "ok")?;
+
+	let (replies, queue) = sync_channel(RCON_SEND_QUEUE);
+	spawn_rcon_writer(id, stream, queue);
+	send.send(ConsoleEvent::Connected(id, replies))?;
+
+	for line in lines {
+		let line = line?;
+		let line = line.trim();
+		if !line.is_empty() {
+			send.send(ConsoleEvent::Command(ConsoleID::Rcon(id), line.to_owned()))?;
+		}
+	}
+	Ok(())
+}
+
+// Write queued replies to an RCON client, until the game drops the client or the connection breaks.
+fn spawn_rcon_writer(id: u32, mut stream: TcpStream, queue: Receiver<String>) {
+	thread::spawn(move || {
+		for msg in queue {
+			if let Err(e) = writeln!(stream, "{msg}") {
+				info!("rcon {id}: {e}");
+				break;
+			}
+		}
+		// also ends the reading side (`serve_rcon`), if still connected.
+		let _ = stream.shutdown(std::net::Shutdown::Both);
+	});
+}
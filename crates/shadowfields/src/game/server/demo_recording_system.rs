@@ -0,0 +1,119 @@
+//!
+//! Record a demo of every match to `demo_dir` (if configured), for archiving and playback with `play --play-demo`.
+//! Server demos contain everything broadcast to all players, so they play back in spectator view.
+//!
+
+use super::internal::*;
+
+/// Called when the match starts: start a new demo file (and close the previous one, if any).
+pub(crate) fn start_demo_recording(state: &mut ServerState) {
+	finish_demo_recording(state);
+	let Some(dir) = state.demo_dir.clone() else { return };
+	let file = dir.join(format!("match_{}_{}.demo", state.match_log.start_time, state.map.name()));
+	match DemoRecorder::create(&file, None, state.map_switch_msg()) {
+		Ok(demo) => state.demo = Some(demo),
+		Err(e) => error!("record demo {file:?}: {e:#}"),
+	}
+}
+
+/// Record the messages that are about to be sent out, if recording.
+/// Messages addressed to just one player (e.g. their HUD) are private, and not recorded.
+pub(crate) fn record_broadcasts(state: &mut ServerState) {
+	let Some(demo) = &mut state.demo else { return };
+	for envelope in state.diffs.iter().filter(|e| !matches!(e.to, Just(_))) {
+		if let Err(e) = demo.record(DemoEvent::Msg(envelope.msg.clone())) {
+			error!("{e:#}: stopped recording");
+			state.demo = None;
+			return;
+		}
+	}
+}
+
+/// Called when the match ends.
+/// Also records what is about to be sent out (e.g. the deciding frag, the winner),
+/// as the match ends halfway through a tick, before `record_broadcasts` runs.
+pub(crate) fn finish_demo_recording(state: &mut ServerState) {
+	record_broadcasts(state);
+	if let Some(demo) = state.demo.take() {
+		if let Err(e) = demo.finish() {
+			error!("record demo: {e:#}")
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::game::server::server_sim::ServerSim;
+
+	#[test]
+	fn record_match() {
+		init_test_assets_dir();
+		let dir = std::env::temp_dir().join(format!("demo_dir_test_{}", std::process::id()));
+		let opts = ServerOpts {
+			maplist: vec![MapListEntry::named("castle")],
+			demo_dir: Some(dir.to_string_lossy().into()),
+			profiles_file: None,
+			bans_file: None,
+			..default()
+		};
+		let mut state = ServerState::new(opts).unwrap();
+		assert!(state.demo.is_some());
+
+		state.diffs.push(Log("for everyone".into()).to_all());
+		state.diffs.push(Log("private".into()).to_just(ID::new()));
+		state.handle_tick(0.01);
+		end_match(&mut state);
+		assert!(state.demo.is_none());
+
+		let file = read_dir_names(&dir).unwrap().next().unwrap();
+		let demo = Demo::load(&dir.join(file)).unwrap();
+		assert_eq!(demo.header.player_id, None);
+		assert_eq!(demo.header.map_switch.map_name, "castle");
+		let logs = demo.records.iter().filter_map(|r| match &r.event {
+			DemoEvent::Msg(Log(msg)) => Some(msg.as_str()),
+			_ => None,
+		});
+		assert_eq!(logs.collect_vec(), vec!["for everyone"]);
+
+		force_remove(&dir).unwrap();
+	}
+
+	#[test]
+	fn record_until_frag_limit() {
+		let dir = std::env::temp_dir().join(format!("demo_dir_frag_limit_test_{}", std::process::id()));
+		let mut sim = ServerSim::with_settings(|s| {
+			s.frag_limit = 1;
+			s.rules.spawn_invulnerability = 0.0;
+			s.demo_dir = Some(dir.to_string_lossy().into());
+		});
+		let alice = sim.join("alice", None);
+		let bob = sim.join("bob", None);
+		sim.tick(1);
+		for _ in 0..100 {
+			if !sim.player(bob).spawned {
+				break;
+			}
+			sim.send(alice, ClientMsg::HitPlayer(bob));
+			sim.tick(1);
+		}
+		assert_eq!(sim.state.match_state.phase, MatchPhase::Intermission);
+		assert!(sim.state.demo.is_none());
+
+		// the match ended in the same tick as the deciding frag: both are in the demo.
+		let file = read_dir_names(&dir).unwrap().next().unwrap();
+		let demo = Demo::load(&dir.join(file)).unwrap();
+		let msgs = demo
+			.records
+			.iter()
+			.filter_map(|r| match &r.event {
+				DemoEvent::Msg(msg) => Some(msg),
+				_ => None,
+			})
+			.collect_vec();
+		assert!(msgs.iter().any(|msg| matches!(msg, Log(log) if log.starts_with("alice ") && log.ends_with(" bob"))));
+		assert!(msgs.iter().any(|msg| matches!(msg, UpdateHUD(hud) if hud.text.ends_with(" wins!"))));
+
+		force_remove(&dir).unwrap();
+	}
+}
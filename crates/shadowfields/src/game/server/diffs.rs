@@ -8,6 +8,10 @@ impl Diffs {
 		self.0.push(msg)
 	}
 
+	pub fn iter(&self) -> impl Iterator<Item = &Envelope<ServerMsg>> {
+		self.0.iter()
+	}
+
 	pub fn into_iter(self) -> impl Iterator<Item = Envelope<ServerMsg>> {
 		self.0.into_iter()
 	}
@@ -0,0 +1,88 @@
+//!
+//! Game events (kills, pickups, ...).
+//!
+//! Systems `publish` what happened to `ServerState::events`,
+//! `dispatch_events` then hands each event to all consumers (scores, sprees, announcer, match log, ...).
+//! This way, e.g. killing a player does not need to know about everything that depends on it.
+//!
+
+use super::internal::*;
+
+#[derive(Clone, Debug)]
+pub(crate) enum GameEvent {
+	/// `killer` fragged `victim`: an enemy, or a teammate (`teamkill`, only with friendly fire).
+	Killed { killer: ID, victim: ID, how: String, teamkill: bool },
+	/// `victim` died by their own fault (fell off the world, shot themselves, ...).
+	Suicided { victim: ID, how: String },
+	/// `player` touched a prop with an `on_collide` handle (e.g. a pickup).
+	/// Published every tick for as long as they touch.
+	EnteredTrigger { player: ID, prop: ID, handle: Handle },
+	/// `player` picked up an item (and the prop has been removed).
+	PickedUp { player: ID, location: vec3, pickup_point: Option<usize> },
+	/// `player` (re-)spawned.
+	Spawned { player: ID },
+	/// Everybody was moved to a new map.
+	MapSwitched { map_name: String },
+}
+
+/// Consumers may publish more events in response,
+/// but more than this many rounds means they keep triggering each other.
+const MAX_ROUNDS: u32 = 8;
+
+pub(crate) fn publish(state: &mut ServerState, event: GameEvent) {
+	state.events.push(event)
+}
+
+/// Hand all published events to their consumers (in publication order).
+/// Called after every tick, client message and command.
+pub(crate) fn dispatch_events(state: &mut ServerState) {
+	for _ in 0..MAX_ROUNDS {
+		let events = mem::take(&mut state.events);
+		if events.is_empty() {
+			return;
+		}
+		for event in &events {
+			trace!("event: {event:?}");
+			pickup_shield_event(state, event);
+			pickup_powerup_event(state, event);
+			pickup_point_event(state, event);
+			script_event(state, event);
+			spawn_health_event(state, event);
+			bot_event(state, event);
+			score_event(state, event);
+			spree_event(state, event);
+			match_log_event(state, event);
+			announce_event(state, event);
+		}
+	}
+	error!("dispatch_events: giving up after {MAX_ROUNDS} rounds, dropping {} events", state.events.len());
+	state.events.clear();
+}
+
+/// Log, HUD and sound announcements for events.
+fn announce_event(state: &mut ServerState, event: &GameEvent) {
+	use GameEvent::*;
+	if let Killed { killer, victim, .. } = *event {
+		state.diffs.push(KilledBy(killer).to_just(victim));
+	}
+	match *event {
+		Killed { killer, victim, teamkill: false, .. } => {
+			log(state, format!("{} confettied {}", must_name(state, killer), must_name(state, victim)));
+			hud_announce(state, Just(killer), format!("You confettied {}", must_name(state, victim)));
+			hud_announce(state, Just(victim), format!("You got confettied by {}", must_name(state, killer)));
+		}
+		Killed { killer, victim, teamkill: true, .. } => {
+			log(state, format!("{} confettied teammate {}", must_name(state, killer), must_name(state, victim)));
+			hud_announce(state, Just(killer), format!("You confettied your teammate {}", must_name(state, victim)));
+			hud_announce(state, Just(victim), format!("You got confettied by your teammate {}", must_name(state, killer)));
+		}
+		Suicided { victim, ref how } => {
+			log(state, format!("{} {}", must_name(state, victim), how));
+			hud_announce(state, Just(victim), format!("You {}", how));
+			sound_announce(state, Just(victim), handle("ann_be_careful"));
+		}
+		PickedUp { location, .. } => sound_effect(state, handle("xmas_hat"), location, 1.0),
+		MapSwitched { ref map_name } => hud_announce(state, All, map_name.clone()),
+		EnteredTrigger { .. } | Spawned { .. } => (),
+	}
+}
@@ -4,69 +4,72 @@ use super::internal::*;
 pub(crate) struct Health {
 	pub health: f32,
 	pub shield: f32,
-
-	pub target_shield: f32,
-	pub shield_recharge_rate: f32,
-	pub shield_discharge_rate: f32,
 	pub invulnerability_ttl: Option<f32>,
-
-	pub advantage: bool,
-	// max shield, recharge rate, ...
 }
 
 impl Health {
-	pub fn reset(&mut self) {
-		self.health = 100.0;
-		self.shield = 100.0;
-		self.invulnerability_ttl = Some(1.0);
-	}
-
-	pub fn advantaged() -> Self {
+	pub fn new(rules: &Rules) -> Self {
 		Self {
-			target_shield: 100.0,
-			shield_discharge_rate: 3.0,
-			..Self::default()
+			health: rules.health,
+			shield: 0.0,
+			invulnerability_ttl: None,
 		}
+		.with(|s| s.reset(rules))
 	}
 
-	pub fn is_invulnerable(&self) -> bool {
-		self.invulnerability_ttl.map(|ttl| ttl > 0.0).unwrap_or_default()
+	/// Called on (re-)spawn.
+	pub fn reset(&mut self, rules: &Rules) {
+		self.health = rules.health;
+		self.shield = rules.spawn_shield;
+		self.invulnerability_ttl = Some(rules.spawn_invulnerability);
 	}
-}
 
-impl Default for Health {
-	fn default() -> Self {
-		Self {
-			health: 100.0,
-			shield: 0.0,
-			target_shield: 0.0,
-			shield_recharge_rate: 10.0,
-			shield_discharge_rate: 5.0,
-			invulnerability_ttl: None,
-			advantage: false,
+	/// Subtract damage from shield first, then health. Returns true if this killed the player.
+	pub fn take_damage(&mut self, damage: f32) -> bool {
+		let mut damage = select(self.is_invulnerable(), 0.0, damage);
+
+		if self.shield > 0.0 {
+			let shield_damage = f32::min(damage, self.shield);
+			self.shield -= shield_damage;
+			damage -= shield_damage;
+			if self.shield <= 0.0 {
+				// shield break effect
+			}
 		}
-		.with(|s| s.reset())
+
+		let health_damage = f32::min(self.health, damage);
+		self.health -= health_damage;
+		self.health <= 0.1
+	}
+
+	pub fn is_invulnerable(&self) -> bool {
+		self.invulnerability_ttl.map(|ttl| ttl > 0.0).unwrap_or_default()
 	}
 }
 
 pub(crate) fn tick_health(state: &mut ServerState) {
 	let dt = state.dt;
-	for (_id, health) in &mut state.health {
+	let rules = &state.rules;
+	for (id, health) in &mut state.health {
+		// shield discharges to 0, or recharges for advantaged players.
+		let advantaged = state.entities.players.get(id).is_some_and(|p| p.powerups.has(PowerupKind::Advantage));
+		let target_shield = select(advantaged, rules.advantage_shield, 0.0);
+
 		match &mut health.invulnerability_ttl {
 			None => (),
 			Some(ttl) if *ttl >= 0.0 => *ttl -= state.dt,
 			Some(_) => health.invulnerability_ttl = None,
 		}
 
-		if health.shield < health.target_shield {
-			health.shield += dt * health.shield_recharge_rate
+		if health.shield < target_shield {
+			health.shield += dt * rules.shield_recharge_rate
 		}
 
-		if health.shield > health.target_shield {
-			health.shield -= dt * health.shield_discharge_rate
+		if health.shield > target_shield {
+			health.shield -= dt * rules.shield_discharge_rate
 		}
 
-		health.shield = health.shield.clamp(0.0, 500.0 /*max*/);
+		health.shield = health.shield.clamp(0.0, rules.max_shield);
 	}
 }
 
@@ -79,33 +82,62 @@ pub(crate) fn tick_health(state: &mut ServerState) {
 // count as a hit regardless of latency.
 // Otherwise players with more than about 30ms latency would be at a noticeable disadvantage.
 pub(crate) fn handle_hit_player(state: &mut ServerState, actor: ID, victim: ID) {
-	shoot_player(state, actor, victim, 110.0);
+	if state.match_state.phase == MatchPhase::Intermission {
+		return; // ignore shots fired just before the match ended
+	}
+	state.scores.by_player(actor).hits += 1;
+	let damage = state.rules.weapon_damage;
+	shoot_player(state, actor, victim, damage);
 }
 
 pub(crate) fn shoot_player(state: &mut ServerState, actor: ID, victim: ID, damage: f32) -> Option<()> {
 	trace!("{actor} hit {victim}");
 
-	let h = state.health.entry(victim).or_default();
+	// Friendly fire and self-damage rules decide who gets hurt, and how much.
+	let rules = &state.rules;
+	let friendly = actor != victim && !state.map.mode.are_enemies(player(state, actor)?, player(state, victim)?);
+	let (victim, damage) = match (actor == victim, friendly, rules.friendly_fire) {
+		(true, _, _) => (victim, damage * rules.self_damage),
+		(false, true, FriendlyFire::Off) => return None,
+		(false, true, FriendlyFire::On) => (victim, damage * rules.team_damage),
+		(false, true, FriendlyFire::Reflect) => (actor, damage * rules.team_damage),
+		(false, false, _) => (victim, damage),
+	};
+	let damage = damage * rules.damage_multiplier * player(state, actor)?.powerups.damage_multiplier();
 
-	let mut damage = select(h.is_invulnerable(), 0.0, damage);
+	let h = state.health.entry(victim).or_insert_with(|| Health::new(rules));
+	let dead = h.take_damage(damage);
 
-	if h.shield > 0.0 {
-		let shield_damage = f32::min(damage, h.shield);
-		h.shield -= shield_damage;
-		damage -= shield_damage;
-		if h.shield <= 0.0 {
-			// shield break effect
-		}
-	}
+	match (dead, actor == victim) {
+		(true, false) => active_kill(state, actor, victim, "shot"),
+		(true, true) => suicide(state, victim, select(friendly, "got burned by friendly fire", "self-confettied")),
+		(false, false) => apply_impulse(state, actor, victim),
+		(false, true) => None,
+	};
 
-	let health_damage = f32::min(h.health, damage);
-	h.health -= health_damage;
+	Some(())
+}
 
-	match h.health <= 0.1 {
-		true => active_kill(state, actor, victim),
-		false => apply_impulse(state, actor, victim),
-	};
+/// Damage not caused by another player (e.g. by scripts). Dying counts as a suicide.
+pub(crate) fn damage_player(state: &mut ServerState, victim: ID, damage: f32) -> Option<()> {
+	if !player(state, victim)?.spawned {
+		return None;
+	}
+	let rules = &state.rules;
+	if state.health.entry(victim).or_insert_with(|| Health::new(rules)).take_damage(damage) {
+		suicide(state, victim, "got confettied");
+	}
+	Some(())
+}
 
+/// Restore health, up to the spawn health (see `Rules`).
+pub(crate) fn heal_player(state: &mut ServerState, player_id: ID, amount: f32) -> Option<()> {
+	if !player(state, player_id)?.spawned {
+		return None;
+	}
+	let rules = &state.rules;
+	let h = state.health.entry(player_id).or_insert_with(|| Health::new(rules));
+	h.health = f32::min(h.health + amount, rules.health);
 	Some(())
 }
 
@@ -121,14 +153,31 @@ fn apply_impulse(state: &mut ServerState, actor: ID, victim: ID) -> Option<()> {
 	Some(())
 }
 
-pub(crate) fn pickup_shield(state: &mut ServerState, player_id: ID, collider_id: ID) -> Option<()> {
-	let collider = state.entities.props.get(&collider_id)?;
-	let location = collider.transform.translation;
+/// Spawning restores full health (see `Rules`).
+pub(crate) fn spawn_health_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::Spawned { player } = *event {
+		let rules = &state.rules;
+		state.health.entry(player).or_insert_with(|| Health::new(rules)).reset(rules);
+	}
+}
 
-	sound_effect(state, handle("xmas_hat"), location, 1.0);
-	state.remove_game_object(collider_id);
-	let powerups = state.health.entry(player_id).or_default();
-	powerups.shield += 100.0;
+/// Pick up shields and gift boxes when touching them.
+pub(crate) fn pickup_shield_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::EnteredTrigger { player, prop, handle } = *event {
+		// TODO: `handle!` macro so we can match on the numerical representation.
+		match handle.as_str() {
+			"shield" => pickup_shield(state, player, prop),
+			"gift_box" => pickup_gift_box(state, player, prop),
+			_ => None,
+		};
+	}
+}
+
+fn pickup_shield(state: &mut ServerState, player_id: ID, collider_id: ID) -> Option<()> {
+	pickup(state, player_id, collider_id)?;
+	let rules = &state.rules;
+	let powerups = state.health.entry(player_id).or_insert_with(|| Health::new(rules));
+	powerups.shield = f32::min(powerups.shield + 100.0, rules.max_shield);
 	let shield = powerups.shield;
 	let msg = format!("{:03.0}% shield", shield);
 	hud_announce(state, Just(player_id), msg);
@@ -136,16 +185,9 @@ pub(crate) fn pickup_shield(state: &mut ServerState, player_id: ID, collider_id:
 	Some(())
 }
 
-pub(crate) fn pickup_gift_box(state: &mut ServerState, player_id: ID, collider_id: ID) -> Option<()> {
-	let collider = state.entities.props.get(&collider_id)?;
-	let location = collider.transform.translation;
-
-	sound_effect(state, handle("xmas_hat"), location, 1.0);
-	state.remove_game_object(collider_id);
-	//let powerups = state.health.entry(player_id).or_default();
-	//powerups.shield += 100.0;
-	//let msg = format!("{}% shield", powerups.shield);
-	//hud_announce(state, Just(player_id), msg);
-
-	Some(())
+fn pickup_gift_box(state: &mut ServerState, player_id: ID, collider_id: ID) -> Option<()> {
+	pickup(state, player_id, collider_id)?;
+	// surprise!
+	let kind = *pick_random_with(&mut state.rng, &PowerupKind::PICKUPS)?;
+	give_powerup(state, player_id, kind)
 }
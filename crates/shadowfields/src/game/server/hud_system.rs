@@ -24,8 +24,14 @@ fn hud_health_text(state: &ServerState, id: ID) -> String {
 
 	if let Some(h) = state.health.get(&id) {
 		let invuln = select(h.is_invulnerable(), "*", " ");
-		let adv = select(h.advantage, "+", " ");
-		format!("{adv}{FONT_SHIELD}{:03.0}\n{invuln}{FONT_HEART}{:03.0}", h.shield, h.health)
+		let mut text = format!(" {FONT_SHIELD}{:03.0}\n{invuln}{FONT_HEART}{:03.0}", h.shield, h.health);
+		for (kind, ttl) in state.entities.players.get(&id).map(|p| p.powerups.iter().collect_vec()).unwrap_or_default() {
+			match ttl.is_finite() {
+				true => text += &format!("\n {kind} {:.0}", ttl.ceil()),
+				false => text += &format!("\n {kind}"),
+			}
+		}
+		text
 	} else {
 		String::new()
 	}
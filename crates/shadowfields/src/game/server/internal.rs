@@ -3,13 +3,27 @@ pub use crate::prelude::*;
 pub use super::super::internal::*;
 
 pub(crate) use super::autoswitch_system::*;
+pub(crate) use super::ban_system::*;
+pub(crate) use super::bot_host_system::*;
+pub(crate) use super::chat_system::*;
 pub(crate) use super::collision_system::*;
+pub(crate) use super::console::*;
+pub(crate) use super::demo_recording_system::*;
+pub(crate) use super::event_system::*;
 pub(crate) use super::health_system::*;
 pub(crate) use super::hud_system::*;
 pub(crate) use super::killplane_system::*;
 pub(crate) use super::mapswitch_system::*;
+pub(crate) use super::match_system::*;
+pub(crate) use super::mover_system::*;
+pub(crate) use super::powerup_system::*;
+pub(crate) use super::profile_system::*;
+pub(crate) use super::report_system::*;
 pub(crate) use super::score_system::*;
+pub(crate) use super::script_system::*;
 pub(crate) use super::server_commands::*;
 pub(crate) use super::spree_system::*;
+pub(crate) use super::team_system::*;
+pub(crate) use super::vote_system::*;
 pub(crate) use Addressee::*;
 pub(crate) use ServerMsg::*;
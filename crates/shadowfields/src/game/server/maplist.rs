@@ -0,0 +1,104 @@
+use super::internal::*;
+use serde::Deserializer;
+
+/// An entry in the server's maplist: a map name,
+/// optionally overriding server-wide settings for that map.
+///
+/// In `server.toml`, either just a name or a table. E.g.:
+///
+///    maplist = ["deck", { name = "pit", frag_limit = 10, mode = "deathmatch", max_players = 4 }]
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MapListEntry {
+	pub name: String,
+
+	pub frag_limit: Option<u32>,
+	pub time_limit: Option<u32>,
+	pub mode: Option<GameMode>,
+	pub gravity: Option<f32>,
+	/// Teams playing on this map (e.g. `["red", "blue"]`).
+	pub teams: Option<Vec<Team>>,
+
+	/// Only used by `RotationPolicy::PlayerCount`.
+	pub min_players: Option<usize>,
+	pub max_players: Option<usize>,
+}
+
+/// How the server picks the next map from the maplist.
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationPolicy {
+	/// Play maps in maplist order.
+	#[default]
+	Sequential,
+	/// Play all maps in random order before repeating any.
+	Shuffle,
+	/// Next map (in maplist order) whose `min_players`, `max_players` fit the current number of players.
+	PlayerCount,
+}
+
+impl MapListEntry {
+	/// Entry without overrides.
+	pub fn named(name: impl Into<String>) -> Self {
+		Self { name: name.into(), ..default() }
+	}
+
+	pub fn fits_player_count(&self, num_players: usize) -> bool {
+		self.min_players.map(|min| num_players >= min).unwrap_or(true) && self.max_players.map(|max| num_players <= max).unwrap_or(true)
+	}
+}
+
+impl FromStr for MapListEntry {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		Ok(Self::named(s))
+	}
+}
+
+impl FromStr for RotationPolicy {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		use RotationPolicy::*;
+		match s {
+			"sequential" => Ok(Sequential),
+			"shuffle" => Ok(Shuffle),
+			"player_count" => Ok(PlayerCount),
+			bad => Err(anyhow!("unknown rotation policy `{}`, options: `sequential`, `shuffle`, `player_count`", bad)),
+		}
+	}
+}
+
+/// Deserialize a maplist where each entry is either a map name or a `MapListEntry` table.
+pub(crate) fn deserialize_maplist<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<MapListEntry>, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum NameOrEntry {
+		Name(String),
+		Entry(MapListEntry),
+	}
+
+	Ok(Vec::<NameOrEntry>::deserialize(deserializer)?
+		.into_iter()
+		.map(|e| match e {
+			NameOrEntry::Name(name) => MapListEntry::named(name),
+			NameOrEntry::Entry(entry) => entry,
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse_maplist() {
+		let opts: ServerOpts = toml::from_str(r#"maplist = ["deck", { name = "pit", frag_limit = 10, mode = "deathmatch" }]"#).unwrap();
+		assert_eq!(opts.maplist[0].name, "deck");
+		assert_eq!(opts.maplist[0].frag_limit, None);
+		assert_eq!(opts.maplist[1].name, "pit");
+		assert_eq!(opts.maplist[1].frag_limit, Some(10));
+		assert_eq!(opts.maplist[1].mode, Some(GameMode::Deathmatch));
+	}
+}
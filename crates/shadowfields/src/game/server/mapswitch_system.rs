@@ -4,16 +4,93 @@
 
 use super::internal::*;
 
+/// Which map to play next, and with which settings.
+pub(crate) struct MapRotation {
+	pub maplist: Vec<MapListEntry>,
+	pub policy: RotationPolicy,
+
+	// Server-wide settings, unless overridden by the maplist entry.
+	pub frag_limit: u32,
+	pub time_limit: u32,
+	pub mode: GameMode,
+	pub teams: Vec<Team>,
+
+	// `RotationPolicy::Shuffle`: maps (indices into maplist) not yet played this round.
+	shuffle_bag: Vec<usize>,
+}
+
+impl MapRotation {
+	pub fn from_settings(settings: &ServerOpts) -> Result<Self> {
+		Ok(Self::new(settings, load_maplist(settings)?))
+	}
+
+	/// Like `from_settings`, but with a given maplist (not verified).
+	pub fn new(settings: &ServerOpts, maplist: Vec<MapListEntry>) -> Self {
+		Self {
+			maplist,
+			policy: settings.rotation,
+			frag_limit: settings.frag_limit,
+			time_limit: settings.time_limit,
+			mode: settings.mode,
+			teams: match settings.teams.len() {
+				0 => all_teams(),
+				_ => settings.teams.clone(),
+			},
+			shuffle_bag: default(),
+		}
+	}
+
+	/// Maplist entry for a map name, if present.
+	pub fn entry(&self, map_name: &str) -> Option<&MapListEntry> {
+		self.maplist.iter().find(|e| e.name == map_name)
+	}
+
+	pub fn map_names(&self) -> impl Iterator<Item = &str> {
+		self.maplist.iter().map(|e| e.name.as_str())
+	}
+
+	/// Can the map be picked with the current number of players?
+	/// (Player counts only matter for `RotationPolicy::PlayerCount`).
+	pub fn eligible(&self, entry: &MapListEntry, num_players: usize) -> bool {
+		self.policy != RotationPolicy::PlayerCount || entry.fits_player_count(num_players)
+	}
+
+	/// Pick the map to play after `curr`, according to the rotation policy.
+	pub fn next_map(&mut self, curr: &str, num_players: usize, rng: &mut impl Rng) -> Option<String> {
+		let curr = self.maplist.iter().position(|e| e.name == curr).unwrap_or(0);
+		let n = self.maplist.len();
+		let sequential = (curr + 1) % n.max(1);
+
+		let next = match self.policy {
+			RotationPolicy::Sequential => sequential,
+			RotationPolicy::Shuffle => {
+				if self.shuffle_bag.is_empty() {
+					// new round, but don't play the current map twice in a row
+					self.shuffle_bag = shuffled_with(rng, (0..n).filter(|&i| i != curr || n == 1).collect());
+				}
+				self.shuffle_bag.pop().unwrap_or(sequential)
+			}
+			RotationPolicy::PlayerCount => (1..=n) //
+				.map(|i| (curr + i) % n)
+				.find(|&i| self.maplist[i].fits_player_count(num_players))
+				.unwrap_or(sequential),
+		};
+
+		self.maplist.get(next).map(|e| e.name.clone())
+	}
+}
+
 /// Take the maplist from server settings.
 /// Use all maps (except test maps) if no list specified.
 /// Verify maps by loading them once, so that we get no unexpected errors later.
-pub(crate) fn load_maplist(settings: &ServerOpts) -> Result<Vec<String>> {
-	println!("server: maplist: {}", settings.maplist.join(", "));
+fn load_maplist(settings: &ServerOpts) -> Result<Vec<MapListEntry>> {
+	println!("server: maplist: {}", settings.maplist.iter().map(|e| e.name.as_str()).join(", "));
 	let maplist = match settings.maplist.len() {
 		0 => assets_dir()
 			.find_all_maps()?
 			.into_iter()
 			.filter(|name| !(name.starts_with("test") || name.starts_with("fail")))
+			.map(MapListEntry::named)
 			.collect_vec(),
 		_ => settings.maplist.clone(),
 	};
@@ -32,47 +109,48 @@ pub(crate) fn switch_map(state: &mut ServerState, map_name: &str) {
 			for id in ids(&state.entities.players) {
 				despawn(state, id);
 			}
-			// announce_winner(state);
 			//state.world.switch_map(&mut state.diffs, new_map);
 
-			let map_name = new_map.name().to_string();
 			let mut tmp = new_map;
 			mem::swap(&mut state.map, &mut tmp);
 			drop(tmp /*now the old map*/);
+			apply_map_settings(state);
+			reassign_inactive_teams(state);
+			load_scripts(state);
 
 			// Keep players, remove other entities
 
 			state.entities.props.clear();
 
-			state.diffs.push(
-				SwitchMap(MapSwitch {
-					map_name,
-					entities: state.entities.clone(),
-				})
-				.to_all(),
-			);
+			state.diffs.push(SwitchMap(state.map_switch_msg()).to_all());
 
 			state.autoswitch.time_playing = 0.0;
 			state.scores.reset(state.entities.players.keys().copied());
 			broadcast_scores(state);
-			hud_announce(state, All, state.map.name().to_owned());
+			begin_warmup(state);
+			publish(state, GameEvent::MapSwitched { map_name: state.map.name().to_owned() });
 		}
 	}
 }
 
-/// Switch to the next map in state.maplist.
+/// Apply the current map's maplist overrides (if any) on top of the server-wide settings.
+pub(crate) fn apply_map_settings(state: &mut ServerState) {
+	let rotation = &state.rotation;
+	let entry = rotation.entry(state.map.name()).cloned().unwrap_or_default();
+	state.autoswitch.frag_limit = entry.frag_limit.unwrap_or(rotation.frag_limit) as i32;
+	state.autoswitch.time_limit = entry.time_limit.unwrap_or(rotation.time_limit) as f32;
+	state.map.mode = entry.mode.unwrap_or(rotation.mode);
+	state.map.gravity = entry.gravity.unwrap_or(STD_GRAVITY);
+	state.teams.active = entry.teams.filter(|teams| !teams.is_empty()).unwrap_or(rotation.teams.clone());
+}
+
+/// Switch to the next map, according to the rotation policy.
 pub(crate) fn switch_next_map(state: &mut ServerState) {
 	info!("switching to next map");
 
-	let curr = state.maplist.iter().position(|map| map == state.map.name()).unwrap_or(0);
-
-	let next = match (curr + 1) == state.maplist.len() {
-		true => 0,
-		false => curr + 1,
-	};
-
-	if let Some(next_name) = state.maplist.get(next) {
-		switch_map(state, &next_name.clone())
+	let num_players = state.entities.players.len();
+	if let Some(next_name) = state.rotation.next_map(state.map.name(), num_players, &mut state.rng) {
+		switch_map(state, &next_name)
 	}
 }
 
@@ -81,13 +159,13 @@ pub(crate) fn broadcast_error(state: &mut ServerState, err: Error) {
 	state.diffs.push(Log(format!("{err}")).to_all());
 }
 
-fn verify_maps(maplist: &[String]) -> Result<()> {
+fn verify_maps(maplist: &[MapListEntry]) -> Result<()> {
 	if maplist.is_empty() {
 		return Err(anyhow!("server: maplist: need at least one map"));
 	}
-	for map_name in maplist {
-		if let Err(e) = Map::load(map_name) {
-			return Err(anyhow!("map {} failed verification: {:#}", map_name, e));
+	for entry in maplist {
+		if let Err(e) = Map::load(&entry.name) {
+			return Err(anyhow!("map {} failed verification: {:#}", entry.name, e));
 		}
 	}
 	Ok(())
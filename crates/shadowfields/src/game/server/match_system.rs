@@ -0,0 +1,160 @@
+//!
+//! Match phases: warmup (optionally waiting for players to be ready), playing, intermission.
+//!
+
+use super::internal::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchPhase {
+	/// Players can frag around, but scores will be reset when the match starts.
+	Warmup,
+	/// Scores count, frag and time limits apply (see `AutoSwitch`).
+	Playing,
+	/// The match is over: players are frozen and the final scoreboard is shown.
+	Intermission,
+}
+
+pub(crate) struct MatchState {
+	pub phase: MatchPhase,
+	/// Seconds since the current phase started.
+	pub phase_time: f32,
+	/// Players who typed `ready` during warmup.
+	pub ready: Set<ID>,
+
+	pub warmup_time: f32,
+	pub ready_up: bool,
+	pub intermission_time: f32,
+}
+
+impl MatchState {
+	/// Starts out in warmup, where scores don't count. `begin_warmup` decides whether to stay there.
+	pub fn from_settings(settings: &ServerOpts) -> Self {
+		Self {
+			phase: MatchPhase::Warmup,
+			phase_time: 0.0,
+			ready: default(),
+			warmup_time: settings.warmup_time as f32,
+			ready_up: settings.ready_up,
+			intermission_time: settings.intermission_time as f32,
+		}
+	}
+
+	fn has_warmup(&self) -> bool {
+		self.ready_up || self.warmup_time > 0.0
+	}
+
+	fn enter(&mut self, phase: MatchPhase) {
+		trace!("match phase: {phase:?}");
+		self.phase = phase;
+		self.phase_time = 0.0;
+	}
+}
+
+pub(crate) fn tick_match(state: &mut ServerState) {
+	state.match_state.phase_time += state.dt;
+	match state.match_state.phase {
+		MatchPhase::Warmup => tick_warmup(state),
+		MatchPhase::Playing => tick_autoswitch(state),
+		MatchPhase::Intermission => tick_intermission(state),
+	}
+}
+
+fn tick_warmup(state: &mut ServerState) {
+	let m = &state.match_state;
+	let time_up = m.warmup_time > 0.0 && m.phase_time >= m.warmup_time;
+	let all_ready = m.ready_up && num_humans(state) != 0 && state.entities.players.keys().all(|&id| m.ready.contains(&id) || is_bot(state, id));
+
+	if time_up || all_ready {
+		start_match(state);
+		hud_announce(state, All, "Fight!");
+	} else if (m.phase_time - state.dt) as i32 != m.phase_time as i32 {
+		// broadcast every second so the timer ticks down
+		broadcast_warmup_status(state);
+	}
+}
+
+fn tick_intermission(state: &mut ServerState) {
+	if state.match_state.phase_time >= state.match_state.intermission_time {
+		match finish_map_vote(state) {
+			Some(map_name) => switch_map(state, &map_name),
+			None => switch_next_map(state),
+		}
+	}
+}
+
+/// Called after a map switch: warm up, or start playing right away if there is no warmup.
+pub(crate) fn begin_warmup(state: &mut ServerState) {
+	state.match_state.ready.clear();
+	freeze_all(state, false);
+	match state.match_state.has_warmup() {
+		true => state.match_state.enter(MatchPhase::Warmup),
+		false => start_match(state),
+	}
+}
+
+/// End of warmup: reset scores and start counting.
+fn start_match(state: &mut ServerState) {
+	info!("match started");
+	state.match_state.enter(MatchPhase::Playing);
+	state.autoswitch.time_playing = 0.0;
+	state.scores.reset(state.entities.players.keys().copied());
+	state.sprees.clear();
+	reset_match_log(state);
+	start_demo_recording(state);
+	broadcast_scores(state);
+	sound_announce(state, All, handle("ann_begin"));
+}
+
+/// Frag or time limit reached: freeze everyone and show the final scoreboard.
+/// Switches to the next map after the intermission.
+pub(crate) fn end_match(state: &mut ServerState) {
+	info!("match ended");
+	state.match_state.enter(MatchPhase::Intermission);
+	freeze_all(state, true);
+	announce_winner(state);
+	write_match_report(state);
+	finish_demo_recording(state);
+	profile_end_match(state);
+	start_map_vote(state);
+}
+
+pub(crate) fn ready_cmd(state: &mut ServerState, player_id: ID) -> Result<()> {
+	if !(state.match_state.ready_up && state.match_state.phase == MatchPhase::Warmup) {
+		bail!("not waiting for players to be ready");
+	}
+	if state.match_state.ready.insert(player_id) {
+		log(state, format!("{} is ready", must_name(state, player_id)));
+		broadcast_warmup_status(state);
+	}
+	Ok(())
+}
+
+fn broadcast_warmup_status(state: &mut ServerState) {
+	let m = &state.match_state;
+	let mut text = String::from("warmup");
+	if m.warmup_time > 0.0 {
+		let sec_remaining = f32::max(0.0, m.warmup_time - m.phase_time) as u32;
+		text += &format!(": {}:{:02}", sec_remaining / 60, sec_remaining % 60);
+	}
+	if m.ready_up {
+		let num_ready = state.entities.players.keys().filter(|id| m.ready.contains(id)).count();
+		text += &format!("\ntype `ready` ({num_ready}/{} ready)", num_humans(state));
+	}
+
+	state.diffs.push(
+		UpdateHUD(HUDUpdate {
+			pos: HUDPos::TopLeft,
+			text,
+			ttl_sec: 3.0,
+		})
+		.to_all(),
+	);
+}
+
+fn freeze_all(state: &mut ServerState, frozen: bool) {
+	for id in ids(&state.entities.players) {
+		if player(state, id).map(|p| p.frozen) != Some(frozen) {
+			state.apply_to_player_partial(id, |p| p.frozen = frozen);
+		}
+	}
+}
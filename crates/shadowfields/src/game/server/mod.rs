@@ -3,24 +3,45 @@
 pub mod internal;
 
 mod autoswitch_system;
+mod ban_system;
+mod bot_host_system;
+mod chat_system;
 mod collision_system;
+mod console;
+mod demo_recording_system;
 mod diffs;
+mod event_system;
 mod health_system;
 mod hud_system;
 mod killplane_system;
 mod lava_system;
+mod maplist;
 mod mapswitch_system;
+mod match_system;
+mod mover_system;
 mod net_server;
 mod pickup_point_system;
+mod powerup_system;
+mod profile_system;
+mod report_system;
+mod rules;
 mod score_system;
+mod script_system;
 mod server_commands;
 mod server_opts;
+#[cfg(test)]
+mod server_sim;
 mod server_state;
 mod spree_system;
+mod team_system;
+mod vote_system;
 
+pub use bot_host_system::*;
 pub use diffs::*;
 pub use lava_system::*;
+pub use maplist::*;
 pub use net_server::*;
 pub use pickup_point_system::*;
+pub use rules::*;
 pub use server_opts::*;
 pub use server_state::*;
@@ -0,0 +1,56 @@
+//!
+//! Mover system animates doors, lifts, platforms,... (see `MoverDef`).
+//! Clients are told whenever a mover changes direction, and move it locally in between.
+//!
+use super::internal::*;
+
+pub(crate) fn tick_movers(state: &mut ServerState) {
+	let dt = state.dt;
+	for i in 0..state.map.movers.len() {
+		let triggered = match state.map.movers[i].def.trigger {
+			MoverTrigger::Proximity { radius } => any_player_near(state, &state.map.movers[i].bounds(), radius),
+			MoverTrigger::Timer => false,
+		};
+
+		let mover = &mut state.map.movers[i];
+		let before = mover.state;
+		mover.tick(dt);
+
+		match mover.def.trigger {
+			MoverTrigger::Proximity { .. } => {
+				if triggered {
+					mover.wait_timer.reset();
+					if !mover.at_end() {
+						mover.state.dir = 1.0;
+					}
+				} else if mover.state.dir >= 0.0 && mover.state.time > 0.0 && mover.wait_timer.tick(dt).is_idle() {
+					mover.state.dir = -1.0;
+				}
+			}
+			MoverTrigger::Timer => {
+				if mover.state.dir == 0.0 && mover.wait_timer.tick(dt).just_finished() {
+					mover.state.dir = if mover.at_end() { -1.0 } else { 1.0 };
+					mover.wait_timer.reset();
+				}
+			}
+		}
+
+		if mover.state.dir != before.dir {
+			state.diffs.push(UpdateMover(i, mover.state).to_all());
+		}
+	}
+}
+
+/// Send the state of all movers to a player who just joined.
+pub(crate) fn sync_movers(state: &mut ServerState, player_id: ID) {
+	for (i, mover) in state.map.movers.iter().enumerate() {
+		state.diffs.push(UpdateMover(i, mover.state).to_just(player_id));
+	}
+}
+
+// Is any spawned player within `radius` of a bounding box?
+fn any_player_near(state: &ServerState, bounds: &BoundingBox32, radius: f32) -> bool {
+	let margin = vec3::repeat(radius);
+	let sensor = BoundingBox32::new(bounds.min - margin, bounds.max + margin);
+	state.entities.players.values().filter(|p| p.spawned).any(|p| p.skeleton.bounds_for(p.skeleton.target_position).overlaps(&sensor))
+}
@@ -11,7 +11,9 @@ use std::sync::mpsc::TryRecvError;
 ///
 pub struct NetServer {
 	listen: Receiver<TcpStream>, // incoming connections are sent here
+	addr: SocketAddr,            // where we listen (differs from `opts.addr` for port 0)
 	clients: HashMap<ID, NetPipe>,
+	consoles: Consoles,
 	tick_duration: Duration,
 
 	state: ServerState,
@@ -26,13 +28,28 @@ impl NetServer {
 		Self::new(opts)?.main_loop()
 	}
 
+	/// Serve in a background thread (e.g. for tests, with `opts.addr` on port 0).
+	/// Returns the address we actually listen on.
+	pub fn spawn(opts: ServerOpts) -> Result<SocketAddr> {
+		let mut server = Self::new(opts)?;
+		let addr = server.addr;
+		thread::spawn(move || {
+			if let Err(e) = server.main_loop() {
+				error!("server {addr}: {e:#}")
+			}
+		});
+		Ok(addr)
+	}
+
 	fn new(opts: ServerOpts) -> Result<Self> {
-		let listen_for_conn = Self::spawn_listen_loop(&opts.addr)?;
+		let (listen_for_conn, addr) = Self::spawn_listen_loop(&opts.addr)?;
 		let tick_duration = Duration::from_millis(8); // TODO
 
 		Ok(Self {
 			listen: listen_for_conn,
+			addr,
 			clients: HashMap::default(),
+			consoles: Consoles::spawn(&opts)?,
 			state: ServerState::new(opts)?,
 			tick_duration,
 		})
@@ -55,13 +72,15 @@ impl NetServer {
 	fn tick(&mut self) -> Result<()> {
 		self.tick_listen()?;
 		self.tick_client_msgs()?;
+		self.consoles.tick(&mut self.state);
 			Self::smooth_players_movement(&mut self.state.entities.players);
 		let diffs = self.state.handle_tick(self.tick_duration.as_secs_f32());
 		self.flush_diffs(diffs);
+		self.tick_kicked();
 		Ok(())
 	}
 
-	fn smooth_players_movement(players: &mut HashMap<ID, Player>){
+	pub(crate) fn smooth_players_movement(players: &mut HashMap<ID, Player>){
 		// Server does not require smooth movement,
 		// but does need to sync filtered position (e.g. for collision detection)
 		for player in players.values_mut(){
@@ -91,12 +110,19 @@ impl NetServer {
 	fn handle_conn_with_result(&mut self, mut tcp_stream: TcpStream) -> Result<()> {
 		// Perform a handshake:
 		//  * Client sends JoinMsg with player info
-		//  * Server sends AcceptMsg with client ID and map to load.
+		//  * Server sends AcceptMsg with client ID and map to load,
+		//    or refuses the connection (e.g. banned IP).
 		let join_msg: JoinRequest = wireformat::deserialize_from(&mut tcp_stream)?;
 		let name = join_msg.name.clone();
-		let (player_id, map_switch) = self.state.handle_join_new_player(join_msg);
-		info!("accepting {:?} ({:?}) as {}", tcp_stream.local_addr().unwrap(), name, player_id);
-		wireformat::serialize_into(&mut tcp_stream, &AcceptedMsg { player_id, map_switch })?;
+		let ip = tcp_stream.peer_addr()?.ip();
+		if let Some(reason) = self.state.handle_check_ban(ip) {
+			info!("refusing {ip} ({name:?}): {reason}");
+			wireformat::serialize_into(&mut tcp_stream, &JoinResponse::Refused(reason))?;
+			return Ok(());
+		}
+		let (player_id, map_switch) = self.state.handle_join_new_player(join_msg, ip);
+		info!("accepting {ip} ({:?}) as {}", name, player_id);
+		wireformat::serialize_into(&mut tcp_stream, &JoinResponse::Accepted(AcceptedMsg { player_id, map_switch }))?;
 		let pipe = NetPipe::new(tcp_stream);
 		assert!(self.clients.insert(player_id, pipe).is_none());
 		Ok(())
@@ -139,6 +165,13 @@ impl NetServer {
 		//self.flush_diffs(diffs); // needed?
 	}
 
+	// Disconnect players kicked by `ServerState` (after they were sent the reason).
+	fn tick_kicked(&mut self) {
+		for id in mem::take(&mut self.state.kicked) {
+			self.handle_drop_client(id)
+		}
+	}
+
 	//____________________________________________________________ communication protocol
 
 	fn flush_diffs(&mut self, diffs: Diffs) {
@@ -176,12 +209,14 @@ impl NetServer {
 
 	// Spawn a loop that accepts incoming TCP connections on `address`,
 	// sends them over a channel for non-blocking access by the server's main thread.
-	fn spawn_listen_loop(address: &str) -> Result<Receiver<TcpStream>> {
+	// Returns the bound address (`address` may have port 0).
+	fn spawn_listen_loop(address: &str) -> Result<(Receiver<TcpStream>, SocketAddr)> {
 		let (send, recv) = channel();
+		let listener = TcpListener::bind(address)?;
+		let address = listener.local_addr()?;
 		println!("------------------------------------");
 		println!(" Listening on {address}");
 		println!("------------------------------------");
-		let listener = TcpListener::bind(address)?;
 		thread::spawn(move || {
 			for stream in listener.incoming() {
 				match stream {
@@ -196,6 +231,6 @@ impl NetServer {
 				}
 			}
 		});
-		Ok(recv)
+		Ok((recv, address))
 	}
 }
@@ -13,7 +13,12 @@ pub(crate) fn tick_pickup_points(state: &mut ServerState) {
 		pickup_point.timer.tick(dt);
 		if pickup_point.timer.just_finished() {
 			log::trace!("pickup point {i}: timer finished");
-			match Prop::from_handle(pickup_point.item){
+			// items can be built-in or scripted props.
+			let prop = match state.scripts.prop(pickup_point.item.as_str()) {
+				Some(prop) => Ok(prop),
+				None => Prop::from_handle(pickup_point.item),
+			};
+			match prop {
 				Ok(prop) => props.push(
 					prop.with(|p| p.transform.translation = pickup_point.pos)
 					.with(|p| p.pickup_point_id = Some(i)),
@@ -26,3 +31,12 @@ pub(crate) fn tick_pickup_points(state: &mut ServerState) {
 		state.spawn_game_object(prop);
 	}
 }
+
+/// Start the timer to re-populate a pickup point after its item was picked up.
+pub(crate) fn pickup_point_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::PickedUp { pickup_point: Some(i), .. } = *event {
+		if let Some(pickup_point) = state.map.pickup_points.get_mut(i) {
+			pickup_point.timer.reset();
+		}
+	}
+}
@@ -0,0 +1,67 @@
+//!
+//! Timed powerups (see `PowerupKind`): pickup, regeneration, expiry.
+//! Damage, movement and rendering consult `Player::powerups` directly.
+//!
+
+use super::internal::*;
+
+/// Seconds before expiry to warn the player.
+const EXPIRY_WARNING: f32 = 3.0;
+
+pub(crate) fn tick_powerups(state: &mut ServerState) {
+	let dt = state.dt;
+	for id in state.entities.spawned_player_ids().collect_vec() {
+		let Some(player) = state.entities.players.get_mut(&id) else { continue };
+		if player.powerups.is_empty() {
+			continue;
+		}
+
+		let warn = player.powerups.iter().filter(|(_, ttl)| *ttl > EXPIRY_WARNING && *ttl - dt <= EXPIRY_WARNING).map(|(kind, _)| kind).collect_vec();
+		let expired = player.powerups.tick(dt);
+		let regenerate = player.powerups.has(PowerupKind::Regeneration);
+
+		if regenerate {
+			regenerate_health(state, id);
+		}
+		for kind in warn {
+			hud_announce2(state, Just(id), format!("{kind} is running out"));
+		}
+		if !expired.is_empty() {
+			// let clients know (movement, rendering).
+			state.apply_to_player_partial(id, |_| ());
+			for kind in expired {
+				hud_announce2(state, Just(id), format!("{kind} wore off"));
+			}
+		}
+	}
+}
+
+fn regenerate_health(state: &mut ServerState, player_id: ID) {
+	let rules = &state.rules;
+	let delta = REGENERATION_RATE * state.dt;
+	let h = state.health.entry(player_id).or_insert_with(|| Health::new(rules));
+	if h.health < rules.health {
+		h.health = f32::min(h.health + delta, rules.health);
+	}
+	if h.shield < rules.spawn_shield {
+		h.shield = f32::min(h.shield + delta, rules.spawn_shield);
+	}
+}
+
+/// Player touched a powerup prop: activate the powerup.
+pub(crate) fn pickup_powerup_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::EnteredTrigger { player, prop, handle } = *event {
+		if let Ok(kind) = PowerupKind::from_str(handle.as_str()) {
+			if pickup(state, player, prop).is_some() {
+				give_powerup(state, player, kind);
+			}
+		}
+	}
+}
+
+pub(crate) fn give_powerup(state: &mut ServerState, player_id: ID, kind: PowerupKind) -> Option<()> {
+	state.apply_to_player_partial(player_id, |p| p.powerups.add(kind))?;
+	hud_announce(state, Just(player_id), format!("{kind}!"));
+	log(state, format!("{} picked up {kind}", must_name(state, player_id)));
+	Some(())
+}
@@ -0,0 +1,163 @@
+//!
+//! Persistent player profiles: lifetime stats and reserved names,
+//! keyed by the identity key that clients send in their `JoinRequest`.
+//!
+
+use super::internal::*;
+use std::collections::BTreeMap;
+
+/// Seconds between saving changed profiles (they are also saved at the end of every match).
+const SAVE_INTERVAL: f32 = 60.0;
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub(crate) struct Profile {
+	/// Last name used by this player. Reserved: other players cannot join under this name.
+	pub name: String,
+	pub kills: u64,
+	pub deaths: u64,
+	pub matches: u64,
+	pub wins: u64,
+	/// Seconds.
+	pub playtime: f64,
+}
+
+pub(crate) struct Profiles {
+	/// Where profiles are persisted (None: keep in memory only).
+	file: Option<PathBuf>,
+	/// Identity key -> profile.
+	by_key: BTreeMap<String, Profile>,
+	/// Connected players -> identity key.
+	online: HashMap<ID, String>,
+	/// Changed since the last save (not counting playtime), saved by `tick_profiles`.
+	dirty: bool,
+	save_timer: Timer,
+}
+
+impl Profiles {
+	/// Load profiles from `file`, if it exists.
+	pub fn load(file: Option<PathBuf>) -> Result<Self> {
+		let by_key = match &file {
+			Some(file) if file.exists() => load_ron(file)?,
+			_ => default(),
+		};
+		Ok(Self {
+			file,
+			by_key,
+			online: default(),
+			dirty: false,
+			save_timer: Timer::repeating(SAVE_INTERVAL),
+		})
+	}
+
+	fn save(&mut self) {
+		self.dirty = false;
+		if let Some(file) = &self.file {
+			if let Err(e) = save_ron(file, &self.by_key) {
+				error!("save profiles: {e:#}");
+			}
+		}
+	}
+
+	/// Profile of a connected player, if they have one.
+	fn online_mut(&mut self, id: ID) -> Option<&mut Profile> {
+		let key = self.online.get(&id)?;
+		self.by_key.get_mut(key)
+	}
+
+	fn by_name(&self, name: &str) -> Option<&Profile> {
+		self.by_key.values().find(|p| p.name.eq_ignore_ascii_case(name))
+	}
+
+	/// Is `name` reserved by someone other than `key`?
+	fn is_reserved(&self, name: &str, key: &str) -> bool {
+		self.by_key.iter().any(|(k, p)| k != key && p.name.eq_ignore_ascii_case(name))
+	}
+}
+
+/// Called before a player joins:
+/// make sure they don't take a name reserved by someone else (append a number if needed).
+pub(crate) fn unreserved_name(state: &ServerState, join_msg: &JoinRequest) -> String {
+	let name = &join_msg.name;
+	let key = &join_msg.identity_key;
+	if !state.profiles.is_reserved(name, key) {
+		return name.clone();
+	}
+	(2..).map(|i| format!("{name}{i}")).find(|n| !state.profiles.is_reserved(n, key)).expect("unreachable")
+}
+
+/// Called after a player joined: create or update their profile.
+pub(crate) fn profile_join(state: &mut ServerState, player_id: ID, identity_key: &str) {
+	if identity_key.is_empty() {
+		return; // anonymous
+	}
+	let name = must_name(state, player_id).to_owned();
+	let profiles = &mut state.profiles;
+	profiles.by_key.entry(identity_key.to_owned()).or_default().name = name;
+	profiles.online.insert(player_id, identity_key.to_owned());
+	profiles.dirty = true;
+}
+
+/// Called when a player leaves: keep the kills/deaths they made in the current match.
+pub(crate) fn profile_leave(state: &mut ServerState, player_id: ID) {
+	if state.match_state.phase == MatchPhase::Playing {
+		credit_match(state, player_id, None);
+	}
+	state.profiles.online.remove(&player_id);
+	state.profiles.dirty = true;
+}
+
+/// Called at the end of a match: add the match to the profiles of everyone who played it.
+pub(crate) fn profile_end_match(state: &mut ServerState) {
+	let winners = winners(state);
+	for id in state.profiles.online.keys().copied().collect_vec() {
+		credit_match(state, id, Some(winners.contains(&id)));
+	}
+	state.profiles.save();
+}
+
+// Add a player's match score to their profile. `won`: None if the match did not finish.
+fn credit_match(state: &mut ServerState, player_id: ID, won: Option<bool>) {
+	let score = state.scores.by_player(player_id);
+	let (frags, deaths) = (score.frags, score.deaths);
+	if let Some(profile) = state.profiles.online_mut(player_id) {
+		profile.kills += frags as u64;
+		profile.deaths += deaths as u64;
+		if let Some(won) = won {
+			profile.matches += 1;
+			profile.wins += won as u64;
+		}
+	}
+}
+
+/// Count playtime, save changed profiles every `SAVE_INTERVAL`.
+pub(crate) fn tick_profiles(state: &mut ServerState) {
+	let profiles = &mut state.profiles;
+	let dt = state.dt as f64;
+	for key in profiles.online.values() {
+		if let Some(profile) = profiles.by_key.get_mut(key) {
+			profile.playtime += dt;
+		}
+	}
+	if profiles.save_timer.tick(state.dt).just_finished() && profiles.dirty {
+		profiles.save();
+	}
+}
+
+/// `stats <name>`: show a player's lifetime stats.
+pub(crate) fn stats_cmd(state: &mut ServerState, caller: Caller, name: &str) -> Result<()> {
+	let p = state.profiles.by_name(name).ok_or_else(|| anyhow!("no profile for {name}"))?;
+	let minutes = (p.playtime / 60.0) as u64;
+	let msg = format!(
+		"{}: {} kills, {} deaths, {} wins in {} matches, played {}h{:02}m",
+		p.name,
+		p.kills,
+		p.deaths,
+		p.wins,
+		p.matches,
+		minutes / 60,
+		minutes % 60
+	);
+	reply(state, caller, msg);
+	Ok(())
+}
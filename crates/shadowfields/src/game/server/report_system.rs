@@ -0,0 +1,200 @@
+//!
+//! Write a JSON match report when a match ends (e.g. for league trackers).
+//!
+
+use super::internal::*;
+use std::time::SystemTime;
+
+/// Bump when making incompatible changes to `MatchReport`.
+const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Things that happened during the current match, not recorded by `Scores`.
+#[derive(Default)]
+pub(crate) struct MatchLog {
+	/// Wall clock time when the match started (seconds since the UNIX epoch).
+	pub start_time: u64,
+	kills: Vec<KillRecord>,
+	/// Name and team of players who left during the match.
+	left: HashMap<ID, (String, Team)>,
+}
+
+#[derive(Serialize)]
+struct KillRecord {
+	/// Seconds since the start of the match.
+	time: f32,
+	/// `None` for suicides (e.g. fell in lava).
+	killer: Option<String>,
+	victim: String,
+	how: String,
+	/// Victim position.
+	position: vec3,
+}
+
+#[derive(Serialize)]
+struct MatchReport {
+	schema_version: u32,
+	map: String,
+	mode: GameMode,
+	start_time: u64,
+	duration: f32,
+	teams: Vec<TeamReport>,
+	players: Vec<PlayerReport>,
+	kills: Vec<KillRecord>,
+}
+
+#[derive(Serialize)]
+struct TeamReport {
+	team: Team,
+	score: i32,
+	players: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PlayerReport {
+	name: String,
+	team: Team,
+	score: i32,
+	frags: u32,
+	deaths: u32,
+	suicides: u32,
+	multi_kills: u32,
+	pickups: u32,
+	shots: u32,
+	hits: u32,
+	/// Fraction of shots that hit (0 if no shots were fired).
+	accuracy: f32,
+}
+
+/// Called when the match starts: forget the previous match.
+pub(crate) fn reset_match_log(state: &mut ServerState) {
+	state.match_log = MatchLog {
+		start_time: unix_time(),
+		..default()
+	};
+}
+
+/// Add a kill (or suicide, if there is no `killer`) to the timeline.
+/// Record kills and suicides in the match log.
+pub(crate) fn match_log_event(state: &mut ServerState, event: &GameEvent) {
+	match *event {
+		GameEvent::Killed { killer, victim, ref how, .. } => record_kill(state, Some(killer), victim, how),
+		GameEvent::Suicided { victim, ref how } => record_kill(state, None, victim, how),
+		_ => (),
+	}
+}
+
+fn record_kill(state: &mut ServerState, killer: Option<ID>, victim: ID, how: &str) {
+	if state.match_state.phase != MatchPhase::Playing {
+		return;
+	}
+	let record = KillRecord {
+		time: state.autoswitch.time_playing,
+		killer: killer.map(|id| must_name(state, id).to_owned()),
+		victim: must_name(state, victim).to_owned(),
+		how: how.to_owned(),
+		position: player(state, victim).map(|p| p.position()).unwrap_or_default(),
+	};
+	state.match_log.kills.push(record);
+}
+
+/// Remember the name of a player who is leaving, so they still show up in the report.
+pub(crate) fn record_leave(state: &mut ServerState, player_id: ID) {
+	if let Some(player) = player(state, player_id) {
+		let entry = (player.name.clone(), player.team);
+		state.match_log.left.insert(player_id, entry);
+	}
+}
+
+/// Write the report for the match that just ended to `report_dir` (if configured).
+pub(crate) fn write_match_report(state: &mut ServerState) {
+	let Some(dir) = state.report_dir.clone() else { return };
+	let report = match_report(state);
+	let file = dir.join(format!("match_{}_{}.json", report.start_time, report.map));
+	match save_match_report(&dir, &file, &report) {
+		Ok(()) => info!("wrote match report {file:?}"),
+		Err(e) => error!("write match report {file:?}: {e:#}"),
+	}
+}
+
+fn save_match_report(dir: &Path, file: &Path, report: &MatchReport) -> Result<()> {
+	mkdir(dir)?;
+	Ok(serde_json::to_writer_pretty(create(file)?, report)?)
+}
+
+fn match_report(state: &mut ServerState) -> MatchReport {
+	let name_and_team = |state: &ServerState, id: ID| match player(state, id) {
+		Some(p) => Some((p.name.clone(), p.team)),
+		None => state.match_log.left.get(&id).cloned(),
+	};
+
+	let players = state
+		.scores
+		.iter()
+		.filter_map(|(id, score)| {
+			let (name, team) = name_and_team(state, id)?;
+			Some(PlayerReport {
+				name,
+				team,
+				score: score.total,
+				frags: score.frags,
+				deaths: score.deaths,
+				suicides: score.suicides,
+				multi_kills: score.multi_kills,
+				pickups: score.pickups,
+				shots: score.shots,
+				hits: score.hits,
+				accuracy: if score.shots == 0 { 0.0 } else { score.hits as f32 / score.shots as f32 },
+			})
+		})
+		.sorted_by_key(|p| -p.score)
+		.collect_vec();
+
+	let teams = [Team::Red, Team::Blue, Team::Green]
+		.into_iter()
+		.map(|team| TeamReport {
+			team,
+			score: state.scores.by_team[team as usize],
+			players: players.iter().filter(|p| p.team == team).map(|p| p.name.clone()).collect(),
+		})
+		.filter(|t| !t.players.is_empty())
+		.collect();
+
+	MatchReport {
+		schema_version: REPORT_SCHEMA_VERSION,
+		map: state.map.name().to_owned(),
+		mode: state.map.mode,
+		start_time: state.match_log.start_time,
+		duration: state.autoswitch.time_playing,
+		teams,
+		players,
+		kills: mem::take(&mut state.match_log.kills),
+	}
+}
+
+/// Seconds since the UNIX epoch.
+pub(crate) fn unix_time() -> u64 {
+	SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::game::server::server_sim::ServerSim;
+
+	#[test]
+	fn first_match_report() {
+		let dir = std::env::temp_dir().join(format!("report_dir_test_{}", std::process::id()));
+		let mut sim = ServerSim::with_settings(|s| s.report_dir = Some(dir.to_string_lossy().into()));
+		let start_time = sim.state.match_log.start_time;
+		assert_ne!(start_time, 0);
+
+		sim.join("alice", None);
+		sim.tick(1);
+		end_match(&mut sim.state);
+
+		let file = read_dir_names(&dir).unwrap().next().unwrap();
+		assert_eq!(file, PathBuf::from(format!("match_{start_time}_test.json")));
+
+		force_remove(&dir).unwrap();
+	}
+}
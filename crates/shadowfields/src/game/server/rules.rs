@@ -0,0 +1,121 @@
+//!
+//! Damage model and health tuning, from the `[rules]` section in `server.toml`.
+//! The server operator can inspect and change rules at runtime with the `rules` command.
+//!
+
+use super::internal::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+	/// What happens when shooting a teammate (team modes only).
+	pub friendly_fire: FriendlyFire,
+
+	/// Health after spawning.
+	pub health: f32,
+	/// Shield after spawning. Discharges down to 0 over time.
+	pub spawn_shield: f32,
+	/// Shield can never exceed this (e.g. when picking up shields).
+	pub max_shield: f32,
+	/// Shield points per second while below target (advantaged players only).
+	pub shield_recharge_rate: f32,
+	/// Shield points per second while above target (e.g. after spawning).
+	pub shield_discharge_rate: f32,
+	/// Shield that advantaged players (`adv` command) recharge up to.
+	pub advantage_shield: f32,
+	/// Seconds of invulnerability after spawning.
+	pub spawn_invulnerability: f32,
+
+	/// Damage done by one hit (before multipliers).
+	pub weapon_damage: f32,
+	/// Multiplies all damage.
+	pub damage_multiplier: f32,
+	/// Multiplies damage to teammates (or reflected to the shooter), if `friendly_fire` is not off.
+	pub team_damage: f32,
+	/// Multiplies damage to oneself (0: no self-damage).
+	pub self_damage: f32,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FriendlyFire {
+	/// Teammates can't hurt each other.
+	#[default]
+	Off,
+	/// Teammates take damage. Killing a teammate costs a point.
+	On,
+	/// The shooter takes the damage instead of their teammate.
+	Reflect,
+}
+
+impl Default for Rules {
+	fn default() -> Self {
+		Self {
+			friendly_fire: default(),
+			health: 100.0,
+			spawn_shield: 100.0,
+			max_shield: 500.0,
+			shield_recharge_rate: 10.0,
+			shield_discharge_rate: 5.0,
+			advantage_shield: 200.0,
+			spawn_invulnerability: 1.0,
+			weapon_damage: 110.0,
+			damage_multiplier: 1.0,
+			team_damage: 1.0,
+			self_damage: 0.0,
+		}
+	}
+}
+
+impl Rules {
+	/// Set a rule by name, e.g. `set("friendly_fire", "reflect")`.
+	/// Values are parsed like in `server.toml` (quotes around strings are optional).
+	pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+		let mut table = toml::Table::try_from(&*self)?;
+		if !table.contains_key(key) {
+			bail!("unknown rule: {key}, options: {}", table.keys().join(", "));
+		}
+		let value = match toml::from_str::<toml::Table>(&format!("v = {value}")) {
+			Ok(mut parsed) => parsed.remove("v").expect("parsed value"),
+			Err(_) => toml::Value::String(value.to_owned()),
+		};
+		table.insert(key.to_owned(), value);
+		*self = table.try_into().map_err(|e| anyhow!("{key}: {e}"))?;
+		Ok(())
+	}
+}
+
+/// `rules`: show the current rules.
+pub(crate) fn rules_cmd(state: &mut ServerState, caller: Caller) -> Result<()> {
+	let msg = toml::to_string(&state.rules)?;
+	reply(state, caller, msg.trim_end());
+	Ok(())
+}
+
+/// `rules <name> <value>`: change a rule.
+pub(crate) fn set_rule_cmd(state: &mut ServerState, key: &str, value: &str) -> Result<()> {
+	state.rules.set(key, value)?;
+	log(state, format!("rule changed: {key} = {value}"));
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn set() {
+		let mut rules = Rules::default();
+		rules.set("friendly_fire", "reflect").unwrap();
+		rules.set("health", "150").unwrap();
+		rules.set("team_damage", "0.5").unwrap();
+		assert_eq!(rules.friendly_fire, FriendlyFire::Reflect);
+		assert_eq!(rules.health, 150.0);
+		assert_eq!(rules.team_damage, 0.5);
+
+		assert!(rules.set("friendly_fire", "sometimes").is_err());
+		assert!(rules.set("health", "lots").is_err());
+		assert!(rules.set("no_such_rule", "1").is_err());
+		assert_eq!(rules.health, 150.0);
+	}
+}
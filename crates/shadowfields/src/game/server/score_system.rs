@@ -21,6 +21,11 @@ pub struct Score {
 	pub multi_kills: u32,
 	pub headshots: u32,
 	pub deaths: u32,
+	pub pickups: u32,
+
+	// for accuracy
+	pub shots: u32,
+	pub hits: u32,
 }
 
 impl Scores {
@@ -49,43 +54,31 @@ impl Scores {
 		}
 	}
 
-	pub fn max(&self) -> i32 {
-		self.by_team.iter().copied().max().unwrap_or_default()
+	/// Top score: the leading team's, or the leading player's in Deathmatch.
+	pub fn max(&self, mode: GameMode) -> i32 {
+		match mode {
+			GameMode::TeamDeathmatch => self.by_team.iter().copied().max(),
+			GameMode::Deathmatch => self.by_player.values().map(|s| s.total).max(),
+		}
+		.unwrap_or_default()
 	}
 }
 /// Someone killed someone else
-pub(crate) fn active_kill(state: &mut ServerState, actor: ID, victim: ID) -> Option<()> {
-	let actor_team = player(state, actor)?.team;
-	let vicitm_team = player(state, victim)?.team;
-
-	if actor_team == vicitm_team {
+pub(crate) fn active_kill(state: &mut ServerState, actor: ID, victim: ID, how: &str) -> Option<()> {
+	let teamkill = !state.map.mode.are_enemies(player(state, actor)?, player(state, victim)?);
+	if teamkill && (actor == victim || state.rules.friendly_fire == FriendlyFire::Off) {
 		trace!("friendly fire {actor} -> {victim}");
 		return None;
 	}
-
 	trace!("{actor} killed {victim}");
 
-	//  "N frags remain gets announced when the leader makes progress"
-	let remaining1 = state.scores.max() - state.autoswitch.frag_limit;
-
-	*state.scores.by_team(actor_team) += 1;
-	state.scores.by_player(actor).frags += 1;
-	record_spree(state, actor);
-
-	let remaining2 = state.scores.max() - state.autoswitch.frag_limit;
-	if remaining1 != remaining2 {
-		announce_remaining_frags(state)
-	}
-
-	log(state, format!("{} confettied {}", must_name(state, actor), must_name(state, victim)));
-	hud_announce(state, Just(actor), format!("You confettied {}", must_name(state, victim)));
-	hud_announce(state, Just(victim), format!("You got confettied by {}", must_name(state, actor)));
-
-	kill(state, victim);
-
+	kill(state, victim)?;
+	let how = how.to_owned();
+	publish(state, GameEvent::Killed { killer: actor, victim, how, teamkill });
 	Some(())
 }
 
+/// Despawn `victim`, however they died (also used for kills by the operator, which publish no event).
 pub(crate) fn kill(state: &mut ServerState, victim: ID) -> Option<()> {
 	despawn(state, victim)?;
 	add_effect(state, Effect::particle_explosion(player(state, victim)?.center(), handle("star_blue"))); // << todo: color
@@ -97,22 +90,61 @@ pub(crate) fn kill(state: &mut ServerState, victim: ID) -> Option<()> {
 pub(crate) fn suicide(state: &mut ServerState, victim: ID, msg: &str) -> Option<()> {
 	if player(state, victim)?.spawned {
 		trace!("{victim} suicide");
-		state.scores.by_player(victim).total -= 1;
-		state.scores.by_player(victim).suicides += 1;
 		kill(state, victim);
-		log(state, format!("{} {}", must_name(state, victim), msg));
-		hud_announce(state, Just(victim), format!("You {}", msg));
-		sound_announce(state, Just(victim), handle("ann_be_careful"));
+		publish(state, GameEvent::Suicided { victim, how: msg.to_owned() });
 	}
 
 	Some(())
 }
 
+/// Keep score for kills and suicides, count pickups.
+pub(crate) fn score_event(state: &mut ServerState, event: &GameEvent) -> Option<()> {
+	match *event {
+		GameEvent::PickedUp { player, .. } => {
+			state.scores.by_player(player).pickups += 1;
+			return Some(());
+		}
+		GameEvent::Killed { killer, victim, teamkill: false, .. } => {
+			//  "N frags remain gets announced when the leader makes progress"
+			let remaining1 = state.scores.max(state.map.mode) - state.autoswitch.frag_limit;
+
+			*state.scores.by_team(player(state, killer)?.team) += 1;
+			state.scores.by_player(killer).total += 1;
+			state.scores.by_player(killer).frags += 1;
+
+			let remaining2 = state.scores.max(state.map.mode) - state.autoswitch.frag_limit;
+			if remaining1 != remaining2 {
+				announce_remaining_frags(state)
+			}
+			state.scores.by_player(victim).deaths += 1;
+		}
+		// killing a teammate costs a point.
+		GameEvent::Killed { killer, victim, teamkill: true, .. } => {
+			*state.scores.by_team(player(state, killer)?.team) -= 1;
+			state.scores.by_player(killer).total -= 1;
+			state.scores.by_player(victim).deaths += 1;
+		}
+		GameEvent::Suicided { victim, .. } => {
+			state.scores.by_player(victim).total -= 1;
+			state.scores.by_player(victim).suicides += 1;
+			state.scores.by_player(victim).deaths += 1;
+		}
+		_ => return None,
+	}
+	broadcast_scores(state);
+	Some(())
+}
+
 pub(crate) fn broadcast_scores(state: &mut ServerState) {
 	// Score delta:
 	// 	`+N` against the second one if you're leading,
 	//  `-N` against the leader if you're behind.
-	let sorted = sorted(state.scores.by_team.to_vec()).with(|v| v.reverse());
+	let mode = state.map.mode;
+	let all_scores = match mode {
+		GameMode::TeamDeathmatch => state.scores.by_team.to_vec(),
+		GameMode::Deathmatch => state.scores.iter().map(|(_, s)| s.total).collect(),
+	};
+	let sorted = sorted(all_scores).with(|v| v.reverse());
 	let top_score = sorted.get(0).copied().unwrap_or_default();
 	let scnd_score = sorted.get(1).copied().unwrap_or_default();
 	let delta = |score| if score == top_score { score - scnd_score } else { score - top_score };
@@ -122,14 +154,17 @@ pub(crate) fn broadcast_scores(state: &mut ServerState) {
 	let min = sec_remaining / 60;
 	let sec = sec_remaining % 60;
 
-	for (id, _score) in state.scores.iter() {
+	for (id, score) in state.scores.iter() {
 		let team = match player(state, id) {
 			None => continue,
 			Some(player) => player.team,
 		};
-		let score = state.scores.by_team[team as usize];
+		let (label, score) = match mode {
+			GameMode::TeamDeathmatch => (team.to_string(), state.scores.by_team[team as usize]),
+			GameMode::Deathmatch => ("you".to_string(), score.total),
+		};
 		let delta = delta(score);
-		let text = format!("time: {min}:{sec:02}\n{team}: {score} / {max} ({delta:+})");
+		let text = format!("time: {min}:{sec:02}\n{label}: {score} / {max} ({delta:+})");
 
 		state.diffs.push(
 			UpdateHUD(HUDUpdate {
@@ -142,9 +177,22 @@ pub(crate) fn broadcast_scores(state: &mut ServerState) {
 	}
 }
 
-fn announce_winner(state: &mut ServerState) {
+/// Players who won the match: the winning team, or the top player(s) in Deathmatch.
+pub(crate) fn winners(state: &ServerState) -> Vec<ID> {
+	let top_score = state.scores.max(state.map.mode);
+	match state.map.mode {
+		GameMode::TeamDeathmatch => state.entities.players.values().filter(|p| state.scores.by_team[p.team as usize] == top_score).map(|p| p.id).collect(),
+		GameMode::Deathmatch => state.scores.iter().filter(|(_, s)| s.total == top_score).map(|(id, _)| id).collect(),
+	}
+}
+
+pub(crate) fn announce_winner(state: &mut ServerState) {
+	if state.map.mode == GameMode::Deathmatch {
+		return announce_winning_player(state);
+	}
+
 	use Team::*;
-	let top_score = state.scores.max();
+	let top_score = state.scores.max(state.map.mode);
 	let winning_team = [Red, Green, Blue].into_iter().find(|&t| *state.scores.by_team(t) == top_score);
 	if let Some(winning_team) = winning_team {
 		hud_announce(state, All, format!("Team {winning_team} wins!"));
@@ -175,6 +223,37 @@ fn announce_winner(state: &mut ServerState) {
 			}
 		}
 	}
+	show_scoreboard(state, scores);
+}
+
+// Deathmatch: there are no winning teams, just a winning player.
+fn announce_winning_player(state: &mut ServerState) {
+	let sorted_players = state.scores.iter().map(|(id, score)| (id, score.total)).collect_vec().with(|v| v.sort_by_key(|(_, total)| -total));
+
+	if let Some(&(winner, _)) = sorted_players.first() {
+		hud_announce(state, All, format!("{} wins!", must_name(state, winner)));
+		sound_announce(state, Just(winner), handle("ann_you_win"));
+		sound_announce(state, Not(winner), handle("ann_game_over"));
+	}
+
+	use std::fmt::Write;
+	let mut scores = String::from("\n\n___________________________________________\n");
+	for (id, total) in sorted_players {
+		let deaths = state.scores.by_player(id).deaths;
+		let _ = writeln!(&mut scores, "{:+20}: {:2} frags | {:2} deaths", must_name(state, id), total, deaths);
+	}
+	show_scoreboard(state, scores);
+}
+
+fn show_scoreboard(state: &mut ServerState, scores: String) {
 	println!("{}", &scores);
-	hud_announce2(state, All, scores);
+	// keep the scoreboard up during the entire intermission
+	state.diffs.push(
+		UpdateHUD(HUDUpdate {
+			pos: HUDPos::TopCenter2,
+			text: scores,
+			ttl_sec: f32::max(ANNOUNCE_TTL, state.match_state.intermission_time),
+		})
+		.to(All),
+	);
 }
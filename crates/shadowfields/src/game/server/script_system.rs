@@ -0,0 +1,496 @@
+//!
+//! Server-side scripts (Rhai, see https://rhai.rs/book).
+//!
+//! Scripts are loaded from `assets/scripts/*.rhai` (server-wide)
+//! and `assets/maps/<map>.map/scripts/*.rhai` (shipped with a map), on startup and on every map switch.
+//! The top level of a script runs once, to register handlers:
+//!
+//! ```rhai
+//! // props that can be spawned or put on a pickup point (`mesh`, `texture`, `size`, `on_collide`)
+//! prop("jump_boost", #{ mesh: "gift_box", texture: "#00FF88", on_collide: "jump_boost" });
+//!
+//! // called every tick while a player touches a prop with this `on_collide` handle
+//! on_collide("jump_boost", |player, prop| {
+//!     pickup(player, prop); // remove the prop, like built-in pickups
+//!     heal(player, 50.0);
+//!     announce(player, "Boost!");
+//! });
+//!
+//! // new console command: `heal_all 25`. `caller` is -1 for the server operator.
+//! // Returning a string replies to the caller.
+//! command("heal_all", |caller, args| {
+//!     for p in players() { heal(p, parse_float(args[0])) }
+//!     "healed everybody"
+//! });
+//! ```
+//!
+//! Scripts are sandboxed: no file or network access, no `import`, limited run time (see `new_engine`).
+//! They can only inspect a snapshot of the players and queue `ScriptAction`s,
+//! which are applied to the `ServerState` after the handler returns:
+//!
+//! ```text
+//! queries: players(), name(p), team(p), position(p), health(p)
+//! actions: spawn_prop(name, [x, y, z]), remove_prop(prop), pickup(p, prop), damage(p, amount), heal(p, amount),
+//!          announce(p, msg), announce_all(msg), play_sound(clip), play_sound(clip, [x, y, z]), after(seconds, fn)
+//! ```
+//!
+//! Script errors are reported to the operator consoles.
+//!
+
+use super::internal::*;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FnPtr, AST, FLOAT, INT};
+use std::sync::MutexGuard;
+
+/// Loaded scripts and the handlers they registered.
+pub(crate) struct Scripts {
+	engine: Engine,
+	scripts: Vec<Script>,
+	ctx: Arc<Mutex<ScriptCtx>>,
+	/// Seconds remaining, callback (see `after`).
+	timers: Vec<(f32, Callback)>,
+}
+
+struct Script {
+	name: String,
+	ast: AST,
+}
+
+/// A script function, and the index of the script that defined it.
+#[derive(Clone)]
+struct Callback(usize, FnPtr);
+
+/// Shared between `Scripts` and the functions registered with the Rhai engine.
+#[derive(Default)]
+struct ScriptCtx {
+	/// Index of the script that is currently running.
+	current: usize,
+
+	on_collide: HashMap<Handle, Callback>,
+	commands: HashMap<String, Callback>,
+	props: HashMap<String, Prop>,
+
+	/// Queued by the script that is currently running.
+	actions: Vec<ScriptAction>,
+	/// What scripts can see of the game.
+	players: HashMap<ID, PlayerInfo>,
+}
+
+#[derive(Clone)]
+struct PlayerInfo {
+	name: String,
+	team: Team,
+	position: vec3,
+	health: f32,
+}
+
+/// Something a script wants to do to the `ServerState`.
+enum ScriptAction {
+	SpawnProp(String, vec3),
+	RemoveProp(ID),
+	Pickup(ID, ID),
+	Damage(ID, f32),
+	Heal(ID, f32),
+	Announce(Addressee, String),
+	PlaySound(Handle, Option<vec3>),
+	After(f32, Callback),
+}
+
+impl Scripts {
+	/// Load server-wide scripts and those of `map_name`.
+	/// Scripts that fail to load are skipped (and reported).
+	pub fn load(map_name: &str) -> (Self, Vec<String>) {
+		let files = [assets_dir().scripts_dir(), assets_dir().find_map_dir(map_name).scripts_dir()] //
+			.into_iter()
+			.flat_map(|dir| read_dir_names(&dir).map(|names| names.map(|name| dir.join(name)).collect_vec()).unwrap_or_default())
+			.filter(|file| file.extension().is_some_and(|ext| ext == "rhai"))
+			.sorted()
+			.collect_vec();
+
+		let mut errors = vec![];
+		let mut sources = vec![];
+		for file in files {
+			match std::fs::read_to_string(&file) {
+				Ok(src) => sources.push((file.to_string_lossy().to_string(), src)),
+				Err(e) => errors.push(format!("script {file:?}: {e}")),
+			}
+		}
+		let (scripts, more_errors) = Self::from_sources(sources);
+		errors.extend(more_errors);
+		(scripts, errors)
+	}
+
+	/// Compile and run the top level of each (name, source) pair.
+	fn from_sources(sources: Vec<(String, String)>) -> (Self, Vec<String>) {
+		let ctx = Arc::new(Mutex::new(ScriptCtx::default()));
+		let mut scripts = Self {
+			engine: new_engine(&ctx),
+			scripts: vec![],
+			ctx,
+			timers: vec![],
+		};
+
+		let mut errors = vec![];
+		for (name, src) in sources {
+			info!("loading script {name}");
+			let ast = match scripts.engine.compile(&src) {
+				Ok(ast) => ast,
+				Err(e) => {
+					errors.push(format!("script {name}: {e}"));
+					continue;
+				}
+			};
+			scripts.ctx().current = scripts.scripts.len();
+			if let Err(e) = scripts.engine.run_ast(&ast) {
+				errors.push(format!("script {name}: {e}"));
+			}
+			scripts.scripts.push(Script { name, ast });
+		}
+		// registering handlers is all the top level may do.
+		scripts.ctx().actions.clear();
+		(scripts, errors)
+	}
+
+	fn ctx(&self) -> MutexGuard<'_, ScriptCtx> {
+		self.ctx.lock().expect("poisoned")
+	}
+
+	/// A prop defined by a script (see `prop`), if any.
+	pub fn prop(&self, name: &str) -> Option<Prop> {
+		self.ctx().props.get(name).cloned()
+	}
+
+	fn on_collide(&self, handle: Handle) -> Option<Callback> {
+		self.ctx().on_collide.get(&handle).cloned()
+	}
+
+	fn command(&self, name: &str) -> Option<Callback> {
+		self.ctx().commands.get(name).cloned()
+	}
+
+	/// Call a script function, return its result and the actions it queued.
+	fn call(&self, players: HashMap<ID, PlayerInfo>, Callback(script, f): &Callback, args: Vec<Dynamic>) -> (Result<Dynamic>, Vec<ScriptAction>) {
+		{
+			let mut ctx = self.ctx();
+			ctx.current = *script;
+			ctx.players = players;
+		}
+		let script = &self.scripts[*script];
+		let result = f.call::<Dynamic>(&self.engine, &script.ast, args).map_err(|e| anyhow!("script {}: {}: {e}", script.name, f.fn_name()));
+		(result, mem::take(&mut self.ctx().actions))
+	}
+}
+
+impl Default for Scripts {
+	/// No scripts.
+	fn default() -> Self {
+		Self::from_sources(vec![]).0
+	}
+}
+
+/// (Re-)load scripts for the current map.
+pub(crate) fn load_scripts(state: &mut ServerState) {
+	let (scripts, errors) = Scripts::load(state.map.name());
+	state.scripts = scripts;
+	for e in errors {
+		report_script_error(state, e);
+	}
+}
+
+/// `reload_scripts`: pick up script changes without switching maps.
+pub(crate) fn reload_scripts_cmd(state: &mut ServerState, caller: Caller) {
+	load_scripts(state);
+	let names = state.scripts.scripts.iter().map(|s| s.name.as_str()).join("\n");
+	reply(state, caller, format!("loaded {} script(s)\n{names}", state.scripts.scripts.len()));
+}
+
+/// Run due timers (see `after`).
+pub(crate) fn tick_scripts(state: &mut ServerState) {
+	if state.scripts.timers.is_empty() {
+		return;
+	}
+	let dt = state.dt;
+	let timers = mem::take(&mut state.scripts.timers);
+	let (due, pending): (Vec<_>, Vec<_>) = timers.into_iter().map(|(ttl, f)| (ttl - dt, f)).partition(|(ttl, _)| *ttl <= 0.0);
+	state.scripts.timers = pending;
+	for (_, f) in due {
+		let _ = call_script(state, &f, vec![]); // errors have been reported
+	}
+}
+
+/// Call the scripted `on_collide` handler, if any.
+pub(crate) fn script_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::EnteredTrigger { player, prop, handle } = *event {
+		if let Some(f) = state.scripts.on_collide(handle) {
+			let _ = call_script(state, &f, vec![id_to_script(player), id_to_script(prop)]); // errors have been reported
+		}
+	}
+}
+
+/// Run a scripted command (see `command`). `Ok(false)` if there is no such command.
+pub(crate) fn script_command(state: &mut ServerState, caller: Caller, cmd: &str) -> Result<bool> {
+	let mut words = cmd.split_ascii_whitespace();
+	let Some(f) = words.next().and_then(|name| state.scripts.command(name)) else { return Ok(false) };
+	let args: Array = words.map(|arg| Dynamic::from(arg.to_owned())).collect();
+	let caller_id = match caller {
+		Caller::Player(id) => id_to_script(id),
+		Caller::Operator(_) => Dynamic::from(-1 as INT),
+	};
+	let result = call_script(state, &f, vec![caller_id, Dynamic::from(args)])?;
+	if result.is_string() {
+		reply(state, caller, result.to_string());
+	}
+	Ok(true)
+}
+
+/// Call a script function and apply the actions it queued.
+/// Errors are reported to the operator (and returned, for commands).
+fn call_script(state: &mut ServerState, f: &Callback, args: Vec<Dynamic>) -> Result<Dynamic> {
+	let (result, actions) = state.scripts.call(player_infos(state), f, args);
+	for action in actions {
+		apply_action(state, action);
+	}
+	if let Err(e) = &result {
+		report_script_error(state, format!("{e:#}"));
+	}
+	result
+}
+
+fn apply_action(state: &mut ServerState, action: ScriptAction) {
+	use ScriptAction::*;
+	match action {
+		SpawnProp(name, position) => match make_prop(state, &name) {
+			Ok(prop) => drop(state.spawn_game_object(prop.with(|p| p.transform.translation = position))),
+			Err(e) => report_script_error(state, format!("spawn_prop: {e}")),
+		},
+		RemoveProp(id) => {
+			if state.entities.props.contains_key(&id) {
+				state.remove_game_object(id)
+			}
+		}
+		Pickup(player, prop) => drop(pickup(state, player, prop)),
+		Damage(player, amount) => drop(damage_player(state, player, amount)),
+		Heal(player, amount) => drop(heal_player(state, player, amount)),
+		Announce(to, msg) => hud_announce(state, to, msg),
+		PlaySound(clip, Some(location)) => sound_effect(state, clip, location, 1.0),
+		PlaySound(clip, None) => sound_announce(state, All, clip),
+		After(seconds, f) => state.scripts.timers.push((seconds, f)),
+	}
+}
+
+/// A prop by name: defined by a script (see `prop`), or built-in (see `Prop::from_str`).
+pub(crate) fn make_prop(state: &ServerState, name: &str) -> Result<Prop> {
+	match state.scripts.prop(name) {
+		Some(prop) => Ok(prop),
+		None => Prop::from_str(name),
+	}
+}
+
+/// Script errors are reported to the operator consoles, not to players.
+fn report_script_error(state: &mut ServerState, msg: String) {
+	error!("{msg}");
+	state.operator_alerts.push(msg);
+}
+
+fn player_infos(state: &ServerState) -> HashMap<ID, PlayerInfo> {
+	state
+		.entities
+		.players
+		.values()
+		.map(|p| {
+			let info = PlayerInfo {
+				name: p.name.clone(),
+				team: p.team,
+				position: p.position(),
+				health: state.health.get(&p.id).map(|h| h.health).unwrap_or_default(),
+			};
+			(p.id, info)
+		})
+		.collect()
+}
+
+//-------------------------------------------------------------------------------- engine
+
+/// Scripts may not run longer than this (Rhai operations, roughly one per expression).
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+fn new_engine(ctx: &Arc<Mutex<ScriptCtx>>) -> Engine {
+	let mut engine = Engine::new();
+	engine
+		.set_max_operations(MAX_OPERATIONS)
+		.set_max_call_levels(32)
+		.set_max_expr_depths(64, 32)
+		.set_max_string_size(10_000)
+		.set_max_array_size(10_000)
+		.set_max_map_size(1_000)
+		.disable_symbol("eval")
+		.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+		.on_print(|msg| info!("script: {msg}"))
+		.on_debug(|msg, _, pos| debug!("script {pos}: {msg}"));
+
+	// Register a function that gets exclusive access to the `ScriptCtx`.
+	macro_rules! register {
+		($name:literal, |$ctx:ident $(, $arg:ident: $ty:ty)*| -> $ret:ty $body:block) => {{
+			let ctx = ctx.clone();
+			engine.register_fn($name, move |$($arg: $ty),*| -> $ret {
+				#[allow(unused_mut)]
+				let mut $ctx = ctx.lock().expect("poisoned");
+				$body
+			});
+		}};
+		($name:literal, |$ctx:ident $(, $arg:ident: $ty:ty)*| $body:expr) => {{
+			let ctx = ctx.clone();
+			engine.register_fn($name, move |$($arg: $ty),*| {
+				#[allow(unused_mut)]
+				let mut $ctx = ctx.lock().expect("poisoned");
+				$body
+			});
+		}};
+	}
+
+	// registration
+	register!("on_collide", |ctx, handle: &str, f: FnPtr| -> ScriptResult<()> {
+		let handle = Handle::from_str(handle).map_err(script_err)?;
+		let f = Callback(ctx.current, f);
+		ctx.on_collide.insert(handle, f);
+		Ok(())
+	});
+	register!("command", |ctx, name: &str, f: FnPtr| {
+		let f = Callback(ctx.current, f);
+		ctx.commands.insert(name.to_owned(), f);
+	});
+	register!("prop", |ctx, name: &str, def: rhai::Map| -> ScriptResult<()> {
+		let prop = prop_from_script(&def)?;
+		ctx.props.insert(name.to_owned(), prop);
+		Ok(())
+	});
+
+	// queries
+	register!("players", |ctx| ctx.players.keys().copied().sorted().map(id_to_script).collect::<Array>());
+	register!("name", |ctx, player: INT| -> ScriptResult<String> { Ok(player_info(&ctx, player)?.name) });
+	register!("team", |ctx, player: INT| -> ScriptResult<String> { Ok(player_info(&ctx, player)?.team.to_string()) });
+	register!("position", |ctx, player: INT| -> ScriptResult<Array> { Ok(vec_to_script(player_info(&ctx, player)?.position)) });
+	register!("health", |ctx, player: INT| -> ScriptResult<FLOAT> { Ok(player_info(&ctx, player)?.health as FLOAT) });
+
+	// actions
+	register!("spawn_prop", |ctx, name: &str, position: Array| -> ScriptResult<()> {
+		let position = vec_from_script(&position)?;
+		ctx.actions.push(ScriptAction::SpawnProp(name.to_owned(), position));
+		Ok(())
+	});
+	register!("remove_prop", |ctx, prop: INT| ctx.actions.push(ScriptAction::RemoveProp(id_from_script(prop))));
+	register!("pickup", |ctx, player: INT, prop: INT| ctx.actions.push(ScriptAction::Pickup(id_from_script(player), id_from_script(prop))));
+	register!("damage", |ctx, player: INT, amount: FLOAT| ctx.actions.push(ScriptAction::Damage(id_from_script(player), amount as f32)));
+	register!("heal", |ctx, player: INT, amount: FLOAT| ctx.actions.push(ScriptAction::Heal(id_from_script(player), amount as f32)));
+	register!("announce", |ctx, player: INT, msg: &str| ctx.actions.push(ScriptAction::Announce(Just(id_from_script(player)), msg.to_owned())));
+	register!("announce_all", |ctx, msg: &str| ctx.actions.push(ScriptAction::Announce(All, msg.to_owned())));
+	register!("play_sound", |ctx, clip: &str| -> ScriptResult<()> {
+		let clip = Handle::from_str(clip).map_err(script_err)?;
+		ctx.actions.push(ScriptAction::PlaySound(clip, None));
+		Ok(())
+	});
+	register!("play_sound", |ctx, clip: &str, location: Array| -> ScriptResult<()> {
+		let clip = Handle::from_str(clip).map_err(script_err)?;
+		ctx.actions.push(ScriptAction::PlaySound(clip, Some(vec_from_script(&location)?)));
+		Ok(())
+	});
+	register!("after", |ctx, seconds: FLOAT, f: FnPtr| {
+		let f = Callback(ctx.current, f);
+		ctx.actions.push(ScriptAction::After(seconds as f32, f));
+	});
+
+	engine
+}
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+fn script_err(e: impl fmt::Display) -> Box<EvalAltResult> {
+	e.to_string().into()
+}
+
+fn id_to_script(id: ID) -> Dynamic {
+	Dynamic::from(id.to_int() as INT)
+}
+
+fn id_from_script(id: INT) -> ID {
+	ID::from_int(id as usize)
+}
+
+fn player_info(ctx: &ScriptCtx, player: INT) -> ScriptResult<PlayerInfo> {
+	ctx.players.get(&id_from_script(player)).cloned().ok_or_else(|| script_err(format!("no such player: {player}")))
+}
+
+fn vec_to_script(v: vec3) -> Array {
+	[v.x(), v.y(), v.z()].map(|c| Dynamic::from(c as FLOAT)).into()
+}
+
+fn vec_from_script(v: &Array) -> ScriptResult<vec3> {
+	let c = |i: usize| -> ScriptResult<f32> {
+		let c = v.get(i).ok_or_else(|| script_err("expected [x, y, z]"))?;
+		c.as_float().or_else(|_| c.as_int().map(|i| i as FLOAT)).map(|c| c as f32).map_err(|_| script_err("expected [x, y, z]"))
+	};
+	Ok(vec3(c(0)?, c(1)?, c(2)?))
+}
+
+/// Prop from e.g. `#{ mesh: "gift_box", texture: "#00FF88", size: [1, 1, 1], on_collide: "jump_boost" }`.
+fn prop_from_script(def: &rhai::Map) -> ScriptResult<Prop> {
+	let mut prop = Prop::from_str("cube").map_err(script_err)?;
+	for (key, value) in def {
+		let handle = || Handle::from_str(&value.to_string()).map_err(script_err);
+		match key.as_str() {
+			"mesh" => prop.mesh = handle()?,
+			"texture" => prop.texture = handle()?,
+			"on_collide" => prop.on_collide = Some(handle()?),
+			"size" => prop.aabb_size = vec_from_script(&value.clone().into_array()?)?,
+			_ => return Err(script_err(format!("prop: unknown key `{key}`, options: mesh, texture, size, on_collide"))),
+		}
+	}
+	Ok(prop)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn register_and_call() {
+		let src = r#"
+			prop("boost", #{ mesh: "gift_box", on_collide: "boost" });
+			on_collide("boost", |player, prop| { pickup(player, prop); heal(player, 10.0); });
+			command("hello", |caller, args| `hello ${args[0]}`);
+		"#;
+		let (scripts, errors) = Scripts::from_sources(vec![("test".into(), src.into())]);
+		assert!(errors.is_empty(), "{errors:?}");
+		assert_eq!(scripts.prop("boost").unwrap().on_collide, Some(handle("boost")));
+
+		let f = scripts.on_collide(handle("boost")).unwrap();
+		let (result, actions) = scripts.call(default(), &f, vec![Dynamic::from(1 as INT), Dynamic::from(2 as INT)]);
+		assert!(result.unwrap().is_unit());
+		assert!(matches!(actions[..], [ScriptAction::Pickup(..), ScriptAction::Heal(_, 10.0)]));
+
+		let f = scripts.command("hello").unwrap();
+		let args: Array = vec![Dynamic::from("world")];
+		let (result, _) = scripts.call(default(), &f, vec![Dynamic::from(-1 as INT), Dynamic::from(args)]);
+		assert_eq!(result.unwrap().to_string(), "hello world");
+	}
+
+	#[test]
+	fn errors() {
+		let (_, errors) = Scripts::from_sources(vec![("syntax".into(), "fn (".into()), ("runtime".into(), "no_such_fn()".into())]);
+		assert_eq!(errors.len(), 2);
+
+		let (scripts, _) = Scripts::from_sources(vec![("loop".into(), "command(\"spin\", |c, a| { loop {} });".into())]);
+		let f = scripts.command("spin").unwrap();
+		let (result, _) = scripts.call(default(), &f, vec![Dynamic::from(-1 as INT), Dynamic::from(Array::new())]);
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn no_imports() {
+		// a module that the default resolver would happily load from disk.
+		let module = std::env::temp_dir().join("shadowfields_test_module");
+		std::fs::write(module.with_extension("rhai"), "fn f() { 1 }").unwrap();
+		let src = format!("import {:?} as m;", module.to_string_lossy());
+		let (_, errors) = Scripts::from_sources(vec![("import".into(), src)]);
+		assert_eq!(errors.len(), 1);
+	}
+}
@@ -1,63 +1,198 @@
 //!
-//! Handle console commands by players.
+//! Handle console commands by players and the server operator.
 //!
 
 use super::internal::*;
 
-pub(crate) fn server_command(state: &mut ServerState, client_id: ID, cmd: String) {
-	info!("command from {client_id} ({}): '{cmd}'", must_name(state, client_id));
-	match server_command_with_result(state, client_id, cmd) {
+/// Who issued a command: a player (via `ClientMsg::Command`), or the server operator
+/// (via stdin or RCON, see `Consoles`). The operator may use privileged commands.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Caller {
+	Player(ID),
+	Operator(ConsoleID),
+}
+
+impl Caller {
+	/// The calling player, for commands that only make sense for players.
+	fn player(self) -> Result<ID> {
+		match self {
+			Caller::Player(id) => Ok(id),
+			Caller::Operator(_) => bail!("only players can use this command"),
+		}
+	}
+
+	fn require_operator(self) -> Result<()> {
+		match self {
+			Caller::Player(_) => bail!("only the server operator can use this command"),
+			Caller::Operator(_) => Ok(()),
+		}
+	}
+}
+
+pub(crate) fn server_command(state: &mut ServerState, caller: Caller, cmd: String) {
+	info!("command from {}: '{cmd}'", caller_name(state, caller));
+	match server_command_with_result(state, caller, cmd) {
 		Ok(()) => info!("command ok"),
 		Err(e) => {
 			info!("command error: {e:#}");
-			state.diffs.push(Log(format!("error: {e:#}")).to_just(client_id));
+			reply(state, caller, format!("error: {e:#}"));
 		}
 	}
 }
 
-fn server_command_with_result(state: &mut ServerState, client_id: ID, cmd: String) -> Result<()> {
+fn server_command_with_result(state: &mut ServerState, caller: Caller, cmd: String) -> Result<()> {
 	Ok(match &cmd.split_ascii_whitespace().collect::<Vec<_>>()[..] {
-		["say", ..] => say(state, client_id, cmd["say".len()..].trim_start()),
+		["say", ..] => say_cmd(state, caller, rest(&cmd, 1))?,
+		["say_team", ..] => say_team_cmd(state, caller.player()?, rest(&cmd, 1))?,
+		["tell", name, ..] => tell_cmd(state, caller, name, rest(&cmd, 2))?,
+		["mute"] => muted_cmd(state, caller.player()?),
+		["mute", name] => mute_cmd(state, caller.player()?, name)?,
+		["unmute", name] => unmute_cmd(state, caller.player()?, name)?,
 		["switch"] => switch_next_map(state),
 		["switch", map_name] => switch_map(state, map_name),
-		["kill", victim_name] => kill_cmd(state, client_id, victim_name)?,
+		["kill", victim_name] => kill_cmd(state, caller, victim_name)?,
 		["adv", player_name] => adv_cmd(state, player_name)?,
-		["summon", handle] => summon_cmd(state, client_id, handle)?,
+		["summon", handle] => summon_cmd(state, caller.player()?, handle)?,
+		["team", choice] => team_cmd(state, caller.player()?, choice)?,
+		["ready"] => ready_cmd(state, caller.player()?)?,
+		["stats", name] => stats_cmd(state, caller, name)?,
+		["vote", choice] => vote_cmd(state, caller.player()?, choice)?,
+		["callvote", "map", map_name] => callvote_cmd(state, caller.player()?, VoteSubject::Map(map_name.to_string()))?,
+		["callvote", "kick", player_name] => callvote_cmd(state, caller.player()?, VoteSubject::Kick(player_by_name(state, player_name)?))?,
 		["time_limit", seconds] => state.autoswitch.time_limit = seconds.parse()?,
 		["frag_limit", frags] => state.autoswitch.frag_limit = frags.parse()?,
-		_ => return Err(anyhow!("unknown command: {}", cmd)),
+		["status"] => status_cmd(state, caller),
+		["players"] => players_cmd(state, caller),
+		["maplist"] => maplist_cmd(state, caller),
+		["kick", player_name, reason @ ..] => {
+			caller.require_operator()?;
+			let reason = match reason {
+				[] => "kicked by the server operator".to_owned(),
+				_ => format!("kicked: {}", reason.join(" ")),
+			};
+			kick(state, player_by_name(state, player_name)?, &reason)
+		}
+		["ban", target] => {
+			caller.require_operator()?;
+			ban_cmd(state, caller, target, None)?
+		}
+		["ban", target, duration] => {
+			caller.require_operator()?;
+			ban_cmd(state, caller, target, Some(duration))?
+		}
+		["unban", target] => {
+			caller.require_operator()?;
+			unban_cmd(state, caller, target)?
+		}
+		["rules"] => rules_cmd(state, caller)?,
+		["rules", key, value] => {
+			caller.require_operator()?;
+			set_rule_cmd(state, key, value)?
+		}
+		["bans"] => {
+			caller.require_operator()?;
+			bans_cmd(state, caller)
+		}
+		["reload_scripts"] => {
+			caller.require_operator()?;
+			reload_scripts_cmd(state, caller)
+		}
+		_ => {
+			if !script_command(state, caller, &cmd)? {
+				bail!("unknown command: {}", cmd)
+			}
+		}
 	})
 }
 
+/// Send command output back to whoever issued the command.
+pub(crate) fn reply(state: &mut ServerState, caller: Caller, msg: impl Into<String>) {
+	match caller {
+		Caller::Player(id) => state.diffs.push(Log(msg.into()).to_just(id)),
+		Caller::Operator(console) => state.console_replies.push((console, msg.into())),
+	}
+}
+
+fn caller_name(state: &ServerState, caller: Caller) -> String {
+	match caller {
+		Caller::Player(id) => format!("{id} ({})", must_name(state, id)),
+		Caller::Operator(console) => format!("operator ({console:?})"),
+	}
+}
+
+fn status_cmd(state: &mut ServerState, caller: Caller) {
+	let sec_remaining = f32::max(0.0, state.autoswitch.time_remaining()) as u32;
+	let msg = format!(
+		"map: {} ({}), phase: {:?}, time remaining: {}:{:02}, top score: {} / {}, players: {}",
+		state.map.name(),
+		state.map.mode,
+		state.match_state.phase,
+		sec_remaining / 60,
+		sec_remaining % 60,
+		state.scores.max(state.map.mode),
+		state.autoswitch.frag_limit,
+		state.entities.players.len(),
+	);
+	reply(state, caller, msg)
+}
+
+fn players_cmd(state: &mut ServerState, caller: Caller) {
+	let lines = state
+		.entities
+		.players
+		.values()
+		.sorted_by_key(|p| p.name.to_ascii_lowercase())
+		.map(|p| {
+			let score = state.scores.iter().find(|(id, _)| *id == p.id).map(|(_, s)| s.total).unwrap_or_default();
+			format!("{:>8} {:20} {:6} score: {score}", p.id.to_string(), p.name, p.team.to_string())
+		})
+		.collect_vec();
+	match lines.len() {
+		0 => reply(state, caller, "no players"),
+		_ => reply(state, caller, lines.join("\n")),
+	}
+}
+
+fn maplist_cmd(state: &mut ServerState, caller: Caller) {
+	let curr = state.map.name();
+	let msg = state.rotation.map_names().map(|name| format!("{} {name}", select(name == curr, "*", " "))).join("\n");
+	reply(state, caller, msg)
+}
+
 fn summon_cmd(state: &mut ServerState, client_id: ID, handle: &str) -> Result<()> {
 	const DIST_FROM_PLAYER: f32 = 2.0;
 	let player = player(state, client_id).ok_or_else(noexist)?;
 	let position = player.position() + DIST_FROM_PLAYER * player.skeleton.filtered_frame().orientation.look_dir_h();
-	let mut gobj = Prop::from_str(handle)?;
+	let mut gobj = make_prop(state, handle)?;
 	gobj.transform.translation = position;
 	state.spawn_game_object(gobj);
 	Ok(())
 }
 
-fn kill_cmd(state: &mut ServerState, player_id: ID, victim_name: &str) -> Result<()> {
+fn kill_cmd(state: &mut ServerState, caller: Caller, victim_name: &str) -> Result<()> {
 	let victim_id = player_by_name(state, victim_name)?;
-	active_kill(state, player_id, victim_id).ok_or(anyhow!("failed (maybe monad bailed out)"))
+	match caller {
+		Caller::Player(player_id) => active_kill(state, player_id, victim_id, "kill command"),
+		Caller::Operator(_) => kill(state, victim_id),
+	}
+	.ok_or(anyhow!("failed (maybe monad bailed out)"))
 }
 
 fn adv_cmd(state: &mut ServerState, player_name: &str) -> Result<()> {
 	let player_id = player_by_name(state, player_name)?;
 	log::info!("adv {player_name}");
-	let health = state.health.entry(player_id).or_default();
-	health.advantage = true;
-	health.target_shield = 200.0;
-	health.shield_discharge_rate = 3.0;
+	state.apply_to_player_partial(player_id, |p| p.powerups.add(PowerupKind::Advantage)).ok_or(noexist())?;
 	state.diffs.push(ServerMsg::Command("adv".to_string()).to_just(player_id));
 	Ok(())
 }
 
-fn say(state: &mut ServerState, player_id: ID, msg: &str) {
-	let msg = format!("{}: {}", must_name(state, player_id), msg);
-	state.diffs.push(Log(msg).to_all());
+/// `cmd` without its first `n` words, e.g. the message in `tell bob hello there`.
+fn rest(cmd: &str, n: usize) -> &str {
+	let mut rest = cmd.trim_start();
+	for _ in 0..n {
+		rest = rest.trim_start_matches(|c: char| !c.is_ascii_whitespace()).trim_start();
+	}
+	rest
 }
 
 fn noexist() -> Error {
@@ -3,16 +3,101 @@ use clap::Parser;
 
 /// Command-line options for game server.
 #[derive(Parser, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ServerOpts {
 	#[arg(short, long, default_value = "127.0.0.1:3344")]
 	pub addr: String,
 
+	/// Maps to play, optionally with per-map settings (see `MapListEntry`).
 	#[arg(short, long)]
-	pub maplist: Vec<String>,
+	#[serde(deserialize_with = "deserialize_maplist")]
+	pub maplist: Vec<MapListEntry>,
+
+	#[arg(long, default_value = "sequential")]
+	pub rotation: RotationPolicy,
 
 	pub frag_limit: u32,
 
 	pub time_limit: u32,
+
+	#[arg(long, default_value = "team_deathmatch")]
+	pub mode: GameMode,
+
+	/// Teams to play with, e.g. `red,blue` (default: all teams).
+	/// Can be overridden per map in the maplist.
+	#[arg(long, value_delimiter = ',')]
+	pub teams: Vec<Team>,
+
+	/// Move players between teams to keep team sizes within one of each other.
+	#[arg(long)]
+	pub auto_balance: bool,
+
+	/// Seconds of warmup before each match (0: start right away).
+	/// With `ready_up`: maximum warmup time (0: wait for everyone to be ready).
+	#[arg(long, default_value_t = 0)]
+	pub warmup_time: u32,
+
+	/// Wait for all players to type `ready` before starting a match.
+	#[arg(long)]
+	pub ready_up: bool,
+
+	/// Seconds to show the final scoreboard before switching to the next map.
+	#[arg(long, default_value_t = 10)]
+	pub intermission_time: u32,
+
+	/// Number of maps to vote on during intermission (0: no vote, play the next map from the maplist).
+	#[arg(long, default_value_t = 3)]
+	pub vote_candidates: u32,
+
+	/// Write a JSON match report to this directory after every match (see `MatchReport`).
+	#[arg(long)]
+	pub report_dir: Option<String>,
+
+	/// Record a demo of every match to this directory (see `Demo`).
+	#[arg(long)]
+	pub demo_dir: Option<String>,
+
+	/// Player profiles (lifetime stats, reserved names), stored next to `server.toml`.
+	/// None: don't keep profiles across server restarts.
+	#[arg(long)]
+	pub profiles_file: Option<String>,
+
+	/// Banned IP addresses (see `ban` command), stored next to `server.toml`.
+	/// None: don't keep bans across server restarts.
+	#[arg(long)]
+	pub bans_file: Option<String>,
+
+	/// Chat rate limit: number of messages a player may send in quick succession.
+	#[arg(long, default_value_t = 5)]
+	pub chat_burst: u32,
+
+	/// Chat rate limit: seconds to earn back one message of the burst.
+	#[arg(long, default_value_t = 2.0)]
+	pub chat_interval: f32,
+
+	/// Words to replace by asterisks in chat, e.g. `darn,heck`.
+	#[arg(long, value_delimiter = ',')]
+	pub chat_filter: Vec<String>,
+
+	/// Seed for the server's random choices (spawn points, map rotation, bots, ...). None: different every time.
+	#[arg(long)]
+	pub seed: Option<u64>,
+
+	/// Damage model and health tuning (`[rules]` section).
+	#[arg(skip)]
+	pub rules: Rules,
+
+	/// Bots hosted by the server (`[bots]` section).
+	#[arg(skip)]
+	pub bots: BotOpts,
+
+	/// Accept operator commands over TCP on this address (see `Consoles`). E.g. `127.0.0.1:3345`.
+	#[arg(long)]
+	pub rcon_addr: Option<String>,
+
+	/// Required if `rcon_addr` is set.
+	#[arg(long)]
+	pub rcon_password: Option<String>,
 }
 
 impl Default for ServerOpts {
@@ -20,8 +105,28 @@ impl Default for ServerOpts {
 		Self {
 			addr: "127.0.0.1:3344".into(),
 			maplist: vec![],
+			rotation: default(),
 			frag_limit: 20,
 			time_limit: 460,
+			mode: default(),
+			teams: vec![],
+			auto_balance: false,
+			warmup_time: 0,
+			ready_up: false,
+			intermission_time: 10,
+			vote_candidates: 3,
+			report_dir: None,
+			demo_dir: None,
+			profiles_file: Some("profiles.ron".into()),
+			bans_file: Some("bans.ron".into()),
+			chat_burst: 5,
+			chat_interval: 2.0,
+			chat_filter: vec![],
+			seed: None,
+			rules: default(),
+			bots: default(),
+			rcon_addr: None,
+			rcon_password: None,
 		}
 	}
 }
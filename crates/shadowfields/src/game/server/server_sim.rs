@@ -0,0 +1,203 @@
+//!
+//! Deterministic server simulation for tests.
+//!
+//! `ServerSim` runs a `ServerState` on a small in-memory map (no assets, no network),
+//! with a seeded RNG and IDs counting from 0, so that the same script of
+//! `ClientMsg`s and ticks always produces the same `Diffs`.
+//!
+
+use super::internal::*;
+use std::net::Ipv4Addr;
+
+/// Server tick time used by `ServerSim::tick` (s).
+pub(crate) const SIM_DT: f32 = 1.0 / 60.0;
+
+/// Spawn points of the test map. Far apart, so that nobody spawns on top of the pickup.
+pub(crate) const SIM_SPAWN_POINTS: [vec3; 2] = [vec3(-20.0, 0.0, 0.0), vec3(20.0, 0.0, 0.0)];
+
+/// Position of the test map's only pickup point (a shield).
+pub(crate) const SIM_PICKUP_POS: vec3 = vec3(0.0, 0.0, 10.0);
+
+/// A `ServerState` under test, plus everything it sent out.
+pub(crate) struct ServerSim {
+	pub state: ServerState,
+	/// Every message sent so far, in order.
+	pub sent: Vec<Envelope<ServerMsg>>,
+	_ids: IdScope,
+}
+
+impl ServerSim {
+	/// A server on the test map, with default settings and seed 1.
+	pub fn new() -> Self {
+		Self::with_settings(|_| ())
+	}
+
+	/// Like `new`, but with tweaked settings (e.g. rules, frag limit, seed).
+	pub fn with_settings(tweak: impl FnOnce(&mut ServerOpts)) -> Self {
+		init_test_assets_dir();
+		let _ids = IdScope::new();
+		let settings = ServerOpts {
+			maplist: vec![MapListEntry::named("test")],
+			profiles_file: None,
+			bans_file: None,
+			seed: Some(1),
+			..default()
+		}
+		.with(tweak);
+		let state = ServerState::with_map(settings, test_map()).unwrap();
+		Self { state, sent: vec![], _ids }
+	}
+
+	/// Join a new player, spawn them and return their ID.
+	pub fn join(&mut self, name: &str, team: Option<Team>) -> ID {
+		let join_msg = JoinRequest {
+			name: name.into(),
+			avatar_id: 0,
+			team,
+			identity_key: format!("key-{name}"),
+		};
+		let (id, _) = self.state.handle_join_new_player(join_msg, Ipv4Addr::LOCALHOST.into());
+		self.send(id, ClientMsg::ReadyToSpawn);
+		id
+	}
+
+	/// Handle a message as if sent by `player`.
+	pub fn send(&mut self, player: ID, msg: ClientMsg) {
+		self.state.handle_client_msg(player, msg);
+	}
+
+	/// Run `n` server ticks, like `NetServer` does.
+	pub fn tick(&mut self, n: usize) {
+		for _ in 0..n {
+			NetServer::smooth_players_movement(&mut self.state.entities.players);
+			self.sent.extend(self.state.handle_tick(SIM_DT).into_iter());
+		}
+	}
+
+	/// Move a spawned player to `position`, as their client would.
+	pub fn move_to(&mut self, player: ID, position: vec3) {
+		let frame = self.player(player).skeleton.target_frame().with(|f| f.position = position);
+		self.send(player, ClientMsg::MovePlayerIfSpawned(frame));
+	}
+
+	pub fn player(&self, id: ID) -> &Player {
+		self.state.entities.players.get(&id).expect("no such player")
+	}
+
+	pub fn score(&mut self, id: ID) -> &Score {
+		self.state.scores.by_player(id)
+	}
+
+	pub fn health(&self, id: ID) -> Option<&Health> {
+		self.state.health.get(&id)
+	}
+
+	/// Messages sent so far that `player` received.
+	pub fn msgs_to(&self, player: ID) -> impl Iterator<Item = &ServerMsg> {
+		self.sent.iter().filter(move |e| match e.to {
+			Just(id) => id == player,
+			Not(id) => id != player,
+			All => true,
+		}).map(|e| &e.msg)
+	}
+
+	/// Everything sent so far, serialized. Equal for equal runs.
+	pub fn transcript(&self) -> Vec<u8> {
+		let addressee = |to: Addressee| match to {
+			Just(id) => (0, id),
+			Not(id) => (1, id),
+			All => (2, ID::default()),
+		};
+		bincode::serialize(&self.sent.iter().map(|e| (addressee(e.to), &e.msg)).collect_vec()).unwrap()
+	}
+}
+
+/// A flat 100x100m floor with two spawn points and a shield.
+fn test_map() -> Map {
+	let metadata = Metadata {
+		spawn_points: SIM_SPAWN_POINTS.iter().map(|&position| SpawnPoint { position, yaw: 0.0 }).collect(),
+		pickup_points: vec![PickupPoint {
+			pos: SIM_PICKUP_POS,
+			item: handle("shield"),
+			timer: Timer::one_off_ready(10.0),
+		}],
+		..default()
+	};
+	Map::test_floor(metadata, &[])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// A short deathmatch: join, walk around, pick up a shield, shoot.
+	fn script(sim: &mut ServerSim) -> (ID, ID) {
+		let alice = sim.join("alice", None);
+		let bob = sim.join("bob", None);
+		sim.tick(10);
+		sim.move_to(alice, SIM_PICKUP_POS);
+		sim.tick(10);
+		for _ in 0..10 {
+			sim.send(alice, ClientMsg::HitPlayer(bob));
+			sim.tick(1);
+		}
+		sim.tick(10);
+		(alice, bob)
+	}
+
+	#[test]
+	fn deterministic() {
+		let mut sim1 = ServerSim::new();
+		let ids1 = script(&mut sim1);
+		let mut sim2 = ServerSim::new();
+		let ids2 = script(&mut sim2);
+
+		assert_eq!(ids1, ids2);
+		assert!(!sim1.sent.is_empty());
+		assert_eq!(sim1.transcript(), sim2.transcript());
+	}
+
+	#[test]
+	fn frag() {
+		let mut sim = ServerSim::with_settings(|s| s.rules.spawn_invulnerability = 0.0);
+		let alice = sim.join("alice", None);
+		let bob = sim.join("bob", None);
+		sim.tick(1);
+
+		// spawn shield absorbs most of the first hit
+		sim.send(alice, ClientMsg::HitPlayer(bob));
+		assert!(sim.health(bob).unwrap().health < sim.state.rules.health);
+		assert!(sim.player(bob).spawned);
+
+		for _ in 0..100 {
+			if !sim.player(bob).spawned {
+				break;
+			}
+			sim.send(alice, ClientMsg::HitPlayer(bob));
+			sim.tick(1);
+		}
+		assert!(!sim.player(bob).spawned);
+		assert_eq!(sim.score(alice).frags, 1);
+		assert_eq!(sim.score(bob).deaths, 1);
+		assert!(sim.msgs_to(bob).any(|msg| matches!(msg, KilledBy(id) if *id == alice)));
+		assert!(!sim.msgs_to(alice).any(|msg| matches!(msg, KilledBy(_))));
+	}
+
+	fn shields(sim: &ServerSim) -> usize {
+		sim.state.entities.props.values().filter(|p| p.on_collide == Some(handle("shield"))).count()
+	}
+
+	#[test]
+	fn pickup_shield() {
+		let mut sim = ServerSim::new();
+		let alice = sim.join("alice", None);
+		sim.tick(1);
+		assert_eq!(shields(&sim), 1);
+		let before = sim.health(alice).map(|h| h.shield).unwrap_or_default();
+
+		sim.move_to(alice, SIM_PICKUP_POS);
+		sim.tick(1);
+		assert_eq!(shields(&sim), 0);
+		assert!(sim.health(alice).unwrap().shield > before);
+	}
+}
@@ -14,13 +14,31 @@ pub(crate) struct ServerState {
 
 	// Each of these pieces of data is primarily controlled by one system.
 	// (Though there are some dependencies).
-	pub maplist: Vec<String>,
+	pub rotation: MapRotation,
 	pub autoswitch: AutoSwitch,
+	pub match_state: MatchState,
+	pub votes: Votes,
+	pub rules: Rules,
+	pub teams: Teams,
+	pub scripts: Scripts,
+	pub chat: ChatState,
+	pub bots: ServerBots,
+	pub match_log: MatchLog,
+	pub profiles: Profiles,
+	pub bans: BanList,
+	pub report_dir: Option<PathBuf>,
+	pub demo_dir: Option<PathBuf>,
+	/// Recording of the current match, if `demo_dir` is set.
+	pub demo: Option<DemoRecorder>,
 	pub scores: Scores,
 	pub sprees: HashMap<ID, (f32, u32)>,
 	pub health: HashMap<ID, Health>,
 	pub hud_timer: Timer,
 
+	/// All of the server's random choices (spawn points, map rotation and votes, powerups, ...),
+	/// so that games can be replayed from a seed. Hosted bots have their own, seeded from this one.
+	pub rng: Xoshiro256PlusPlus,
+
 	pub map: Map,
 
 	pub entities: Entities,
@@ -28,15 +46,54 @@ pub(crate) struct ServerState {
 	prev_entities: Entities,
 
 	pub diffs: Diffs,
+
+	/// Published by systems, handed to consumers by `dispatch_events`.
+	pub events: Vec<GameEvent>,
+
+	/// IP address of each connected player (for bans).
+	pub addresses: HashMap<ID, IpAddr>,
+
+	/// Players to be disconnected by the NetServer (see `kick`).
+	pub kicked: Vec<ID>,
+
+	/// Output of operator commands, to be sent back by `Consoles`.
+	pub console_replies: Vec<(ConsoleID, String)>,
+
+	/// Messages for all operator consoles (e.g. script errors).
+	pub operator_alerts: Vec<String>,
 }
 
 impl ServerState {
 	pub fn new(settings: ServerOpts) -> Result<Self> {
-		let maplist = load_maplist(&settings)?;
+		let rotation = MapRotation::from_settings(&settings)?;
+		let map = Map::load(rotation.map_names().next().ok_or_else(|| anyhow!("no maps"))?)?;
+		Self::with_rotation(settings, rotation, map)
+	}
+
+	/// Like `new`, but starting on a given map (e.g. an in-memory test map, see `ServerSim`).
+	/// The maplist is taken as-is, without loading the maps.
+	#[cfg(test)]
+	pub fn with_map(settings: ServerOpts, map: Map) -> Result<Self> {
+		let rotation = MapRotation::new(&settings, settings.maplist.clone());
+		Self::with_rotation(settings, rotation, map)
+	}
+
+	fn with_rotation(settings: ServerOpts, rotation: MapRotation, map: Map) -> Result<Self> {
 		let autoswitch = AutoSwitch::from_settings(&settings);
+		let match_state = MatchState::from_settings(&settings);
+		let votes = Votes::from_settings(&settings);
+		let teams = Teams::from_settings(&settings);
+		let scripts = default();
+		let chat = ChatState::from_settings(&settings);
+		let mut rng = Xoshiro256PlusPlus::seed_from_u64(settings.seed.unwrap_or_else(rand::random));
+		let bots = ServerBots::from_settings(&settings, &mut rng);
+		let rules = settings.rules.clone();
+		let report_dir = settings.report_dir.as_ref().map(PathBuf::from);
+		let demo_dir = settings.demo_dir.as_ref().map(PathBuf::from);
+		let profiles = Profiles::load(settings.profiles_file.as_deref().map(|f| assets_dir().settings_file(f)).transpose()?)?;
+		let bans = BanList::load(settings.bans_file.as_deref().map(|f| assets_dir().settings_file(f)).transpose()?)?;
 		let scores = default();
 		let sprees = default();
-		let map = Map::load(maplist.get(0).ok_or_else(|| anyhow!("no maps"))?)?;
 		let entities = default();
 		let diffs = default();
 		let health = default();
@@ -44,22 +101,51 @@ impl ServerState {
 
 		Ok(Self {
 			dt: default(),
-			maplist,
+			rotation,
 			autoswitch,
+			match_state,
+			votes,
+			teams,
+			scripts,
+			chat,
+			bots,
+			rules,
+			match_log: default(),
+			report_dir,
+			demo_dir,
+			demo: None,
+			profiles,
+			bans,
 			map,
 			sprees,
 			scores,
 			entities,
 			prev_entities: default(),
 			diffs,
+			events: default(),
 			health,
 			hud_timer,
-		})
+			rng,
+			addresses: default(),
+			kicked: default(),
+			console_replies: default(),
+			operator_alerts: default(),
+		}
+		.with(apply_map_settings)
+		.with(load_scripts)
+		.with(begin_warmup))
 	}
 
 	/// Add a new player to the game and return their unique ID.
-	pub fn handle_join_new_player(&mut self, join_msg: JoinRequest) -> (ID, MapSwitch) {
-		join_new_player(self, join_msg)
+	pub fn handle_join_new_player(&mut self, join_msg: JoinRequest, ip: IpAddr) -> (ID, MapSwitch) {
+		let (player_id, map_switch) = join_new_player(self, join_msg);
+		self.addresses.insert(player_id, ip);
+		(player_id, map_switch)
+	}
+
+	/// Reason to refuse a new connection from `ip`, if banned.
+	pub fn handle_check_ban(&mut self, ip: IpAddr) -> Option<String> {
+		check_ban(self, ip)
 	}
 
 	pub fn handle_drop_player(&mut self, client_id: ID) {
@@ -70,13 +156,24 @@ impl ServerState {
 		self.dt = dt;
 
 		// systems
+		tick_movers(self);
+		tick_bots(self);
 		tick_pickup_points(self);
 		tick_collisions(self);
 		tick_lava(self);
 		tick_health(self);
+		tick_powerups(self);
 		tick_killplane(self);
 		tick_hud_text(self);
-		tick_autoswitch(self);
+		tick_votes(self);
+		tick_scripts(self);
+		tick_chat(self);
+		tick_profiles(self);
+		tick_team_balance(self);
+		dispatch_events(self); // so that frag and time limits see this tick's scores
+		tick_match(self);
+		dispatch_events(self); // e.g. MapSwitched
+		record_broadcasts(self);
 
 		mem::take(&mut self.diffs)
 	}
@@ -97,8 +194,16 @@ impl ServerState {
 			AddEffect(effect) => self.handle_add_effect(player_id, effect),
 			PlaySound(sound) => self.handle_play_sound(player_id, sound),
 			HitPlayer(victim_id) => handle_hit_player(self, player_id, victim_id),
-			Command(cmd) => server_command(self, player_id, cmd),
+			Command(cmd) => server_command(self, Caller::Player(player_id), cmd),
+			ShotFired => self.scores.by_player(player_id).shots += 1,
 		};
+		dispatch_events(self);
+	}
+
+	/// Execute a command typed by the server operator (see `Consoles`).
+	pub fn handle_console_command(&mut self, console: ConsoleID, cmd: String) {
+		server_command(self, Caller::Operator(console), cmd);
+		dispatch_events(self);
 	}
 
 	fn handle_move_player_if_spawned(&mut self, player_id: ID, frame: Frame) {
@@ -113,6 +218,7 @@ impl ServerState {
 
 	// Handle a client's PlaySound message: just broadcast to other clients.
 	fn handle_play_sound(&mut self, player_id: ID, sound: SoundEffect) {
+		bots_hear(self, &sound);
 		self.diffs.push(PlaySound(sound).to_not(player_id))
 	}
 
@@ -154,8 +260,8 @@ impl ServerState {
 
 	pub fn move_player_if_spawned(&mut self, player_id: ID, frame: Frame) {
 		if let Some(player) = self.entities.players.get_mut(&player_id) {
-			if player.spawned {
-				player.skeleton.set_frame(frame);
+			if player.spawned && !player.frozen {
+				player.set_frame(frame);
 				self.diffs.push(MovePlayer(player_id, player.skeleton.target_frame()).to_not(player_id));
 			}
 		}
@@ -165,17 +271,27 @@ impl ServerState {
 		// Join new player cannot be done via apply(msg):
 		// we need to add the player before we can get the player ID.
 
-		let player_id = self.entities.join_new_player(spawn_point, join_msg);
+		let team = assign_team(self, join_msg.team);
+		let player_id = self.entities.join_new_player(spawn_point, join_msg, team);
+		if let Some(player) = self.entities.players.get_mut(&player_id) {
+			// joined during intermission: wait for the next map like everyone else.
+			player.frozen = self.match_state.phase == MatchPhase::Intermission;
+		}
 		let player = self.entities.players[&player_id].clone();
 		self.diffs.push(AddPlayer(player).to_all());
-		self.health.insert(player_id, default());
+		self.health.insert(player_id, Health::new(&self.rules));
 
-		let map_switch = MapSwitch {
+		(player_id, self.map_switch_msg())
+	}
+
+	/// Message telling clients to load the current map.
+	pub(crate) fn map_switch_msg(&self) -> MapSwitch {
+		MapSwitch {
 			map_name: self.map.name().into(),
 			entities: self.entities.clone(),
-		};
-
-		(player_id, map_switch)
+			gravity: self.map.gravity,
+			mode: self.map.mode,
+		}
 	}
 }
 
@@ -183,21 +299,22 @@ impl ServerState {
 
 pub(crate) fn handle_ready_to_respawn(state: &mut ServerState, player_id: ID) {
 	trace!("ready_to_respawn: {player_id}");
+	// client could request spawn multiple times in a network race.
+	if player(state, player_id).map(|p| p.spawned) != Some(false) {
+		return;
+	}
+	trace!("respawn {player_id}");
 	let spawn_point = pick_spawn_point(state);
 	state.force_apply_to_full(player_id, |p| {
-		if !p.spawned {
-			trace!("respawn {player_id}");
-			// client could request spawn multiple times in a network race.
-			p.spawned = true;
-			p.skeleton.target_position = spawn_point.position;
-			p.skeleton.orientation = spawn_point.orientation();
-		}
+		p.spawned = true;
+		p.skeleton.target_position = spawn_point.position;
+		p.skeleton.orientation = spawn_point.orientation();
 	});
-	state.health.entry(player_id).or_default().reset();
+	publish(state, GameEvent::Spawned { player: player_id });
 }
 
-fn pick_spawn_point(state: &ServerState) -> SpawnPoint {
-	pick_random(&state.map.spawn_points).cloned().unwrap_or_default()
+fn pick_spawn_point(state: &mut ServerState) -> SpawnPoint {
+	pick_random_with(&mut state.rng, &state.map.spawn_points).cloned().unwrap_or_default()
 }
 
 //-------------------------------------------------------------------------------- join/drop/switch players
@@ -205,7 +322,19 @@ fn pick_spawn_point(state: &ServerState) -> SpawnPoint {
 pub(crate) fn join_new_player(state: &mut ServerState, join_msg: JoinRequest) -> (ID, MapSwitch) {
 	let spawn_point = pick_spawn_point(state);
 
+	let requested_name = join_msg.name.clone();
+	let identity_key = join_msg.identity_key.clone();
+	let join_msg = JoinRequest {
+		name: unreserved_name(state, &join_msg),
+		..join_msg
+	};
 	let (player_id, map_switch) = state.join_new_player(&spawn_point, join_msg);
+	profile_join(state, player_id, &identity_key);
+	team_join(state, player_id);
+	if must_name(state, player_id) != requested_name {
+		let msg = format!("name {requested_name} is reserved, you joined as {}", must_name(state, player_id));
+		state.diffs.push(Log(msg).to_just(player_id));
+	}
 
 	state.scores.join_new_player(player_id);
 	// self.broadcast_scores(world);
@@ -213,14 +342,28 @@ pub(crate) fn join_new_player(state: &mut ServerState, join_msg: JoinRequest) ->
 	let map_name = state.map.name().to_string();
 	hud_announce(state, Just(player_id), map_name);
 	state.diffs.push(PlaySound(SoundEffect::raw(handle("ann_begin"))).to_just(player_id));
+	sync_movers(state, player_id);
 	(player_id, map_switch)
 }
 
 pub(crate) fn drop_player(state: &mut ServerState, player_id: ID) {
 	log(state, format!("{} left", must_name(state, player_id)));
+	record_leave(state, player_id);
+	profile_leave(state, player_id);
+	team_leave(state, player_id);
+	chat_leave(state, player_id);
+	bot_leave(state, player_id);
+	state.addresses.remove(&player_id);
 	state.drop_player(player_id);
 }
 
+/// Disconnect a player from the server.
+pub(crate) fn kick(state: &mut ServerState, player_id: ID, reason: &str) {
+	log(state, format!("{} was {reason}", must_name(state, player_id)));
+	state.diffs.push(Log(format!("you were {reason}")).to_just(player_id));
+	state.kicked.push(player_id);
+}
+
 /// Player name.
 pub(crate) fn player_name(state: &ServerState, id: ID) -> Option<&str> {
 	state.entities.players.get(&id).map(|p| p.name.as_str())
@@ -252,19 +395,21 @@ pub(crate) fn sound_announce(state: &mut ServerState, to: Addressee, clip_name:
 }
 
 pub(crate) fn sound_effect(state: &mut ServerState, clip_name: Handle, location: vec3, volume: f32) {
-	state.diffs.push(
-		PlaySound(SoundEffect {
-			clip_name: clip_name.into(),
-			volume,
-			spatial: Some(Spatial { location }),
-		})
-		.to_all(),
-	)
+	let sound = SoundEffect {
+		clip_name: clip_name.into(),
+		volume,
+		spatial: Some(Spatial { location }),
+	};
+	bots_hear(state, &sound);
+	state.diffs.push(PlaySound(sound).to_all())
 }
 
 pub(crate) fn despawn(state: &mut ServerState, id: ID) -> Option<()> {
 	trace!("despawn {id}");
-	state.apply_to_player_partial(id, |p| p.spawned = false)
+	state.apply_to_player_partial(id, |p| {
+		p.spawned = false;
+		p.powerups.remove_on_death();
+	})
 }
 
 pub(crate) fn player(state: &ServerState, id: ID) -> Option<&Player> {
@@ -7,8 +7,15 @@ use super::internal::*;
 /// Need to score at least this fast to count towards killing sprees.
 const SPREE_TIME: f32 = 3.0;
 
+/// Frags count towards the killer's spree (dying ends a spree, see `kill`).
+pub(crate) fn spree_event(state: &mut ServerState, event: &GameEvent) {
+	if let GameEvent::Killed { killer, teamkill: false, .. } = *event {
+		record_spree(state, killer);
+	}
+}
+
 // TODO: spree system should not depend on auto_switch. Need global time + timers.
-pub(crate) fn record_spree(state: &mut ServerState, player: ID) {
+fn record_spree(state: &mut ServerState, player: ID) {
 	//dbg!(&self.sprees);
 
      if let std::collections::hash_map::Entry::Vacant(e) = state.sprees.entry(player) {
@@ -22,6 +29,9 @@ pub(crate) fn record_spree(state: &mut ServerState, player: ID) {
 			let entry = entry.with(|e| e.1 += 1);
 			let n = entry.1;
 			state.sprees.insert(player, entry);
+			if n == 2 {
+				state.scores.by_player(player).multi_kills += 1;
+			}
 			hud_announce2(
 				state,
 				Just(player),
@@ -0,0 +1,119 @@
+//!
+//! Team assignment: auto-assign joining players, `team` command, automatic balancing.
+//!
+
+use super::internal::*;
+
+pub(crate) struct Teams {
+	/// Teams playing on the current map (see `apply_map_settings`).
+	pub active: Vec<Team>,
+	/// Move players to keep team sizes within one of each other.
+	pub auto_balance: bool,
+	/// Players in the order they joined (most recent last).
+	join_order: Vec<ID>,
+}
+
+impl Teams {
+	pub fn from_settings(settings: &ServerOpts) -> Self {
+		Self {
+			active: all_teams(),
+			auto_balance: settings.auto_balance,
+			join_order: default(),
+		}
+	}
+}
+
+/// All `NUM_TEAMS` teams.
+pub(crate) fn all_teams() -> Vec<Team> {
+	vec![Team::Red, Team::Blue, Team::Green]
+}
+
+/// Number of players on each active team.
+fn team_sizes(state: &ServerState) -> Vec<(Team, usize)> {
+	let players = &state.entities.players;
+	state.teams.active.iter().map(|&team| (team, players.values().filter(|p| p.team == team).count())).collect()
+}
+
+/// Active team with the fewest players (random between ties).
+fn smallest_team(state: &mut ServerState) -> Team {
+	let sizes = team_sizes(state);
+	let min = sizes.iter().map(|(_, n)| *n).min().unwrap_or_default();
+	let smallest = sizes.into_iter().filter(|(_, n)| *n == min).map(|(team, _)| team).collect_vec();
+	pick_random_with(&mut state.rng, &smallest).copied().unwrap_or(Team::Red)
+}
+
+/// Called before a player joins: their requested team if it is active, else the smallest team.
+pub(crate) fn assign_team(state: &mut ServerState, requested: Option<Team>) -> Team {
+	match requested {
+		Some(team) if state.teams.active.contains(&team) => team,
+		_ => smallest_team(state),
+	}
+}
+
+pub(crate) fn team_join(state: &mut ServerState, player_id: ID) {
+	state.teams.join_order.push(player_id);
+}
+
+pub(crate) fn team_leave(state: &mut ServerState, player_id: ID) {
+	state.teams.join_order.retain(|&id| id != player_id);
+}
+
+/// Put a player on another team. They respawn there.
+fn switch_team(state: &mut ServerState, player_id: ID, team: Team) {
+	state.apply_to_player_partial(player_id, |p| {
+		p.team = team;
+		p.spawned = false;
+	});
+	log(state, format!("{} joined {team}", must_name(state, player_id)));
+}
+
+/// Called after a map switch: move players off teams that are not active on the new map.
+pub(crate) fn reassign_inactive_teams(state: &mut ServerState) {
+	for id in ids(&state.entities.players) {
+		if player(state, id).map(|p| !state.teams.active.contains(&p.team)) == Some(true) {
+			let team = smallest_team(state);
+			switch_team(state, id, team);
+		}
+	}
+}
+
+/// If team sizes differ by two or more, move the most recent joiner from the largest to the smallest team.
+pub(crate) fn tick_team_balance(state: &mut ServerState) {
+	if !state.teams.auto_balance || state.map.mode != GameMode::TeamDeathmatch || state.match_state.phase == MatchPhase::Intermission {
+		return;
+	}
+
+	let sizes = team_sizes(state);
+	let (Some(&(largest, max)), Some(&(smallest, min))) = (sizes.iter().max_by_key(|(_, n)| *n), sizes.iter().min_by_key(|(_, n)| *n)) else { return };
+	if max < min + 2 {
+		return;
+	}
+
+	let most_recent = state.teams.join_order.iter().rev().copied().find(|&id| player(state, id).map(|p| p.team) == Some(largest));
+	if let Some(id) = most_recent {
+		switch_team(state, id, smallest);
+		hud_announce(state, Just(id), format!("You were moved to {smallest} to balance teams"));
+	}
+}
+
+/// `team <red|blue|green|auto>`: switch teams and respawn.
+pub(crate) fn team_cmd(state: &mut ServerState, player_id: ID, choice: &str) -> Result<()> {
+	let curr = player(state, player_id).map(|p| p.team).ok_or(anyhow!("no such player"))?;
+	let team = match parse_team_choice(choice)? {
+		None => smallest_team(state),
+		Some(team) if !state.teams.active.contains(&team) => bail!("{team} is not playing on this map (teams: {})", state.teams.active.iter().join(", ")),
+		Some(team) => team,
+	};
+	if team == curr {
+		bail!("you are already on {team}");
+	}
+	if state.teams.auto_balance && state.map.mode == GameMode::TeamDeathmatch {
+		let size = |t: Team| team_sizes(state).into_iter().find(|(team, _)| *team == t).map(|(_, n)| n).unwrap_or_default();
+		// after switching, `team` would have two players more than `curr`.
+		if size(team) >= size(curr) {
+			bail!("{team} has too many players");
+		}
+	}
+	switch_team(state, player_id, team);
+	Ok(())
+}
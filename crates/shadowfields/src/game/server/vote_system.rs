@@ -0,0 +1,246 @@
+//!
+//! Voting: players pick the next map during intermission,
+//! and can `callvote` a map switch or kick mid-match.
+//!
+
+use super::internal::*;
+
+/// Seconds before a `callvote` fails for lack of votes.
+const CALLVOTE_TIMEOUT: f32 = 30.0;
+
+#[derive(Default)]
+pub(crate) struct Votes {
+	/// Number of maps to offer during intermission (0: no map vote, just play the next map).
+	pub num_candidates: usize,
+
+	/// Maps offered during intermission (empty: no map vote in progress).
+	pub map_candidates: Vec<String>,
+	/// Player -> index in `map_candidates`.
+	pub map_votes: HashMap<ID, usize>,
+
+	/// Mid-match vote called by a player, if any.
+	pub callvote: Option<CallVote>,
+}
+
+pub(crate) struct CallVote {
+	pub subject: VoteSubject,
+	pub yes: Set<ID>,
+	pub no: Set<ID>,
+	pub timeout: Timer,
+}
+
+#[derive(Clone)]
+pub(crate) enum VoteSubject {
+	Map(String),
+	Kick(ID),
+}
+
+impl Votes {
+	pub fn from_settings(settings: &ServerOpts) -> Self {
+		Self {
+			num_candidates: settings.vote_candidates as usize,
+			..default()
+		}
+	}
+}
+
+pub(crate) fn tick_votes(state: &mut ServerState) {
+	let dt = state.dt;
+	if let Some(callvote) = &mut state.votes.callvote {
+		if callvote.timeout.tick(dt).just_finished() {
+			state.votes.callvote = None;
+			hud_announce(state, All, "Vote failed");
+		}
+	}
+	// players leaving can tip the balance.
+	if state.votes.callvote.is_some() {
+		tally_callvote(state);
+	}
+}
+
+//-------------------------------------------------------------------------------- map vote
+
+/// Offer a few random maps to vote on during intermission (see `end_match`).
+pub(crate) fn start_map_vote(state: &mut ServerState) {
+	let curr = state.map.name();
+	let num_players = state.entities.players.len();
+	let others = state.rotation.maplist.iter().filter(|e| e.name != curr && state.rotation.eligible(e, num_players)).map(|e| e.name.clone()).collect_vec();
+	state.votes.map_candidates = shuffled_with(&mut state.rng, others).into_iter().take(state.votes.num_candidates).collect();
+	state.votes.map_votes.clear();
+
+	if state.votes.map_candidates.len() > 1 {
+		log(state, "vote for the next map: `vote <number>`");
+		broadcast_map_vote(state);
+	} else {
+		// nothing to choose from
+		state.votes.map_candidates.clear();
+	}
+}
+
+/// Close the map vote and return the winner (randomly chosen between ties),
+/// or `None` if there was no vote or nobody voted.
+pub(crate) fn finish_map_vote(state: &mut ServerState) -> Option<String> {
+	let candidates = mem::take(&mut state.votes.map_candidates);
+	let votes = mem::take(&mut state.votes.map_votes);
+	if votes.is_empty() {
+		return None;
+	}
+
+	let tally = map_vote_tally(&candidates, &votes);
+	let max = tally.iter().copied().max().unwrap_or_default();
+	let winners = candidates.into_iter().zip(tally).filter(|(_, n)| *n == max).map(|(name, _)| name).collect_vec();
+	let winner = pick_random_with(&mut state.rng, &winners).cloned();
+	if let Some(winner) = &winner {
+		log(state, format!("{winner} won the vote with {max} vote(s)"));
+	}
+	winner
+}
+
+/// Number of votes for each candidate.
+fn map_vote_tally(candidates: &[String], votes: &HashMap<ID, usize>) -> Vec<u32> {
+	let mut tally = vec![0; candidates.len()];
+	for &i in votes.values() {
+		if let Some(n) = tally.get_mut(i) {
+			*n += 1;
+		}
+	}
+	tally
+}
+
+fn map_vote_cmd(state: &mut ServerState, player_id: ID, choice: &str) -> Result<()> {
+	let candidates = &state.votes.map_candidates;
+	let index = match choice.parse::<usize>() {
+		Ok(number) => number.checked_sub(1).filter(|&i| i < candidates.len()),
+		Err(_) => candidates.iter().position(|name| name == choice),
+	}
+	.ok_or_else(|| anyhow!("no such option: {choice}"))?;
+
+	state.votes.map_votes.insert(player_id, index);
+	broadcast_map_vote(state);
+	Ok(())
+}
+
+fn broadcast_map_vote(state: &mut ServerState) {
+	let tally = map_vote_tally(&state.votes.map_candidates, &state.votes.map_votes);
+	let mut text = String::from("next map: `vote <number>`");
+	for (i, (name, n)) in state.votes.map_candidates.iter().zip(tally).enumerate() {
+		text += &format!("\n{}) {name}: {n}", i + 1);
+	}
+
+	state.diffs.push(
+		UpdateHUD(HUDUpdate {
+			pos: HUDPos::TopLeft,
+			text,
+			ttl_sec: state.match_state.intermission_time,
+		})
+		.to_all(),
+	);
+}
+
+//-------------------------------------------------------------------------------- callvote
+
+pub(crate) fn callvote_cmd(state: &mut ServerState, caller: ID, subject: VoteSubject) -> Result<()> {
+	if state.match_state.phase == MatchPhase::Intermission {
+		bail!("cannot call a vote during intermission");
+	}
+	if state.votes.callvote.is_some() {
+		bail!("a vote is already in progress");
+	}
+
+	let description = match &subject {
+		VoteSubject::Map(map_name) => {
+			if state.rotation.entry(map_name).is_none() {
+				bail!("map not in maplist: {map_name}");
+			}
+			format!("switch to {map_name}")
+		}
+		VoteSubject::Kick(id) => format!("kick {}", must_name(state, *id)),
+	};
+
+	state.votes.callvote = Some(CallVote {
+		subject,
+		yes: default(),
+		no: default(),
+		timeout: Timer::one_off(CALLVOTE_TIMEOUT),
+	});
+	hud_announce(state, All, format!("{} called a vote: {description}", must_name(state, caller)));
+	log(state, "`vote yes` or `vote no`");
+	cast_callvote(state, caller, true)
+}
+
+fn cast_callvote(state: &mut ServerState, player_id: ID, yes: bool) -> Result<()> {
+	let callvote = state.votes.callvote.as_mut().ok_or_else(|| anyhow!("no vote in progress"))?;
+	match yes {
+		true => (callvote.yes.insert(player_id), callvote.no.remove(&player_id)),
+		false => (callvote.no.insert(player_id), callvote.yes.remove(&player_id)),
+	};
+	if let Some((num_yes, num_no, num_players)) = tally_callvote(state) {
+		log(state, format!("vote: {num_yes} yes, {num_no} no ({num_players} players)"));
+	}
+	Ok(())
+}
+
+/// Pass or fail the callvote if there is a majority, else return the counts (yes, no, players).
+/// Majority rules: more than half of the (human) players (not just those who voted).
+/// Votes of players who left don't count.
+fn tally_callvote(state: &mut ServerState) -> Option<(usize, usize, usize)> {
+	let num_players = num_humans(state);
+	let callvote = state.votes.callvote.as_ref()?;
+	let count = |votes: &Set<ID>| votes.iter().filter(|id| state.entities.players.contains_key(id)).count();
+	let (num_yes, num_no) = (count(&callvote.yes), count(&callvote.no));
+
+	if 2 * num_yes > num_players {
+		let subject = callvote.subject.clone();
+		state.votes.callvote = None;
+		hud_announce(state, All, "Vote passed");
+		match subject {
+			VoteSubject::Map(map_name) => switch_map(state, &map_name),
+			VoteSubject::Kick(victim) => kick(state, victim, "kicked by vote"),
+		}
+	} else if 2 * num_no >= num_players {
+		state.votes.callvote = None;
+		hud_announce(state, All, "Vote failed");
+	} else {
+		return Some((num_yes, num_no, num_players));
+	}
+	None
+}
+
+//-------------------------------------------------------------------------------- commands
+
+/// `vote yes|no` during a callvote, `vote <number|map>` during intermission.
+pub(crate) fn vote_cmd(state: &mut ServerState, player_id: ID, choice: &str) -> Result<()> {
+	match choice {
+		"yes" | "no" => cast_callvote(state, player_id, choice == "yes"),
+		_ if !state.votes.map_candidates.is_empty() => map_vote_cmd(state, player_id, choice),
+		_ => bail!("nothing to vote on"),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::game::server::server_sim::ServerSim;
+
+	#[test]
+	fn map_vote_tally() {
+		let candidates = ["a", "b", "c"].map(String::from);
+		let votes = HashMap::from_iter([(ID::new(), 1), (ID::new(), 1), (ID::new(), 2), (ID::new(), 7)]);
+		assert_eq!(super::map_vote_tally(&candidates, &votes), vec![0, 2, 1]);
+	}
+
+	#[test]
+	fn callvote_after_leave() {
+		let mut sim = ServerSim::new();
+		let [alice, bob, carol, dave] = ["alice", "bob", "carol", "dave"].map(|name| sim.join(name, None));
+		sim.send(alice, ClientMsg::Command("callvote kick carol".into()));
+		sim.send(bob, ClientMsg::Command("vote yes".into()));
+		sim.tick(1);
+		assert!(sim.state.votes.callvote.is_some(), "2 out of 4 is no majority");
+
+		sim.state.handle_drop_player(dave);
+		sim.tick(1);
+		assert!(sim.state.votes.callvote.is_none());
+		assert_eq!(sim.state.kicked, vec![carol]);
+	}
+}
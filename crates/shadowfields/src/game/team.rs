@@ -3,8 +3,11 @@ use super::internal::*;
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Hash)]
 #[repr(u8)]
 pub enum Team {
+	#[serde(alias = "red")]
 	Red = 0,
+	#[serde(alias = "blue")]
 	Blue = 1,
+	#[serde(alias = "green")]
 	Green = 2,
 }
 
@@ -20,6 +23,24 @@ impl Team {
 			Team::Green => vec3(0.5, 1.0, 0.3),
 		}
 	}
+
+	/// Solid team color, e.g. for the powerup glow around players.
+	pub fn glow_texture(self) -> Handle {
+		match self {
+			Team::Red => handle("#FF5050"),
+			Team::Blue => handle("#5070FF"),
+			Team::Green => handle("#50FF50"),
+		}
+	}
+
+	/// Team-colored particle texture (confetti beams, explosions,...).
+	pub fn star_texture(self) -> Handle {
+		match self {
+			Team::Red => handle("star_red"),
+			Team::Blue => handle("star_blue"),
+			Team::Green => handle("star_green"),
+		}
+	}
 }
 
 impl FromStr for Team {
@@ -27,7 +48,7 @@ impl FromStr for Team {
 
 	fn from_str(s: &str) -> Result<Self> {
 		use Team::*;
-		match s {
+		match s.to_ascii_lowercase().as_str() {
 			"red" => Ok(Red),
 			"blu" | "blue" => Ok(Blue),
 			"green" => Ok(Green),
@@ -45,3 +66,11 @@ impl fmt::Display for Team {
 		}
 	}
 }
+
+/// Parse a team preference: a team name, or `auto` (None) to let the server pick.
+pub fn parse_team_choice(s: &str) -> Result<Option<Team>> {
+	match s {
+		"auto" => Ok(None),
+		s => Ok(Some(s.parse()?)),
+	}
+}
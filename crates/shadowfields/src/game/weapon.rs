@@ -0,0 +1,161 @@
+//!
+//! Shooting: trigger logic, line of fire and hit detection.
+//! Results are `ClientMsg`s, so that it can be used by the client
+//! as well as by bots running inside the server (see `tick_bots`).
+//!
+
+use super::internal::*;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct WeaponState {
+	pub cooldown_timer: f32,
+	pub cooldown_secs: f32,
+	pub armed: bool,
+	pub automatic: bool,
+}
+
+impl Default for WeaponState {
+	fn default() -> Self {
+		Self {
+			cooldown_timer: default(),
+			cooldown_secs: 0.7, /* like instagib */
+			armed: true,
+			automatic: false,
+		}
+	}
+}
+
+impl WeaponState {
+	/// Semi-auto shooting:
+	/// pressing too early will shoot as soon as ready,
+	/// but the next shot requires a trigger release first.
+	/// Returns `true` if a shot must be fired now (see `shoot`).
+	pub fn tick(&mut self, dt: f32, inputs: &Inputs) -> bool {
+		self.cooldown_timer -= dt;
+		if self.automatic || inputs.just_pressed(Button::Mouse1) {
+			self.armed = true;
+		}
+		if self.armed && self.cooldown_timer <= 0.0 && inputs.was_pressed(Button::Mouse1) {
+			self.cooldown_timer = self.cooldown_secs;
+			self.armed = false;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// Fire a shot: visual and sound effects, and hit detection.
+/// `rng` picks the sound effects.
+pub(crate) fn shoot(world: (&Map, &Entities), player: &Player, rng: &mut impl Rng, out: &mut ClientMsgs) {
+	let (map, _) = world;
+	let line_of_fire = line_of_fire(world, player);
+	let start = line_of_fire.start.to_f32();
+	let end = crosshair_target(world, player);
+	let delta = end - start;
+	let len = delta.len();
+	let dir = delta.safe_normalized();
+	let orientation = Orientation::from_look_dir(dir);
+
+	let texture = player.team.star_texture();
+	out.push(ClientMsg::ShotFired);
+	out.push(ClientMsg::AddEffect(Effect::particle_beam(start, orientation, len, texture)));
+
+	out.push(ClientMsg::PlaySound(SoundEffect::spatial(
+		must_pick_random_with(rng, &[handle("bang1"), handle("bang2"), handle("bang3"), handle("bang4")]),
+		player.center(),
+		30.0,
+	)));
+	out.push(ClientMsg::PlaySound(SoundEffect::spatial(
+		must_pick_random_with(rng, &[handle("ricochet1"), handle("ricochet2"), handle("ricochet3"), handle("ricochet4")]),
+		end,
+		1.0,
+	)));
+
+	if let Some((_, Some(victim_id))) = _intersect_except_player(world, player.id, &line_of_fire) {
+		// Hit a player
+		//out.push(ClientMsg::PlaySound(SoundEffect::spatial(handle("kill"), end, 1.0))); << must be in server
+		out.push(ClientMsg::HitPlayer(victim_id));
+	} else {
+		let hr = map.intersect(&line_of_fire.convert());
+		if let Some((normal, _, material)) = hr.attrib {
+			// Hit a wall: debris effect
+			let pos = line_of_fire.convert::<f32>().at(hr.t);
+			let texture = map.material_palette.get(&material).map(|mat| mat.base_color).unwrap_or(handle("#666666"));
+			out.push(ClientMsg::AddEffect(Effect::debris(pos, normal, texture)));
+		}
+	}
+}
+
+/// Ray from the player's gun nozzle to where the player is looking.
+/// I.e., the trajectory a bullet would follow.
+fn line_of_fire(world: (&Map, &Entities), player: &Player) -> Ray64 {
+	let start = gun_center(player);
+	let look_at = look_at(world, player);
+	let shoot_from_gun = Ray64::new(start.into(), (look_at - start).normalized().into());
+
+	// Because of parallax between the nozzle and camera position,
+	// an object can sometimes be in front of the gun but not in front of the camera.
+	// This can lead to seemingly inexplicably missed shots.
+	//
+	// Many games have this behavior when shooting from the hip.
+	// However, here it is particularly severe as the gun is quite far from the camera.
+	// Therefore, when an object blocks the line of fire but not the line of sight,
+	// shoot from the "eye" rather than from the hip so that the shot is not missed.
+	let shoot_at = _intersect_except_player(world, player.id, &shoot_from_gun).map(|(t, _)| shoot_from_gun.at(t).to_f32());
+	if let Some(shoot_at) = shoot_at {
+		if (shoot_at - look_at).len() > 2.0 {
+			return player.line_of_sight();
+		}
+	}
+
+	shoot_from_gun
+}
+
+/// (Absolute) position of the player's gun.
+/// Note: this is not necessarily the position where a bullet starts,
+/// use `line_of_fire().start` for that.
+fn gun_center(player: &Player) -> vec3 {
+	let gun_internal = gun_pos_internal(player);
+	player.position() + player.skeleton.orientation.look_right() * gun_internal.x() + gun_internal.y() * vec3::EY
+}
+
+/// Position the user is looking at.
+/// If looking at the (infinitely far) sky,
+/// this returns a far-away point in the looking direction.
+fn look_at(world: (&Map, &Entities), player: &Player) -> vec3 {
+	let line_of_sight = player.line_of_sight();
+	_intersect_except_player(world, player.id, &line_of_sight)
+		.map(|(t, _)| line_of_sight.at(t))
+		.unwrap_or(player.camera().position.to_f64() + 10000.0 * player.orientation().look_dir().to_f64())
+		.into()
+}
+
+/// (Absolute) position where the player's gun would hit if it fired.
+fn crosshair_target(world: (&Map, &Entities), player: &Player) -> vec3 {
+	let line_of_fire = line_of_fire(world, player);
+	line_of_fire
+		.at(_intersect_except_player(world, player.id, &line_of_fire).map(|(t, _)| t + 0.01).unwrap_or(10000.0))
+		.into()
+}
+
+pub(crate) fn gun_pos_internal(player: &Player) -> vec3 {
+	vec3(player.torso_size.x(), 0.5 * player.torso_size.y(), 0.0)
+}
+
+/// Intersect a ray (e.g. a line of sight) with the map and players except `player_id`
+/// (to avoid shooting yourself right where the line of fire exits your hitbox).
+/// Returns intersection distance along the ray
+/// and  the ID of the nearest hit player, if any.
+pub(crate) fn _intersect_except_player((map, entities): (&Map, &Entities), player_id: ID, ray: &Ray64) -> Option<(f64, Option<ID>)> {
+	let intersect_map = map.intersect_t_64(ray);
+	let mut nearest = intersect_map.map(|t| (t, None));
+	for (&id, player) in entities.players.iter() {
+		if let Some(t) = player.intersect(ray) {
+			if t < nearest.map(|(t, _)| t).unwrap_or(f64::INFINITY) && id != player_id {
+				nearest = Some((t, Some(id)));
+			}
+		}
+	}
+	nearest
+}
@@ -26,4 +26,18 @@ impl AnimVAOs {
 			index_range: None,
 		}
 	}
+
+	/// Glowing shell around the animated model, same frame as `draw_animated`.
+	pub fn draw_highlight(&self, tex: &Arc<Texture>, matrix: mat4, t: f32, intensity: f32) -> Object {
+		let ctx = ctx();
+		let r = t * (self.vaos.len() as f32);
+		let i = (r.floor() as usize).min(self.vaos.len() - 1);
+		let t = r % 1.0;
+
+		Object {
+			vao: self.vaos[i].clone(),
+			shader: ctx.shader_pack.highlight_animation(tex, matrix, t, intensity),
+			index_range: None,
+		}
+	}
 }
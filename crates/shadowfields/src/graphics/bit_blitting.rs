@@ -9,7 +9,9 @@ use super::internal::*;
 /// |    +-+ |      |      +-+         |
 /// +--------+      |                  |
 ///                 +------------------+
-pub(crate) fn blit(tex_pixels: uvec2, tex_pos: uvec2, sprite_pixels: uvec2, scrn_pixels: uvec2, scrn_pos: uvec2, scale: u32) -> MeshBuffer {
+///
+/// `color` multiplies the texture color (passed to `text.wgsl` as the vertex normal).
+pub(crate) fn blit(tex_pixels: uvec2, tex_pos: uvec2, sprite_pixels: uvec2, scrn_pixels: uvec2, scrn_pos: uvec2, scale: u32, color: vec3) -> MeshBuffer {
 	let sprite_pixels = sprite_pixels.to_f32();
 	let tex_pixels = tex_pixels.to_f32();
 
@@ -29,10 +31,10 @@ pub(crate) fn blit(tex_pixels: uvec2, tex_pos: uvec2, sprite_pixels: uvec2, scrn
 
 	let z = 0.0;
 	let vertices = [
-		VertexLM::new_texcoords(vec3(dst_x0, dst_y0, z), vec2(uv0.x(), uv0.y())),
-		VertexLM::new_texcoords(vec3(dst_x1, dst_y0, z), vec2(uv1.x(), uv0.y())),
-		VertexLM::new_texcoords(vec3(dst_x1, dst_y1, z), vec2(uv1.x(), uv1.y())),
-		VertexLM::new_texcoords(vec3(dst_x0, dst_y1, z), vec2(uv0.x(), uv1.y())),
+		VertexLM::new(vec3(dst_x0, dst_y0, z), vec2(uv0.x(), uv0.y()), color),
+		VertexLM::new(vec3(dst_x1, dst_y0, z), vec2(uv1.x(), uv0.y()), color),
+		VertexLM::new(vec3(dst_x1, dst_y1, z), vec2(uv1.x(), uv1.y()), color),
+		VertexLM::new(vec3(dst_x0, dst_y1, z), vec2(uv0.x(), uv1.y()), color),
 	];
 
 	MeshBuffer::rect(&vertices)
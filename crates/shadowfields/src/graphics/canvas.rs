@@ -221,6 +221,18 @@ impl Canvas {
 						// ! keep the pace with the transforms copied by upload_instance_buffer().
 						advance_instance_id();
 					}
+					Highlight(texture_bindings, _) => {
+						render_pass.set_pipeline(&shaders.highlight_pipeline.pipeline);
+						render_pass.set_bind_group(0, texture_bindings, &[]);
+						// ! keep the pace with the transforms copied by upload_instance_buffer().
+						advance_instance_id();
+					}
+					HighlightAnimation(texture_bindings, _) => {
+						render_pass.set_pipeline(&shaders.highlight_animation_pipeline.pipeline);
+						render_pass.set_bind_group(0, texture_bindings, &[]);
+						// ! keep the pace with the transforms copied by upload_instance_buffer().
+						advance_instance_id();
+					}
 				}
 
 				// TODO: handle gracefully (e.g. do not draw: `continue`). clamping will still draw, just use previous entity transform
@@ -274,7 +286,7 @@ impl Canvas {
 		use Shader::*;
 		for obj in &scene.objects {
 			match &obj.shader {
-				Entity(_, data) | Particles(_, data) | Debris(_, data) | Animation(_, data) => try_push(&mut self.instance_storage.host_data, data.clone()),
+				Entity(_, data) | Particles(_, data) | Debris(_, data) | Animation(_, data) | Highlight(_, data) | HighlightAnimation(_, data) => try_push(&mut self.instance_storage.host_data, data.clone()),
 				Flat(_) => (),
 				Lines(_) => (),
 				Lightmap(_) => (),
@@ -20,6 +20,9 @@ pub struct GraphicsOpts {
 	pub shadows: bool,
 	#[serde(default = "default_true")]
 	pub hud: bool,
+	/// Replay the killer's view after getting fragged.
+	#[serde(default = "default_true")]
+	pub killcam: bool,
 }
 
 fn default_true() -> bool {
@@ -72,6 +75,7 @@ impl Default for GraphicsOpts {
 			vsync: true,
 			shadows: true,
 			hud: true,
+			killcam: true,
 		}
 	}
 }
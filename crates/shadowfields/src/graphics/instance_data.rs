@@ -1,7 +1,7 @@
 use super::internal::*;
 
 /// Data passed to each instance.
-/// Used by shaders like `entity.wgsl`, `animation.wgsl``,
+/// Used by shaders like `entity.wgsl`, `animation.wgsl`, `highlight.wgsl`,
 /// by appending `globals.wgsl`
 ///
 /// ! `repr(C)` required by WGPU.
@@ -17,6 +17,6 @@ pub(crate) struct InstanceData {
 	pub lightbox: LightBox,
 	pub model_matrix: [[f32; 4]; 4],
 	pub time: f32,
-	pub unused: f32,
+	pub intensity: f32, // highlight.wgsl: glow strength, unused by other shaders.
 	pub _padding: [f32; 2],
 }
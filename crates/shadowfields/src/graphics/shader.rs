@@ -15,4 +15,6 @@ pub(crate) enum Shader {
 	Particles(Arc<wgpu::BindGroup>, InstanceData),
 	Debris(Arc<wgpu::BindGroup>, InstanceData),
 	Animation(Arc<wgpu::BindGroup>, InstanceData),
+	Highlight(Arc<wgpu::BindGroup>, InstanceData),
+	HighlightAnimation(Arc<wgpu::BindGroup>, InstanceData),
 }
@@ -14,6 +14,8 @@ pub(crate) struct ShaderPack {
 	pub particles_pipeline: ParticlesPipeline,
 	pub debris_pipeline: ParticlesPipeline,
 	pub animation_pipeline: AnimationPipeline,
+	pub highlight_pipeline: HighlightPipeline,
+	pub highlight_animation_pipeline: HighlightPipeline,
 	pub font_texture: Arc<Texture>,
 }
 
@@ -32,6 +34,8 @@ impl ShaderPack {
 		let particles_pipeline = ParticlesPipeline::new(opts, &device, surface_format, &global_uniforms_layout, true /* additive blend */);
 		let debris_pipeline = ParticlesPipeline::new(opts, &device, surface_format, &global_uniforms_layout, false /* additive blend */);
 		let animation_pipeline = AnimationPipeline::new(opts, &device, surface_format, &global_uniforms_layout);
+		let highlight_pipeline = HighlightPipeline::new(opts, &device, surface_format, &global_uniforms_layout, false /*animated*/);
+		let highlight_animation_pipeline = HighlightPipeline::new(opts, &device, surface_format, &global_uniforms_layout, true /*animated*/);
 
 		Self {
 			device,
@@ -43,6 +47,8 @@ impl ShaderPack {
 			flat_texture_pipeline,
 			entity_pipeline,
 			animation_pipeline,
+			highlight_pipeline,
+			highlight_animation_pipeline,
 			particles_pipeline,
 			debris_pipeline,
 			font_texture,
@@ -88,7 +94,7 @@ impl ShaderPack {
 				lightbox: *lightbox,
 				model_matrix: transform.into(),
 				time: default(),
-				unused: default(),
+				intensity: default(),
 				_padding: default(),
 			},
 		)
@@ -105,7 +111,7 @@ impl ShaderPack {
 				lightbox: default(), // <<
 				model_matrix: transform.into(),
 				time: phase,
-				unused: default(),
+				intensity: default(),
 				_padding: default(),
 			},
 		)
@@ -122,7 +128,7 @@ impl ShaderPack {
 				lightbox: default(), // <<
 				model_matrix: transform.into(),
 				time: phase,
-				unused: default(),
+				intensity: default(),
 				_padding: default(),
 			},
 		)
@@ -141,11 +147,36 @@ impl ShaderPack {
 				lightbox: *lightbox,
 				model_matrix: transform.into(),
 				time: t,
-				unused: default(),
+				intensity: default(),
 				_padding: default(),
 			},
 		)
 	}
+
+	/// Glowing shell around a static mesh (see `highlight.wgsl`). `intensity`: 0..1.
+	pub fn highlight(&self, texture: &Texture, transform: mat4, intensity: f32) -> Shader {
+		Shader::Highlight(
+			Arc::new(self.highlight_pipeline.texture_bind_group(&self.device, texture)),
+			InstanceData {
+				model_matrix: transform.into(),
+				intensity,
+				..default()
+			},
+		)
+	}
+
+	/// Glowing shell around a morph-target animation (see `highlight.wgsl`). `intensity`: 0..1.
+	pub fn highlight_animation(&self, texture: &Texture, transform: mat4, t: f32, intensity: f32) -> Shader {
+		Shader::HighlightAnimation(
+			Arc::new(self.highlight_animation_pipeline.texture_bind_group(&self.device, texture)),
+			InstanceData {
+				model_matrix: transform.into(),
+				time: t,
+				intensity,
+				..default()
+			},
+		)
+	}
 }
 
 fn _embedded_font_texture(ctx: (&Device, &Queue)) -> Texture {
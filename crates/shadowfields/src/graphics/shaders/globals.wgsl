@@ -16,7 +16,7 @@ struct InstanceData {
     lightbox: array<array<array<vec4f, 2>,2>,2>,
     model_matrix: mat4x4f,
     time: f32,
-    unused: f32,
+    intensity: f32,
     _padding: vec2f,
 }
 
@@ -1,36 +1,67 @@
-struct Globals {
-    view_proj: mat4x4f,
-    cam_position: vec3f,
-    sun_dir: vec3f,
-    sun_color: vec3f,
-};
+// import "globals.wgsl"
+
+// Glowing shell around an entity (e.g. players with a powerup).
+// Vertices are pushed outwards along their normal,
+// color is taken from the texture (typically a solid team color), strongest at grazing angles.
 
-@group(1) @binding(0)
-var<uniform> globals: Globals;
+const SHELL_THICKNESS: f32 = 0.03;
 
-struct VertexInput {
+struct StaticVertexInput {
+    // See wgpu::VertexBufferLayout in vertex_lm.rs
     @location(0) position: vec3f,
     @location(1) tex_coords: vec2f,
-    // unused:
     @location(2) normal: vec3f,
+    // unused:
     @location(3) lm_coords: vec2f,
     @location(4) tangent_u: vec3f,
     @location(5) tangent_v: vec3f,
 };
 
+struct AnimatedVertexInput {
+    // See wgpu::VertexBufferLayout in vertex_kf.rs
+    @location(0) tex_coords: vec2f,
+    @location(1) position1: vec3f,
+    @location(2) position2: vec3f,
+    @location(3) normal1: vec3f,
+    @location(4) normal2: vec3f,
+};
+
 struct VertexOutput {
     @builtin(position) clip_position: vec4f,
     @location(0) tex_coords: vec2f,
+    @location(1) normal: vec3f,
+    @location(2) world_position: vec3f,
+    @location(3) intensity: f32,
 };
 
-@vertex
-fn vs_main(model: VertexInput) -> VertexOutput {
+fn shell_vertex(inst_idx: u32, position: vec3f, normal: vec3f, tex_coords: vec2f) -> VertexOutput {
+    let model_matrix = instance_data[inst_idx].model_matrix;
+
     var out: VertexOutput;
-    out.tex_coords = model.tex_coords;
-    out.clip_position = globals.view_proj * vec4(model.position, 1.0);
+
+    out.normal = normalize((model_matrix * vec4(normal, 0.0)).xyz);
+    let world_position = (model_matrix * vec4(position, 1.0)).xyz + SHELL_THICKNESS * out.normal;
+    out.world_position = world_position;
+    out.clip_position = globals.view_proj * vec4(world_position, 1.0);
+    out.tex_coords = tex_coords;
+    out.intensity = instance_data[inst_idx].intensity;
+
     return out;
 }
 
+@vertex
+fn vs_static(model: StaticVertexInput, @builtin(instance_index) inst_idx: u32) -> VertexOutput {
+    return shell_vertex(inst_idx, model.position, model.normal, model.tex_coords);
+}
+
+@vertex
+fn vs_animated(model: AnimatedVertexInput, @builtin(instance_index) inst_idx: u32) -> VertexOutput {
+    let t = instance_data[inst_idx].time;
+    let position = (1.0 - t) * model.position1 + t * model.position2;
+    let normal = normalize((1.0 - t) * model.normal1 + t * model.normal2);
+    return shell_vertex(inst_idx, position, normal, model.tex_coords);
+}
+
 // Fragment shader
 
 @group(0) @binding(0)
@@ -41,7 +72,8 @@ var s_diffuse: sampler;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4f {
-    var color: vec4f = textureSample(t_diffuse, s_diffuse, in.tex_coords);
-    color.w = 0.25;
-    return color;
-}
\ No newline at end of file
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords).xyz;
+    let view_dir = normalize(globals.cam_position - in.world_position);
+    let rim = 1.0 - abs(dot(view_dir, in.normal));
+    return vec4(color, in.intensity * (0.2 + 0.8 * rim));
+}
@@ -1,16 +1,19 @@
 use super::internal::*;
 
-// TODO: dead, remove (editor functinality)
+/// Additive, team-colored glow around entities (see `highlight.wgsl`).
+/// Draws either static meshes (`VertexLM`, like `EntityPipeline`)
+/// or morph-target animations (`VertexKF`, like `AnimationPipeline`).
 pub(crate) struct HighlightPipeline {
 	pub pipeline: wgpu::RenderPipeline,
 	texture_bind_group_layout: wgpu::BindGroupLayout,
 }
 
 impl HighlightPipeline {
-	pub fn new(opts: &GraphicsOpts, device: &wgpu::Device, surface_format: wgpu::TextureFormat, global_uniforms_layout: &wgpu::BindGroupLayout, lines: bool) -> Self {
+	pub fn new(opts: &GraphicsOpts, device: &wgpu::Device, surface_format: wgpu::TextureFormat, global_uniforms_layout: &wgpu::BindGroupLayout, animated: bool) -> Self {
+		let src = concat!(include_str!("globals.wgsl"), include_str!("highlight.wgsl"));
 		let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
 			label: Some(file!()),
-			source: wgpu::ShaderSource::Wgsl(include_str!("highlight.wgsl").into()),
+			source: wgpu::ShaderSource::Wgsl(src.into()),
 		});
 
 		let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -37,17 +40,28 @@ impl HighlightPipeline {
 			label: Some(file!()),
 		});
 
+		let instance_light_bind_group_layout = StorageBuffer::<InstanceData>::make_bind_group_layout(device);
+
+		let (vs_entry_point, vertex_buffers) = match animated {
+			true => ("vs_animated", [VertexKF::desc()]),
+			false => ("vs_static", [VertexLM::desc()]),
+		};
+
 		let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
 			label: Some(file!()),
 			layout: Some(&device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
 				label: Some(file!()),
-				bind_group_layouts: &[&texture_bind_group_layout, global_uniforms_layout],
+				bind_group_layouts: &[
+					&texture_bind_group_layout,        // highlight.wgsl @group(0): t_diffuse, s_diffuse
+					global_uniforms_layout,            // highlight.wgsl @group(1): globals
+					&instance_light_bind_group_layout, // highlight.wgsl @group(2): instance_data
+				],
 				push_constant_ranges: &[],
 			})),
 			vertex: wgpu::VertexState {
 				module: &shader,
-				entry_point: "vs_main",
-				buffers: &[VertexLM::desc()],
+				entry_point: vs_entry_point,
+				buffers: &vertex_buffers, // <- arguments passed to vs_static / vs_animated (highlight.wgsl)
 			},
 			fragment: Some(wgpu::FragmentState {
 				module: &shader,
@@ -55,17 +69,18 @@ impl HighlightPipeline {
 				targets: &[Some(wgpu::ColorTargetState {
 					format: surface_format,
 					blend: Some(wgpu::BlendState {
-						color: wgpu::BlendComponent::OVER, // TODO ////////////////////
+						color: wgpu::BlendComponent {
+							src_factor: wgpu::BlendFactor::SrcAlpha,
+							dst_factor: wgpu::BlendFactor::One,
+							operation: wgpu::BlendOperation::Add,
+						},
 						alpha: wgpu::BlendComponent::OVER,
 					}),
 					write_mask: wgpu::ColorWrites::ALL,
 				})],
 			}),
 			primitive: wgpu::PrimitiveState {
-				topology: match lines {
-					true => wgpu::PrimitiveTopology::LineList,
-					false => wgpu::PrimitiveTopology::TriangleList,
-				},
+				topology: wgpu::PrimitiveTopology::TriangleList,
 				strip_index_format: None,
 				front_face: wgpu::FrontFace::Cw,
 				cull_mode: Some(wgpu::Face::Front),
@@ -75,15 +90,10 @@ impl HighlightPipeline {
 			},
 			depth_stencil: Some(wgpu::DepthStencilState {
 				format: Canvas::DEPTH_FORMAT,
-				depth_write_enabled: false,
+				depth_write_enabled: false, // translucent shell: don't hide what's behind
 				depth_compare: wgpu::CompareFunction::Less,
 				stencil: wgpu::StencilState::default(),
-				bias: wgpu::DepthBiasState {
-					// https://gpuweb.github.io/gpuweb/#abstract-opdef-biased-fragment-depth
-					constant: -10,
-					slope_scale: 0.0,
-					clamp: -100.0,
-				},
+				bias: wgpu::DepthBiasState::default(),
 			}),
 			multisample: wgpu::MultisampleState {
 				count: opts.msaa_sample_count(),
@@ -3,6 +3,7 @@ mod internal;
 mod animation_pipeline;
 mod entity_pipeline;
 mod flat_texture_pipeline;
+mod highlight_pipeline;
 mod lightmap_pipeline;
 mod normalmap_pipeline;
 mod particles_pipeline;
@@ -13,6 +14,7 @@ mod text_pipeline;
 pub(crate) use animation_pipeline::*;
 pub(crate) use entity_pipeline::*;
 pub(crate) use flat_texture_pipeline::*;
+pub(crate) use highlight_pipeline::*;
 pub(crate) use lightmap_pipeline::*;
 pub(crate) use normalmap_pipeline::*;
 pub(crate) use particles_pipeline::*;
@@ -3,8 +3,9 @@
 struct VertexInput {
     @location(0) position: vec3f,
     @location(1) tex_coords: vec2f,
+    // used as text color (see bit_blitting.rs)
+    @location(2) color: vec3f,
     // unused:
-    @location(2) normal: vec3f,
     @location(3) lm_coords: vec2f,
     @location(4) tangent_u: vec3f,
     @location(5) tangent_v: vec3f,
@@ -13,12 +14,14 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4f,
     @location(0) tex_coords: vec2f,
+    @location(1) color: vec3f,
 };
 
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.tex_coords = model.tex_coords;
+    out.color = model.color;
     out.clip_position = vec4(model.position, 1.0);
     return out;
 }
@@ -33,5 +36,6 @@ var s_diffuse: sampler;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4f {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let tex = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    return vec4(in.color * tex.rgb, tex.a);
 }
\ No newline at end of file
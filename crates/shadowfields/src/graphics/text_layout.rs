@@ -14,6 +14,11 @@ pub const UI_SCALE: u32 = 2;
 ///  +----------------+
 ///
 pub(crate) fn layout_text(viewport_size: uvec2, pos: uvec2, text: &str) -> MeshBuffer {
+	layout_text_colored(viewport_size, pos, text, WHITE)
+}
+
+/// Like `layout_text`, but with the font color multiplied by `color`.
+pub(crate) fn layout_text_colored(viewport_size: uvec2, pos: uvec2, text: &str, color: vec3) -> MeshBuffer {
 	let char_stride = UI_SCALE * _EMBEDDED_CHAR_SIZE;
 
 	let mut buf = MeshBuffer::new();
@@ -33,7 +38,7 @@ pub(crate) fn layout_text(viewport_size: uvec2, pos: uvec2, text: &str) -> MeshB
 			char_pos[1] += char_stride.y();
 		}
 
-		buf.append(&blit_chr(viewport_size, char_pos, byte, UI_SCALE));
+		buf.append(&blit_chr(viewport_size, char_pos, byte, UI_SCALE, color));
 
 		char_pos[0] += char_stride.x();
 	}
@@ -96,12 +101,12 @@ pub(crate) fn viewport_size_chars(viewport_size_pix: uvec2) -> uvec2 {
 }
 
 /// A mesh for copying a single character to the screen.
-fn blit_chr(scrn_pixels: uvec2, scrn_pos: uvec2, char: u8, scale: u32) -> MeshBuffer {
+fn blit_chr(scrn_pixels: uvec2, scrn_pos: uvec2, char: u8, scale: u32, color: vec3) -> MeshBuffer {
 	let tex_pixels = EMBEDDED_FONTMAP_SIZE;
 	let sprite_pixels = _EMBEDDED_CHAR_SIZE;
 	let tex_pos = chr_tex_pos_16x8(char, sprite_pixels);
 
-	blit(tex_pixels, tex_pos, sprite_pixels, scrn_pixels, scrn_pos, scale)
+	blit(tex_pixels, tex_pos, sprite_pixels, scrn_pixels, scrn_pos, scale, color)
 }
 
 /// Pixel position (top-left corner) of an ascii character in the embedded font map.
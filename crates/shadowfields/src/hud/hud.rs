@@ -1,7 +1,9 @@
 use super::internal::*;
+use std::collections::VecDeque;
 
 pub(crate) struct HUD {
 	slots: [Slot; 8],
+	chat: VecDeque<ChatLine>,
 	pub crosshair: bool,
 	cache: SingleCache<Object>,
 }
@@ -12,6 +14,17 @@ struct Slot {
 	ttl_secs: f32,
 }
 
+struct ChatLine {
+	text: String,
+	ttl_secs: f32,
+	color: vec3,
+}
+
+/// Maximum number of chat lines shown at once.
+const CHAT_LINES: usize = 8;
+/// Seconds to show a chat line.
+const CHAT_TTL: f32 = 20.0;
+
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[repr(u8)]
 pub enum HUDPos {
@@ -31,6 +44,7 @@ impl HUD {
 	pub fn new() -> Self {
 		Self {
 			slots: default(),
+			chat: default(),
 			crosshair: true,
 			cache: default(),
 		}
@@ -62,6 +76,21 @@ impl HUD {
 		self.cache.clear();
 	}
 
+	/// Show a chat message in the chat area (above the bottom left text),
+	/// colored by the sender's team.
+	pub fn add_chat(&mut self, msg: &ChatMsg) {
+		let color = msg.team.map(Team::color_filter).unwrap_or(WHITE);
+		self.chat.push_back(ChatLine {
+			text: msg.format(),
+			ttl_secs: CHAT_TTL,
+			color,
+		});
+		if self.chat.len() > CHAT_LINES {
+			self.chat.pop_front();
+		}
+		self.cache.clear();
+	}
+
 	pub fn tick(&mut self, dt: f32) {
 		for slot in &mut self.slots {
 			if slot.ttl_secs > 0.0 {
@@ -72,6 +101,14 @@ impl HUD {
 				}
 			}
 		}
+
+		for line in &mut self.chat {
+			line.ttl_secs -= dt;
+		}
+		if self.chat.front().is_some_and(|line| line.ttl_secs < 0.0) {
+			self.chat.retain(|line| line.ttl_secs >= 0.0);
+			self.cache.clear();
+		}
 	}
 
 	pub fn draw_on(&self, sg: &mut SceneGraph) {
@@ -92,6 +129,19 @@ impl HUD {
 		buf.append(&layout_text_right(viewport, text(TopRight)));
 		buf.append(&layout_text_bottom(viewport, text(BottomLeft)));
 
+		{
+			// chat, newest at the bottom, just above the BottomLeft text.
+			let char_size = UI_SCALE * _EMBEDDED_CHAR_SIZE;
+			let chars_per_row = u32::max(1, viewport.x() / char_size.x());
+			let mut y = viewport.y().saturating_sub(char_size.y() * (text_height_chars(text(BottomLeft)) + 1));
+			for line in self.chat.iter().rev() {
+				let rows = u32::max(1, (line.text.len() as u32).div_ceil(chars_per_row)); // long lines wrap
+				let Some(line_y) = y.checked_sub(rows * char_size.y()) else { break };
+				y = line_y;
+				buf.append(&layout_text_colored(viewport, uvec2(0, y), &line.text, line.color));
+			}
+		}
+
 		{
 			let text = text(Center);
 			// some fixed-point arithmetic to get the text about 20% above the crosshairs
@@ -87,6 +87,19 @@ impl ColliderFace {
 	}
 }
 
+/// Place mesh triangles in a BVH tree for efficient collision detection.
+pub fn build_collider_mesh<'a>(primitives: impl Iterator<Item = &'a Primitive>) -> Node<ColliderFace> {
+	let mut collider_faces = vec![];
+	for Primitive { material, mesh } in primitives {
+		let material = *material;
+		for indices in mesh.iter_triangle_indices() {
+			let vert = indices.map(|i| mesh.positions[i as usize]);
+			collider_faces.push(ColliderFace { vert, material });
+		}
+	}
+	Node::build_tree(collider_faces)
+}
+
 //-------------------------------------------------------------------------------- ray tracing
 
 impl Bounded for ColliderFace {
@@ -104,7 +117,31 @@ impl Intersect for ColliderFace {
 	}
 }
 
+impl Collide for ColliderFace {
+	fn overlaps_box(&self, bounds: &BoundingBox32) -> bool {
+		!self.is_degenerate() && box_overlaps_triangle(bounds, &self.vert)
+	}
+
+	fn sweep_box(&self, bounds: &BoundingBox32, delta: vec3, hr: &mut HitRecord<f32, vec3>) -> bool {
+		if self.is_degenerate() {
+			return false;
+		}
+		match sweep_box_triangle(bounds, delta, &self.vert) {
+			Some((t, normal)) if t < hr.t => {
+				hr.record(t, &normal);
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
 impl ColliderFace {
+	/// Zero-area face, e.g. the padding in a BVH leaf (see `Node::build_leaf`). Never collides.
+	fn is_degenerate(&self) -> bool {
+		self.sized_normal() == vec3::ZERO
+	}
+
 	#[inline]
 	fn intersect_triangle(&self, r: &Ray32, hr: &mut HitRecord<f32, (vec3, vec2, Handle)>) -> bool {
 		let o = self.origin();
@@ -4,4 +4,25 @@ use super::internal::*;
 pub struct JumpPad {
 	pub bounds: BoundingBox32,
 	pub jump_height: f32,
+
+	/// Landing point (from a Blender empty, see `CustomProperties::jump_pad_target`).
+	/// `None`: launch straight up by `jump_height`, keeping horizontal momentum.
+	#[serde(default)]
+	pub target: Option<vec3>,
+}
+
+impl JumpPad {
+	/// Velocity for launching from `start`:
+	/// towards `target` if there is one, else straight up (keeping `velocity`'s horizontal component).
+	pub fn launch_velocity(&self, gravity: f32, start: vec3, velocity: vec3) -> vec3 {
+		match self.target {
+			Some(target) => ballistic_velocity(gravity, start, target, self.jump_height),
+			None => velocity.with(|v| v[1] = jump_velocity_for_height(gravity, self.jump_height)),
+		}
+	}
+
+	/// Where players get launched from. Used for previewing the trajectory.
+	pub fn launch_point(&self) -> vec3 {
+		self.bounds.center_bottom()
+	}
 }
@@ -11,13 +11,20 @@ pub struct Map {
 	pub sun_dir: vec3,
 
 	pub gravity: f32, // downward gravity (i.e. positive number) in m/s^2.
+	pub mode: GameMode, // set by the server, see `MapSwitch`.
 
 	pub jump_pads: Vec<JumpPad>,
 	pub spawn_points: Vec<SpawnPoint>,
 	pub pickup_points: Vec<PickupPoint>,
 
+	/// Moving geometry (doors, lifts,...). Collides like the static geometry.
+	pub movers: Vec<Mover>,
+
 	collider_mesh: Node<ColliderFace>,
 
+	/// Where bots can go (see `find_path`).
+	pub nav_graph: NavGraph,
+
 	pub material_palette: MaterialPalette,
 	pub volumetric_light_cache: VolumetricLight, // <<< clear on map switch?
 }
@@ -30,6 +37,27 @@ impl Map {
 		let face_tree = load_collider_mesh(map_dir)?;
 
 		let metadata = Metadata::load(map_dir)?;
+		let movers = load_movers(map_dir, &metadata)?;
+		let nav_graph = load_nav_graph(map_dir)?;
+		Self::new(map_name, metadata, face_tree, movers, nav_graph)
+	}
+
+	/// A Map that only exists in memory, made of `faces` (e.g. a small test map).
+	/// No movers or navigation graph.
+	pub fn in_memory(map_name: &str, metadata: Metadata, faces: Vec<ColliderFace>) -> Result<Self> {
+		Self::new(map_name, metadata, Node::build_tree(faces), vec![], default())
+	}
+
+	/// In-memory test map: a flat 100x100m floor at y = 0, plus extra faces.
+	#[cfg(test)]
+	pub fn test_floor(metadata: Metadata, faces: &[[vec3; 3]]) -> Self {
+		let (a, b, c, d) = (vec3(-50.0, 0.0, -50.0), vec3(50.0, 0.0, -50.0), vec3(50.0, 0.0, 50.0), vec3(-50.0, 0.0, 50.0));
+		let floor = [[a, c, b], [a, d, c]];
+		let faces = floor.iter().chain(faces).map(|&vert| ColliderFace::new(vert, handle("#777777"))).collect();
+		Self::in_memory("test", metadata, faces).unwrap()
+	}
+
+	fn new(map_name: &str, metadata: Metadata, face_tree: Node<ColliderFace>, movers: Vec<Mover>, nav_graph: NavGraph) -> Result<Self> {
 		let sky_box = metadata.sky_box.map(|s| Handle::from_str(&s)).transpose()?;
 
 		Ok(Self {
@@ -41,10 +69,13 @@ impl Map {
 			sun_color: metadata.sun_def.as_ref().map(|sun| sun.color).unwrap_or_default(),
 			sun_dir: metadata.sun_def.as_ref().map(|sun| sun.dir.normalized()).unwrap_or(-vec3::EY),
 			gravity: STD_GRAVITY,
+			mode: default(),
 			jump_pads: metadata.jump_pads,
 			spawn_points: metadata.spawn_points,
 			pickup_points: metadata.pickup_points,
+			movers,
 			collider_mesh: face_tree,
+			nav_graph,
 			volumetric_light_cache: default(),
 			material_palette: metadata.materials,
 		})
@@ -57,51 +88,102 @@ impl Map {
 	/// Where does a ray intersect the map, if any.
 	pub fn intersect_t_64(&self, ray: &Ray64) -> Option<f64> {
 		let ray = Ray::new(ray.start.to_f32(), ray.dir.to_f32());
-		self.collider_mesh.intersection(&ray).maybe_t().map(|t| t as f64)
+		self.intersect(&ray).maybe_t().map(|t| t as f64)
 	}
 
 	/// Where does a ray intersect the map, if any
 	pub fn intersect_t(&self, ray: &Ray32) -> Option<f32> {
-		self.collider_mesh.intersection(ray).maybe_t()
+		self.intersect(ray).maybe_t()
 	}
 
 	pub fn intersects(&self, ray: &Ray32) -> bool {
-		self.collider_mesh.intersection(ray).maybe_t().is_some()
+		self.intersect(ray).maybe_t().is_some()
 	}
 
+	/// Intersect a ray with the static geometry and the movers at their current position.
 	pub fn intersect(&self, ray: &Ray32) -> HitRecord<f32, (Vector3<f32>, Vector2<f32>, Handle)> {
-		self.collider_mesh.intersection(ray)
+		let mut hr = self.collider_mesh.intersection(ray);
+		for mover in &self.movers {
+			mover.intersect(ray, &mut hr);
+		}
+		hr
+	}
+
+	/// Triangles of the static geometry (e.g. for finding walkable floors).
+	pub fn collider_faces(&self) -> Vec<&ColliderFace> {
+		self.collider_mesh.leaves()
+	}
+
+	/// Path from `from` to `to` over the navigation graph, if any.
+	/// Starts from the nearest node that can be seen from `from`,
+	/// ends at the nearest node from where `to` can be seen
+	/// (both seen from slightly above, so that the floor does not get in the way).
+	pub fn find_path(&self, from: vec3, to: vec3) -> Option<Vec<NavStep>> {
+		self.find_path_avoiding(from, to, |_| 0.0)
+	}
+
+	/// Like `find_path`, but walking near `pos` costs `1 + penalty(pos)` times more (e.g. to avoid dangerous spots).
+	/// See `NavGraph::find_path_avoiding`.
+	pub fn find_path_avoiding(&self, from: vec3, to: vec3, penalty: impl Fn(vec3) -> f32) -> Option<Vec<NavStep>> {
+		let lift = NAV_EYE_HEIGHT * vec3::EY;
+		let visible = |pos: vec3| move |node: vec3| !is_obstructed(self, pos + lift, node + lift);
+		let start = self.nav_graph.nearest_node(from, visible(from))?;
+		let goal = self.nav_graph.nearest_node(to, visible(to))?;
+		self.nav_graph.find_path_avoiding(start, goal, penalty)
 	}
 
 	pub fn bumps(&self, bounds: &BoundingBox<f32>) -> bool {
-		// Probe intersection with 4 diagonal line segments in the bounding box.
-		// TODO: use more accurate & robust method, e.g. capsule.
-		// +-----+
-		// |\   /|
-		// | \ / |
-		// +--*--+
-		let p = bounds.center_bottom();
-		let (dx, dy, dz) = bounds.size().into();
-
-		self.intersects_segment(p, p + vec3(dx, dy, dz))
-			|| self.intersects_segment(p, p + vec3(dx, dy, -dz))
-			|| self.intersects_segment(p, p + vec3(-dx, dy, dz))
-			|| self.intersects_segment(p, p + vec3(-dx, dy, dz))
-	}
-
-	fn intersects_segment(&self, start: vec3, end: vec3) -> bool {
-		let delta = end - start;
-		let dir = delta.normalized();
-		let len = delta.len();
-		let ray = Ray::new(start, dir);
-		self.collider_mesh.intersection(&ray).t <= len
+		mesh_bumps(&self.collider_mesh, bounds) || self.movers.iter().any(|mover| mover.bumps(bounds))
+	}
+
+	/// Like `bumps`, but ignoring one mover (e.g. the one carrying a player).
+	pub fn bumps_except_mover(&self, bounds: &BoundingBox<f32>, except: usize) -> bool {
+		mesh_bumps(&self.collider_mesh, bounds) || self.movers.iter().enumerate().any(|(i, mover)| i != except && mover.bumps(bounds))
+	}
+
+	/// Move a box along `delta`, where does it first touch the map (including movers), if at all?
+	/// Returns the time of impact, as a fraction of `delta`, and the contact normal (see `sweep_box_triangle`).
+	pub fn sweep(&self, bounds: &BoundingBox<f32>, delta: vec3) -> Option<(f32, vec3)> {
+		let mut hr = HitRecord::new();
+		self.collider_mesh.sweep_box(bounds, delta, &mut hr);
+		for mover in &self.movers {
+			mover.sweep(bounds, delta, &mut hr);
+		}
+		hr.attrib.map(|normal| (hr.t, normal))
 	}
 }
 
+/// Does a collision mesh bump into a bounding box?
+pub(crate) fn mesh_bumps(mesh: &Node<ColliderFace>, bounds: &BoundingBox<f32>) -> bool {
+	mesh.overlaps_box(bounds)
+}
+
 fn load_collider_mesh(map_dir: &MapDir) -> Result<Node<ColliderFace>> {
 	load_bincode_gz(&map_dir.collider_mesh_file())
 }
 
+fn load_nav_graph(map_dir: &MapDir) -> Result<NavGraph> {
+	match map_dir.nav_graph_file().exists() {
+		true => NavGraph::load(map_dir),
+		false => Ok(default()), // 👈 maps baked before navigation graphs existed.
+	}
+}
+
+fn load_movers(map_dir: &MapDir, metadata: &Metadata) -> Result<Vec<Mover>> {
+	if metadata.movers.is_empty() {
+		return Ok(vec![]); // 👈 maps baked before movers existed have no mover mesh file.
+	}
+	let meshes: Vec<GltfObject> = load_bincode_gz(&map_dir.mover_mesh_file())?;
+	metadata
+		.movers
+		.iter()
+		.map(|def| {
+			let obj = meshes.iter().find(|obj| obj.name == def.name).ok_or_else(|| anyhow!("mover {}: no mesh", def.name))?;
+			Ok(Mover::new(def.clone(), &obj.primitives))
+		})
+		.collect()
+}
+
 // does a wall obstruct the line of sight between two positions?
 pub(crate) fn is_obstructed(map: &Map, pos1: vec3, pos2: vec3) -> bool {
 	let dir = (pos2 - pos1).normalized();
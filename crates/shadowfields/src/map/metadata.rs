@@ -11,6 +11,9 @@ pub struct Metadata {
 	#[serde(default)]
 	pub jump_pads: Vec<JumpPad>,
 
+	#[serde(default)]
+	pub movers: Vec<MoverDef>,
+
 	#[serde(default)]
 	pub sun_def: Option<SunDef>,
 
@@ -6,6 +6,9 @@ mod jump_pad;
 mod map;
 mod material_palette;
 mod metadata;
+mod mover;
+mod nav_baking;
+mod nav_graph;
 mod pickup_point;
 mod point_light_def;
 mod primitive;
@@ -21,6 +24,9 @@ pub use jump_pad::*;
 pub use map::*;
 pub use material_palette::*;
 pub use metadata::*;
+pub use mover::*;
+pub use nav_baking::*;
+pub use nav_graph::*;
 pub use pickup_point::*;
 pub use point_light_def::*;
 pub use primitive::*;
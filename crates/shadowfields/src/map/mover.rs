@@ -0,0 +1,167 @@
+use super::internal::*;
+
+/// Moving map geometry: a door, lift, platform,...
+/// Defined in Blender (see shadowbake `CustomProperties::mover_trigger`),
+/// follows a path of translation keyframes back and forth.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MoverDef {
+	/// GLTF node name, identifies the mover's mesh in `MapDir::mover_mesh_file`.
+	pub name: Handle,
+
+	/// (time in seconds, offset from the rest position), sorted by time, starting at time 0.
+	/// The rest position is where the mesh is in the map file (typically the first keyframe).
+	pub keyframes: Vec<(f32, vec3)>,
+
+	pub trigger: MoverTrigger,
+
+	/// Seconds to wait at the end of the path before turning back.
+	pub wait: f32,
+
+	/// Bounding box at the rest position.
+	pub bounds: BoundingBox32,
+}
+
+/// What makes a mover move.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum MoverTrigger {
+	/// Move out when a player comes within `radius` of the mover,
+	/// return `wait` seconds after the last player has left (e.g. doors).
+	Proximity { radius: f32 },
+
+	/// Move back and forth forever, pausing `wait` seconds at either end (e.g. lifts).
+	Timer,
+}
+
+/// The part of a Mover's state that is replicated from server to clients.
+/// Clients extrapolate by ticking locally.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct MoverState {
+	/// Seconds along the keyframed path.
+	pub time: f32,
+
+	/// Direction of travel along the path: 1 (out), -1 (back), 0 (stopped).
+	pub dir: f32,
+}
+
+/// A MoverDef + runtime state + collision mesh.
+pub struct Mover {
+	pub def: MoverDef,
+	pub state: MoverState,
+
+	/// Server only: counts down before the mover turns around.
+	pub wait_timer: Timer,
+
+	/// How far the mover moved during the last `tick` (to carry players along, see `ride_movers`).
+	pub last_delta: vec3,
+
+	/// Collision mesh at the rest position.
+	collider_mesh: Node<ColliderFace>,
+}
+
+impl MoverDef {
+	/// Time of the last keyframe.
+	pub fn duration(&self) -> f32 {
+		self.keyframes.last().map(|(t, _)| *t).unwrap_or_default()
+	}
+
+	/// Offset from the rest position, `time` seconds along the path.
+	/// Linearly interpolated between keyframes.
+	pub fn offset_at(&self, time: f32) -> vec3 {
+		let i = self.keyframes.partition_point(|(t, _)| *t <= time);
+		match (i.checked_sub(1).and_then(|i| self.keyframes.get(i)), self.keyframes.get(i)) {
+			(Some(&(t0, a)), Some(&(t1, b))) => lerp(a, b, (time - t0) / (t1 - t0)),
+			(Some(&(_, a)), None) => a,
+			(None, Some(&(_, b))) => b,
+			(None, None) => vec3::ZERO,
+		}
+	}
+}
+
+impl Mover {
+	pub fn new(def: MoverDef, primitives: &[Primitive]) -> Self {
+		Self {
+			wait_timer: Timer::one_off(def.wait),
+			def,
+			state: default(),
+			last_delta: vec3::ZERO,
+			collider_mesh: build_collider_mesh(primitives.iter()),
+		}
+	}
+
+	/// Current offset from the rest position.
+	pub fn offset(&self) -> vec3 {
+		self.def.offset_at(self.state.time)
+	}
+
+	/// Current bounding box.
+	pub fn bounds(&self) -> BoundingBox32 {
+		let offset = self.offset();
+		BoundingBox32::new(self.def.bounds.min + offset, self.def.bounds.max + offset)
+	}
+
+	/// Has the mover travelled its path all the way out?
+	pub fn at_end(&self) -> bool {
+		self.state.time >= self.def.duration()
+	}
+
+	/// Advance along the path, stopping at either end.
+	/// Returns how far the mover moved.
+	pub fn tick(&mut self, dt: f32) -> vec3 {
+		let before = self.offset();
+		let duration = self.def.duration();
+		self.state.time = (self.state.time + self.state.dir * dt).clamp(0.0, duration);
+		if (self.state.dir > 0.0 && self.state.time == duration) || (self.state.dir < 0.0 && self.state.time == 0.0) {
+			self.state.dir = 0.0;
+		}
+		self.last_delta = self.offset() - before;
+		self.last_delta
+	}
+
+	/// Intersect with the mover's current position (see `Intersect::intersect`).
+	pub(crate) fn intersect(&self, ray: &Ray32, hr: &mut HitRecord<f32, (vec3, vec2, Handle)>) -> bool {
+		let ray = Ray::new(ray.start - self.offset(), ray.dir);
+		self.collider_mesh.intersect(&ray, hr)
+	}
+
+	/// Does the mover, at its current position, bump into a bounding box? (see `Map::bumps`).
+	pub(crate) fn bumps(&self, bounds: &BoundingBox32) -> bool {
+		if !self.bounds().intersects_box(bounds) {
+			return false;
+		}
+		let offset = self.offset();
+		mesh_bumps(&self.collider_mesh, &BoundingBox32::new(bounds.min - offset, bounds.max - offset))
+	}
+
+	/// Sweep a box against the mover's current position (see `Map::sweep`).
+	pub(crate) fn sweep(&self, bounds: &BoundingBox32, delta: vec3, hr: &mut HitRecord<f32, vec3>) -> bool {
+		let swept = bounds.join(&BoundingBox::new(bounds.min + delta, bounds.max + delta));
+		if !self.bounds().intersects_box(&swept) {
+			return false;
+		}
+		let offset = self.offset();
+		self.collider_mesh.sweep_box(&BoundingBox32::new(bounds.min - offset, bounds.max - offset), delta, hr)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn offset_at() {
+		let def = MoverDef {
+			name: handle("lift"),
+			keyframes: vec![(0.0, vec3::ZERO), (2.0, vec3(0.0, 4.0, 0.0)), (3.0, vec3(2.0, 4.0, 0.0))],
+			trigger: MoverTrigger::Timer,
+			wait: 1.0,
+			bounds: BoundingBox32::new(vec3::ZERO, vec3::ONES),
+		};
+		assert_eq!(def.duration(), 3.0);
+		assert_eq!(def.offset_at(-1.0), vec3::ZERO);
+		assert_eq!(def.offset_at(0.0), vec3::ZERO);
+		assert_eq!(def.offset_at(1.0), vec3(0.0, 2.0, 0.0));
+		assert_eq!(def.offset_at(2.5), vec3(1.0, 4.0, 0.0));
+		assert_eq!(def.offset_at(3.0), vec3(2.0, 4.0, 0.0));
+		assert_eq!(def.offset_at(9.0), vec3(2.0, 4.0, 0.0));
+	}
+}
@@ -0,0 +1,265 @@
+//!
+//! Build a navigation graph (see `NavGraph`) for bots. Used by `shadowbake`.
+//!
+//! Nodes are standing positions on a grid over walkable (not too steep) collider triangles,
+//! where players fit. Neighbouring nodes are connected if a player can walk, drop, or jump
+//! from one to the other. Jump pads connect the nodes on the pad to where they launch players.
+//!
+use super::internal::*;
+
+/// Distance between nodes.
+const GRID: f32 = 1.0;
+/// Steeper triangles are not walkable (cosine of the max. slope).
+const MIN_FLOOR_NORMAL_Y: f32 = 0.7;
+/// Height difference that can be walked (see stair climbing in `movement.rs`).
+const MAX_STEP: f32 = 0.5;
+/// Max. height to drop down from a ledge.
+const MAX_DROP: f32 = 6.0;
+/// Only trust this fraction of a player's jump height.
+/// (At the top of a jump, players still climb `MAX_STEP` when bumping into a ledge).
+const JUMP_MARGIN: f32 = 0.8;
+/// Gaps up to this horizontal distance can be jumped across.
+const MAX_JUMP_DIST: f32 = 3.0;
+/// Extra cost for jumping: bots prefer walking.
+const JUMP_PENALTY: f32 = 2.0;
+/// Lift positions by this much to test if a player fits, so that they don't bump into the floor.
+const SKIN: f32 = 0.05;
+
+/// Build a navigation graph for the map's static geometry and jump pads.
+pub fn build_nav_graph(map: &Map) -> NavGraph {
+	NavBaker::new(map).bake()
+}
+
+struct NavBaker<'m> {
+	map: &'m Map,
+	/// Small and tall avatars: nodes and edges must work for both.
+	probes: [Player; 2],
+	/// How high players can get by jumping (and climbing at the top of the jump).
+	jump_height: f32,
+	graph: NavGraph,
+	/// Node indices by grid cell (x, z), for finding neighbours.
+	cells: HashMap<(i32, i32), SmallVec<[u32; 2]>>,
+}
+
+impl<'m> NavBaker<'m> {
+	fn new(map: &'m Map) -> Self {
+		let probes = [1, 10].map(|avatar_id| Player::new(default(), vec3::ZERO, default(), String::new(), avatar_id, Team::Red));
+		let jump_height = probes.iter().map(|p| p.jump_speed.powi(2) / (2.0 * map.gravity)).fold(f32::INFINITY, f32::min);
+		Self {
+			map,
+			probes,
+			jump_height: JUMP_MARGIN * jump_height + MAX_STEP,
+			graph: default(),
+			cells: default(),
+		}
+	}
+
+	fn bake(mut self) -> NavGraph {
+		self.add_nodes();
+		self.add_neighbour_edges();
+		self.add_jump_pad_edges();
+		self.graph
+	}
+
+	// -------------------------------------------------------------------------------- nodes
+
+	fn add_nodes(&mut self) {
+		for face in self.map.collider_faces() {
+			let normal = face.sized_normal().safe_normalized();
+			if normal.y().abs() < MIN_FLOOR_NORMAL_Y {
+				continue; // walls, steep slopes, zero-sized padding
+			}
+			for pos in grid_points_on(face) {
+				if self.fits(pos) && self.node_at(pos).is_none() {
+					let id = self.graph.add_node(pos);
+					self.cells.entry(cell(pos)).or_default().push(id);
+				}
+			}
+		}
+	}
+
+	/// Node in the same cell, at (about) the same height, if any.
+	fn node_at(&self, pos: vec3) -> Option<u32> {
+		let nodes = self.cells.get(&cell(pos))?;
+		nodes.iter().copied().find(|&i| (self.graph.nodes[i as usize].y() - pos.y()).abs() < MAX_STEP)
+	}
+
+	// -------------------------------------------------------------------------------- edges
+
+	fn add_neighbour_edges(&mut self) {
+		let r = (MAX_JUMP_DIST / GRID).ceil() as i32;
+		for a in 0..self.graph.nodes.len() as u32 {
+			let (cx, cz) = cell(self.node(a));
+			let neighbours = (-r..=r)
+				.cartesian_product(-r..=r)
+				.filter_map(|(dx, dz)| self.cells.get(&(cx + dx, cz + dz)))
+				.flatten()
+				.copied()
+				.filter(|&b| b != a)
+				.collect_vec();
+			for b in neighbours {
+				if let Some(kind) = self.traversal(self.node(a), self.node(b)) {
+					let penalty = if kind == NavEdgeKind::Jump { JUMP_PENALTY } else { 0.0 };
+					self.graph.add_edge(a, b, kind, penalty);
+				}
+			}
+		}
+	}
+
+	/// How (if at all) to get from `a` to nearby `b`.
+	fn traversal(&self, a: vec3, b: vec3) -> Option<NavEdgeKind> {
+		use NavEdgeKind::*;
+		let dist = a.xz().distance_to(b.xz());
+		let dy = b.y() - a.y();
+		let adjacent = dist < 1.5 * GRID;
+
+		if adjacent && dy.abs() <= MAX_STEP && self.can_walk(a, b) {
+			return Some(Walk);
+		}
+		if adjacent && dy < -MAX_STEP && dy > -MAX_DROP && self.can_drop(a, b) {
+			return Some(Walk);
+		}
+		if adjacent && dy > MAX_STEP && dy <= self.jump_height && self.can_jump(a, b, 0.0) {
+			return Some(Jump);
+		}
+		if !adjacent && dist <= MAX_JUMP_DIST && dy.abs() <= MAX_STEP && !self.has_floor(lerp(a, b, 0.5)) && self.can_jump(a, b, 0.5 * self.jump_height) {
+			return Some(Jump); // across a gap
+		}
+		None
+	}
+
+	/// Walk along the floor without bumping into anything.
+	/// Players climb obstacles up to `MAX_STEP` (e.g. stairs), so check if they fit a step higher.
+	fn can_walk(&self, a: vec3, b: vec3) -> bool {
+		let lift = MAX_STEP * vec3::EY;
+		!is_obstructed(self.map, a + lift, b + lift)
+			&& [0.25, 0.5, 0.75].into_iter().all(|t| {
+				let p = lerp(a, b, t);
+				self.floor_below(p + lift, 2.0 * MAX_STEP).is_some_and(|floor| self.fits(floor + lift))
+			})
+	}
+
+	/// Walk off a ledge at `a` and fall down to `b`.
+	fn can_drop(&self, a: vec3, b: vec3) -> bool {
+		let over_b = vec3(b.x(), a.y(), b.z());
+		self.fits(lerp(a, over_b, 0.5)) && self.fits(over_b) && self.floor_below(over_b + SKIN * vec3::EY, MAX_DROP).is_some_and(|floor| floor.distance_to(b) < MAX_STEP)
+	}
+
+	/// Jump from `a` to `b`, clearing the higher of the two by `clearance`.
+	fn can_jump(&self, a: vec3, b: vec3, clearance: f32) -> bool {
+		let top = f32::max(a.y(), b.y()) + clearance + SKIN;
+		let up = |p: vec3| vec3(p.x(), top, p.z());
+		self.fits(up(a)) && self.fits(up(lerp(a, b, 0.5))) && self.fits(up(b))
+	}
+
+	/// Jump pads launch players from any node on the pad to the nodes near the landing point.
+	/// Pads without target launch straight up: to ledges right next to the pad.
+	fn add_jump_pad_edges(&mut self) {
+		for pad in &self.map.jump_pads {
+			let from = (0..self.graph.nodes.len() as u32).filter(|&i| pad.bounds.contains(self.node(i) + SKIN * vec3::EY)).collect_vec();
+			let launch = pad.launch_point();
+			let to = match pad.target {
+				Some(target) => self.graph.nearest_node(target, |node| !is_obstructed(self.map, target, node + NAV_EYE_HEIGHT * vec3::EY)).into_iter().collect_vec(),
+				None => (0..self.graph.nodes.len() as u32)
+					.filter(|&i| {
+						let node = self.node(i);
+						let dy = node.y() - launch.y();
+						node.xz().distance_to(launch.xz()) < 2.0 * GRID && dy > MAX_STEP && dy < JUMP_MARGIN * pad.jump_height && self.can_jump(launch, node, 0.0)
+					})
+					.collect_vec(),
+			};
+			for &a in &from {
+				for &b in &to {
+					self.graph.add_edge(a, b, NavEdgeKind::JumpPad, 0.0);
+				}
+			}
+		}
+	}
+
+	// -------------------------------------------------------------------------------- util
+
+	fn node(&self, i: u32) -> vec3 {
+		self.graph.nodes[i as usize]
+	}
+
+	/// Do small and tall players fit when standing at `pos`?
+	fn fits(&self, pos: vec3) -> bool {
+		let pos = pos + SKIN * vec3::EY;
+		self.probes.iter().all(|p| p.pos_ok(self.map, pos))
+	}
+
+	/// First floor below `pos`, within `max_dist`.
+	fn floor_below(&self, pos: vec3, max_dist: f32) -> Option<vec3> {
+		// 👇 Nudge off the grid: a ray exactly through the edge between two triangles misses both.
+		// Try both ways, `pos` may be right at the edge of a floor.
+		const NUDGE: vec3 = vec3(0.0123, 0.0, 0.0079);
+		[NUDGE, -NUDGE].into_iter().find_map(|nudge| {
+			let ray = Ray32::new(pos + nudge, -vec3::EY);
+			self.map.intersect_t(&ray).filter(|&t| t <= max_dist).map(|t| ray.at(t) - nudge)
+		})
+	}
+
+	fn has_floor(&self, pos: vec3) -> bool {
+		self.floor_below(pos + MAX_STEP * vec3::EY, 2.0 * MAX_STEP).is_some()
+	}
+}
+
+fn lerp(a: vec3, b: vec3, t: f32) -> vec3 {
+	a + t * (b - a)
+}
+
+fn cell(pos: vec3) -> (i32, i32) {
+	((pos.x() / GRID).round() as i32, (pos.z() / GRID).round() as i32)
+}
+
+/// Grid points (multiples of `GRID` in x and z) on a triangle.
+fn grid_points_on(face: &ColliderFace) -> Vec<vec3> {
+	let [a, b, c] = face.vert;
+	let Some(bounds) = BoundingBox32::from_points(face.vert.into_iter()) else { return vec![] };
+	let (x0, z0) = cell(bounds.min);
+	let (x1, z1) = cell(bounds.max);
+	(x0..=x1)
+		.cartesian_product(z0..=z1)
+		.filter_map(|(ix, iz)| {
+			let p = vec2(ix as f32, iz as f32) * GRID;
+			let [u, v, w] = barycentric(p, [a.xz(), b.xz(), c.xz()])?;
+			Some(vec3(p.x(), u * a.y() + v * b.y() + w * c.y(), p.y()))
+		})
+		.collect()
+}
+
+/// Barycentric coordinates of `p` in 2D triangle `t`, if inside.
+fn barycentric(p: vec2, t: [vec2; 3]) -> Option<[f32; 3]> {
+	let [a, b, c] = t;
+	let det = (b.y() - c.y()) * (a.x() - c.x()) + (c.x() - b.x()) * (a.y() - c.y());
+	if det.abs() < 1e-9 {
+		return None; // degenerate (vertical) triangle
+	}
+	let u = ((b.y() - c.y()) * (p.x() - c.x()) + (c.x() - b.x()) * (p.y() - c.y())) / det;
+	let v = ((c.y() - a.y()) * (p.x() - c.x()) + (a.x() - c.x()) * (p.y() - c.y())) / det;
+	let w = 1.0 - u - v;
+	const EPS: f32 = 1e-4;
+	(u >= -EPS && v >= -EPS && w >= -EPS).then_some([u, v, w])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn barycentric_coords() {
+		let t = [vec2(0.0, 0.0), vec2(2.0, 0.0), vec2(0.0, 2.0)];
+		assert_eq!(barycentric(vec2(0.0, 0.0), t), Some([1.0, 0.0, 0.0]));
+		assert_eq!(barycentric(vec2(1.0, 1.0), t), Some([0.0, 0.5, 0.5]));
+		assert_eq!(barycentric(vec2(1.5, 1.5), t), None);
+		assert_eq!(barycentric(vec2(1.0, 1.0), [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(2.0, 0.0)]), None);
+	}
+
+	#[test]
+	fn grid_points() {
+		let face = ColliderFace::new([vec3(0.0, 1.0, 0.0), vec3(2.0, 1.0, 0.0), vec3(0.0, 3.0, 2.0)], default());
+		let points = grid_points_on(&face);
+		assert_eq!(points.len(), 6);
+		assert!(points.contains(&vec3(1.0, 2.0, 1.0)));
+	}
+}
@@ -0,0 +1,238 @@
+use super::internal::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Navigation graph for bots: standing positions on walkable floors,
+/// connected by walking, jumping or jump pad edges.
+///
+/// Baked by `shadowbake` (see `build_nav_graph`), saved in the map dir.
+/// Maps baked before navigation graphs existed have an empty graph
+/// (bots then fall back to wandering around).
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct NavGraph {
+	/// Standing positions (center-bottom of a player's bounding box).
+	pub nodes: Vec<vec3>,
+	/// Outgoing edges, per node. Not necessarily symmetric (e.g. dropping off a ledge is one-way).
+	pub edges: Vec<Vec<NavEdge>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NavEdge {
+	pub to: u32,
+	pub kind: NavEdgeKind,
+	/// Roughly the time it takes to traverse, in meters of walking. Never less than the distance.
+	pub cost: f32,
+}
+
+/// How to get to the next node.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavEdgeKind {
+	/// Walk (up or down stairs, or off a ledge).
+	Walk,
+	/// Jump (up onto a ledge, or across a gap).
+	Jump,
+	/// Walk onto a jump pad, which launches towards the next node.
+	JumpPad,
+}
+
+/// A step along a path found by `NavGraph::find_path`.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct NavStep {
+	pub pos: vec3,
+	/// How to get here from the previous step.
+	pub kind: NavEdgeKind,
+}
+
+/// Height above a node (standing position) from where it must be visible
+/// to be the start or end of a path (see `Map::find_path`).
+pub const NAV_EYE_HEIGHT: f32 = 0.5;
+
+/// Don't look further than this for a node near a start or goal position.
+const MAX_NODE_DIST: f32 = 4.0;
+
+impl NavGraph {
+	pub fn load(map_dir: &MapDir) -> Result<Self> {
+		load_bincode_gz(&map_dir.nav_graph_file())
+	}
+
+	pub fn save(&self, map_dir: &MapDir) -> Result<()> {
+		save_bincode_gz(self, &map_dir.nav_graph_file())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	pub fn num_edges(&self) -> usize {
+		self.edges.iter().map(Vec::len).sum()
+	}
+
+	pub fn add_node(&mut self, pos: vec3) -> u32 {
+		self.nodes.push(pos);
+		self.edges.push(vec![]);
+		(self.nodes.len() - 1) as u32
+	}
+
+	/// Add a one-way edge. Its cost is the distance between the nodes, plus `penalty`.
+	pub fn add_edge(&mut self, from: u32, to: u32, kind: NavEdgeKind, penalty: f32) {
+		let cost = self.nodes[from as usize].distance_to(self.nodes[to as usize]) + penalty;
+		self.edges[from as usize].push(NavEdge { to, kind, cost });
+	}
+
+	/// Nodes near `pos` (within `MAX_NODE_DIST`), closest first, for which `ok` returns true
+	/// (e.g. to skip nodes behind a wall).
+	/// Vertical distance counts double: a node under a player is better than one on a ledge next to them.
+	pub fn nearest_node(&self, pos: vec3, ok: impl Fn(vec3) -> bool) -> Option<u32> {
+		let dist = |node: vec3| (node - pos).mul3(vec3(1.0, 2.0, 1.0)).len();
+		self.nodes
+			.iter()
+			.enumerate()
+			.map(|(i, &node)| (dist(node), i as u32))
+			.filter(|&(d, _)| d < MAX_NODE_DIST)
+			.sorted_by(|a, b| a.0.total_cmp(&b.0))
+			.map(|(_, i)| i)
+			.find(|&i| ok(self.nodes[i as usize]))
+	}
+
+	/// Shortest path (A*) between two nodes. Does not include the start node.
+	pub fn find_path(&self, start: u32, goal: u32) -> Option<Vec<NavStep>> {
+		self.find_path_avoiding(start, goal, |_| 0.0)
+	}
+
+	/// Like `find_path`, but the edges into a node at `pos` cost `1 + penalty(pos)` times more (penalty must not be negative).
+	/// Proportional to the edge cost, so that the penalty for crossing an area does not depend on how densely it is sampled.
+	pub fn find_path_avoiding(&self, start: u32, goal: u32, penalty: impl Fn(vec3) -> f32) -> Option<Vec<NavStep>> {
+		let goal_pos = self.nodes[goal as usize];
+		let heuristic = |node: u32| self.nodes[node as usize].distance_to(goal_pos);
+
+		let mut cost_so_far = vec![f32::INFINITY; self.nodes.len()];
+		let mut came_from = vec![None; self.nodes.len()];
+		let mut open = BinaryHeap::new();
+
+		cost_so_far[start as usize] = 0.0;
+		open.push(Open { estimate: heuristic(start), node: start });
+
+		while let Some(Open { node, estimate }) = open.pop() {
+			if node == goal {
+				return Some(self.reconstruct_path(&came_from, goal));
+			}
+			let cost = cost_so_far[node as usize];
+			if estimate > cost + heuristic(node) {
+				continue; // stale entry, node was reached cheaper since
+			}
+			for edge in &self.edges[node as usize] {
+				let new_cost = cost + edge.cost * (1.0 + penalty(self.nodes[edge.to as usize]));
+				if new_cost < cost_so_far[edge.to as usize] {
+					cost_so_far[edge.to as usize] = new_cost;
+					came_from[edge.to as usize] = Some((node, edge.kind));
+					open.push(Open {
+						estimate: new_cost + heuristic(edge.to),
+						node: edge.to,
+					});
+				}
+			}
+		}
+		None
+	}
+
+	fn reconstruct_path(&self, came_from: &[Option<(u32, NavEdgeKind)>], goal: u32) -> Vec<NavStep> {
+		let mut path = vec![];
+		let mut node = goal;
+		while let Some((prev, kind)) = came_from[node as usize] {
+			path.push(NavStep { pos: self.nodes[node as usize], kind });
+			node = prev;
+		}
+		path.reverse();
+		path
+	}
+}
+
+/// Entry in A*'s open set. Ordered so that `BinaryHeap` pops the lowest estimate first.
+struct Open {
+	estimate: f32,
+	node: u32,
+}
+
+impl PartialEq for Open {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Open {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.estimate.total_cmp(&self.estimate)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use NavEdgeKind::*;
+
+	//  0 --- 1 --- 2
+	//  |           ^
+	//  3 --------- 4 (one-way 4 -> 2, e.g. jump up)
+	fn graph() -> NavGraph {
+		let mut g = NavGraph::default();
+		for pos in [vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(2.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(2.0, 0.0, 1.0)] {
+			g.add_node(pos);
+		}
+		for (a, b) in [(0, 1), (1, 2), (0, 3), (3, 4)] {
+			g.add_edge(a, b, Walk, 0.0);
+			g.add_edge(b, a, Walk, 0.0);
+		}
+		g.add_edge(4, 2, Jump, 0.0);
+		g
+	}
+
+	#[test]
+	fn find_path() {
+		let g = graph();
+		let positions = |path: Vec<NavStep>| path.iter().map(|s| s.pos).collect_vec();
+
+		assert_eq!(positions(g.find_path(0, 2).unwrap()), vec![vec3(1.0, 0.0, 0.0), vec3(2.0, 0.0, 0.0)]);
+		assert_eq!(g.find_path(4, 2).unwrap(), vec![NavStep { pos: vec3(2.0, 0.0, 0.0), kind: Jump }]);
+		assert_eq!(positions(g.find_path(2, 4).unwrap()), vec![vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(2.0, 0.0, 1.0)]);
+		assert_eq!(g.find_path(1, 1).unwrap(), vec![]);
+
+		// going through node 1 is expensive: take the long way round.
+		let avoid_1 = |pos: vec3| select(pos == vec3(1.0, 0.0, 0.0), 10.0, 0.0);
+		assert_eq!(positions(g.find_path_avoiding(0, 2, avoid_1).unwrap()), vec![vec3(0.0, 0.0, 1.0), vec3(2.0, 0.0, 1.0), vec3(2.0, 0.0, 0.0)]);
+
+		let mut g = g;
+		let island = g.add_node(vec3(9.0, 0.0, 9.0));
+		assert_eq!(g.find_path(0, island), None);
+	}
+
+	/// Bots can get from any spawn point to any other, and to all pickups.
+	#[test]
+	fn castle_paths() {
+		init_test_assets_dir();
+		let map = Map::load("castle").unwrap();
+		assert!(!map.nav_graph.is_empty());
+		let spawn_points = map.spawn_points.iter().map(|sp| sp.position).collect_vec();
+		let pickups = map.pickup_points.iter().map(|pp| pp.pos).collect_vec();
+		for &from in &spawn_points {
+			for &to in spawn_points.iter().chain(&pickups) {
+				assert!(map.find_path(from, to).is_some(), "no path from {from} to {to}");
+			}
+		}
+	}
+
+	#[test]
+	fn nearest_node() {
+		let g = graph();
+		assert_eq!(g.nearest_node(vec3(0.9, 0.5, 0.1), |_| true), Some(1));
+		assert_eq!(g.nearest_node(vec3(0.9, 0.5, 0.1), |pos| pos.x() != 1.0), Some(0));
+		assert_eq!(g.nearest_node(vec3(20.0, 0.0, 0.0), |_| true), None);
+	}
+}
@@ -95,3 +95,33 @@ pub(crate) fn upload_scene_mesh(map_dir: &MapDir, res: &mut Resources, settings:
 
 	Ok(objects)
 }
+
+/// A mover's mesh, drawn at the mover's current offset (see `Mover`).
+pub(crate) struct MoverMesh {
+	pub mover: usize, // index into `Map::movers`
+	pub vao: Arc<VAO>,
+	pub texture: Arc<Texture>,
+}
+
+pub(crate) fn upload_mover_meshes(map_dir: &MapDir, map: &Map, res: &mut Resources) -> Result<Vec<MoverMesh>> {
+	if map.movers.is_empty() {
+		return Ok(vec![]); // 👈 maps baked before movers existed have no mover mesh file.
+	}
+
+	let ctx = ctx();
+	let mut mover_meshes = vec![];
+
+	let mover_objects: Vec<GltfObject> = load_bincode_gz(&map_dir.mover_mesh_file())?;
+
+	for (i, mover) in map.movers.iter().enumerate() {
+		let obj = mover_objects.iter().find(|obj| obj.name == mover.def.name).ok_or_else(|| anyhow!("mover {}: no mesh", mover.def.name))?;
+		for Primitive { material, mesh } in &obj.primitives {
+			let vao = Arc::new(ctx.upload_meshbuffer(&mesh.convert_to_packed()?));
+			let material = map.material_palette.get(material).ok_or_else(|| anyhow!("metadata has no material definition for {material}"))?;
+			let texture = res.textures.load_sync(material.base_color).unwrap_or(res.default_texture.clone());
+			mover_meshes.push(MoverMesh { mover: i, vao, texture });
+		}
+	}
+
+	Ok(mover_meshes)
+}
@@ -1,8 +1,9 @@
 use super::internal::*;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub(crate) struct Frame {
 	pub position: vec3,
 	pub velocity: vec3,
 	pub orientation: Orientation,
+	pub crouched: bool,
 }
@@ -2,8 +2,58 @@ use super::internal::*;
 
 pub const STD_GRAVITY:f32 = 9.8;
 
+/// Vector component indices, e.g. `velocity[Y]`.
+pub const X: usize = 0;
+pub const Y: usize = 1;
+pub const Z: usize = 2;
+
 /// How fast does one need to jump to reach a height?
 /// https://en.wikipedia.org/wiki/Torricelli%27s_equation.
 pub fn jump_velocity_for_height(g: f32, height: f32) -> f32 {
 	f32::sqrt(2.0 * g * height)
 }
+
+/// Launch velocity to fly from `start` to `target` under gravity `g`,
+/// passing `apex_height` above the highest of both points.
+/// (Ignores air drag: players flying off a jump pad are not damped, see `launch_from_jump_pad`).
+pub fn ballistic_velocity(g: f32, start: vec3, target: vec3, apex_height: f32) -> vec3 {
+	let apex = f32::max(start.y(), target.y()) + f32::max(apex_height, 0.0);
+	let vy = jump_velocity_for_height(g, apex - start.y());
+	let time_up = vy / g;
+	let time_down = f32::sqrt(2.0 * (apex - target.y()) / g);
+	let flight_time = time_up + time_down;
+	let delta_h = (target - start).with(|v| v[1] = 0.0);
+	match flight_time > 0.0 {
+		true => (delta_h / flight_time).with(|v| v[1] = vy),
+		false => vec3(0.0, vy, 0.0),
+	}
+}
+
+/// Position at time `t` after launching from `start` with `velocity` (no air drag).
+pub fn ballistic_position(g: f32, start: vec3, velocity: vec3, t: f32) -> vec3 {
+	start + t * velocity - vec3(0.0, 0.5 * g * t * t, 0.0)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn ballistic_velocity_lands_on_target() {
+		let g = STD_GRAVITY;
+		for (start, target) in [
+			(vec3(0.0, 0.0, 0.0), vec3(10.0, 0.0, 5.0)),  // level
+			(vec3(0.0, 0.0, 0.0), vec3(-4.0, 6.0, 2.0)),  // up
+			(vec3(1.0, 8.0, 2.0), vec3(12.0, -3.0, 0.0)), // down
+		] {
+			let v = ballistic_velocity(g, start, target, 2.0);
+			let apex_t = v.y() / g;
+			let apex = ballistic_position(g, start, v, apex_t);
+			assert!((apex.y() - (f32::max(start.y(), target.y()) + 2.0)).abs() < 1e-3);
+
+			let time_down = f32::sqrt(2.0 * (apex.y() - target.y()) / g);
+			let landing = ballistic_position(g, start, v, apex_t + time_down);
+			assert!((landing - target).len() < 1e-3, "{start} -> {target}: landed at {landing}");
+		}
+	}
+}
@@ -3,7 +3,9 @@ use super::internal::*;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub(crate) struct Skeleton {
 	pub hsize: f32,
+	/// Height of the bounding box (smaller while crouched, see `Player::set_crouched`).
 	pub vsize: f32,
+	pub crouched: bool,
 	pub target_position: vec3,   // center bottom, not filtered
 	#[serde(skip)]
 	pub pre_filtered_position: vec3, // center bottom,
@@ -23,11 +25,14 @@ impl Skeleton {
 			filtered_position: pos,
 			hsize,
 			vsize,
+			crouched: false,
 			orientation,
 			velocity: default(),
 		}
 	}
 
+	/// Set position, velocity and orientation.
+	/// Crouching also changes the bounding box, see `Player::set_frame`.
 	pub fn set_frame(&mut self, frame: Frame) {
 		self.target_position = frame.position;
 		self.velocity = frame.velocity;
@@ -39,6 +44,7 @@ impl Skeleton {
 			position: self.filtered_position,
 			velocity: self.velocity,
 			orientation: self.orientation,
+			crouched: self.crouched,
 		}
 	}
 
@@ -47,6 +53,7 @@ impl Skeleton {
 			position: self.target_position,
 			velocity: self.velocity,
 			orientation: self.orientation,
+			crouched: self.crouched,
 		}
 	}
 
@@ -59,6 +59,8 @@ pub use std::io::Write;
 pub use std::iter::zip;
 pub use std::mem;
 pub use std::mem::take;
+pub use std::net::IpAddr;
+pub use std::net::SocketAddr;
 pub use std::net::TcpListener;
 pub use std::net::TcpStream;
 pub use std::num::NonZeroU32;
@@ -102,6 +102,12 @@ where
 		// TODO: proper
 		self.contains(other.center()) || other.contains(self.center())
 	}
+
+	/// Do two boxes overlap (or touch)? Unlike `overlaps`, also true when only the edges overlap
+	/// (as needed for collision detection, see `Collide`).
+	pub fn intersects_box(&self, other: &Self) -> bool {
+		(0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+	}
 }
 
 impl<T> BoundingBox<T>
@@ -60,6 +60,23 @@ where
 	}
 }
 
+impl<T> Node<T> {
+	/// All leaves, in no particular order.
+	/// Includes the zero-sized padding leaves (see `build_leaf`).
+	pub fn leaves(&self) -> Vec<&T> {
+		let mut leaves = vec![];
+		self.collect_leaves(&mut leaves);
+		leaves
+	}
+
+	fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a T>) {
+		match self {
+			Node::Inner(ch) => ch.iter().for_each(|(_, node)| node.collect_leaves(leaves)),
+			Node::Leaf(ch) => leaves.extend(ch),
+		}
+	}
+}
+
 //-------------------------------------------------------------------------------- Intersect
 
 /// A Node of things that can intersect, can intersect itself.
@@ -103,6 +120,32 @@ where
 	}
 }
 
+//-------------------------------------------------------------------------------- Collide
+
+/// A Node of things that a box can bump into, can be bumped into itself.
+/// Only descends into children whose bounding box overlaps the (swept) box.
+impl<T> Collide for Node<T>
+where
+	T: Collide,
+{
+	fn overlaps_box(&self, bounds: &BoundingBox32) -> bool {
+		match self {
+			Node::Inner(ch) => ch.iter().any(|(bb, node)| bb.intersects_box(bounds) && node.overlaps_box(bounds)),
+			Node::Leaf(ch) => ch.iter().any(|t| t.overlaps_box(bounds)),
+		}
+	}
+
+	fn sweep_box(&self, bounds: &BoundingBox32, delta: vec3, hr: &mut HitRecord<f32, vec3>) -> bool {
+		match self {
+			Node::Inner(ch) => {
+				let swept = bounds.join(&BoundingBox::new(bounds.min + delta, bounds.max + delta));
+				ch.iter().fold(false, |hit, (bb, node)| hit | (bb.intersects_box(&swept) && node.sweep_box(bounds, delta, hr)))
+			}
+			Node::Leaf(ch) => ch[0].sweep_box(bounds, delta, hr) | ch[1].sweep_box(bounds, delta, hr),
+		}
+	}
+}
+
 //-------------------------------------------------------------------------------- Volume
 
 /// A node of `Volume`s is itself a `Volume`.
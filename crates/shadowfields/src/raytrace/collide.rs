@@ -0,0 +1,145 @@
+use super::internal::*;
+use std::iter;
+
+/// Trait for anything that a (moving) box can bump into.
+/// Implemented by faces, BVH trees,...
+///
+/// Used for player collisions, where a box is more accurate than probing with rays:
+/// thin geometry and edges cannot slip through.
+pub trait Collide {
+	/// Does a box overlap the shape? Touching does not count.
+	fn overlaps_box(&self, bounds: &BoundingBox32) -> bool;
+
+	/// Move a box along `delta`, record where it first touches the shape (if nearer than `hr.t`).
+	/// The `HitRecord` holds the time of impact, as a fraction of `delta`, and the contact normal.
+	/// See `sweep_box_triangle`.
+	fn sweep_box(&self, bounds: &BoundingBox32, delta: vec3, hr: &mut HitRecord<f32, vec3>) -> bool;
+}
+
+/// Does a box overlap a triangle? Touching does not count.
+/// Separating axis test after Akenine-Möller, "Fast 3D Triangle-Box Overlap Testing".
+pub fn box_overlaps_triangle(bounds: &BoundingBox32, tri: &[vec3; 3]) -> bool {
+	separating_axes(tri).all(|axis| {
+		let (b0, b1) = project_box(bounds, axis);
+		let (t0, t1) = project_triangle(tri, axis);
+		b1 > t0 && b0 < t1
+	})
+}
+
+/// Move a box along `delta`, where does it first touch a triangle?
+/// Returns the time of impact, as a fraction of `delta` (between 0 and 1),
+/// and the unit contact normal (facing the box, against the movement).
+///
+/// Triangles that already overlap the box at the start are ignored,
+/// so that a box stuck in geometry can still get out.
+pub fn sweep_box_triangle(bounds: &BoundingBox32, delta: vec3, tri: &[vec3; 3]) -> Option<(f32, vec3)> {
+	// Along each separating axis, the projections overlap during a time interval.
+	// The shapes touch during the intersection of all intervals, starting when the last axis starts overlapping.
+	let mut enter = (f32::NEG_INFINITY, vec3::ZERO);
+	let mut exit = f32::INFINITY;
+	for axis in separating_axes(tri) {
+		let (b0, b1) = project_box(bounds, axis);
+		let (t0, t1) = project_triangle(tri, axis);
+		let d = delta.dot(axis);
+		if d == 0.0 {
+			if b1 <= t0 || b0 >= t1 {
+				return None; // separated along this axis, forever
+			}
+			continue;
+		}
+		let (ta, tb) = ((t0 - b1) / d, (t1 - b0) / d);
+		if f32::min(ta, tb) > enter.0 {
+			enter = (f32::min(ta, tb), -d.signum() * axis);
+		}
+		exit = f32::min(exit, f32::max(ta, tb));
+	}
+	let (t, normal) = enter;
+	((0.0..=1.0).contains(&t) && t < exit).then(|| (t, normal.normalized()))
+}
+
+/// Candidate separating axes for a box and a triangle: the box's face normals, the triangle's normal,
+/// and the cross products of their edges. Zero axes (e.g. from edges parallel to a box face) are skipped.
+fn separating_axes(tri: &[vec3; 3]) -> impl Iterator<Item = vec3> {
+	let box_axes = [vec3::EX, vec3::EY, vec3::EZ];
+	let edges = [tri[1] - tri[0], tri[2] - tri[1], tri[0] - tri[2]];
+	let normal = edges[0].cross(edges[1]);
+	box_axes
+		.into_iter()
+		.chain(iter::once(normal))
+		.chain(edges.into_iter().flat_map(move |e| box_axes.map(|a| e.cross(a))))
+		.filter(|axis| axis.dot(*axis) > 1e-12)
+}
+
+/// Interval covered by a box projected onto an axis.
+fn project_box(bounds: &BoundingBox32, axis: vec3) -> (f32, f32) {
+	let c = bounds.center().dot(axis);
+	let h = bounds.size() / 2.0;
+	let r = h.x() * axis.x().abs() + h.y() * axis.y().abs() + h.z() * axis.z().abs();
+	(c - r, c + r)
+}
+
+/// Interval covered by a triangle projected onto an axis.
+fn project_triangle(tri: &[vec3; 3], axis: vec3) -> (f32, f32) {
+	let [a, b, c] = tri.map(|v| v.dot(axis));
+	(a.min(b).min(c), a.max(b).max(c))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn unit_box(center_bottom: vec3) -> BoundingBox32 {
+		BoundingBox::new(center_bottom - vec3(0.5, 0.0, 0.5), center_bottom + vec3(0.5, 1.0, 0.5))
+	}
+
+	// wall in the plane x = 2, facing -x.
+	const WALL: [vec3; 3] = [vec3(2.0, -10.0, -10.0), vec3(2.0, 10.0, -10.0), vec3(2.0, 0.0, 10.0)];
+
+	#[test]
+	fn overlaps() {
+		assert!(!box_overlaps_triangle(&unit_box(vec3(0.0, 0.0, 0.0)), &WALL));
+		assert!(!box_overlaps_triangle(&unit_box(vec3(1.5, 0.0, 0.0)), &WALL)); // touching
+		assert!(box_overlaps_triangle(&unit_box(vec3(1.6, 0.0, 0.0)), &WALL));
+		assert!(box_overlaps_triangle(&unit_box(vec3(2.4, 0.0, 0.0)), &WALL));
+		assert!(!box_overlaps_triangle(&unit_box(vec3(2.6, 0.0, 0.0)), &WALL));
+
+		// a sliver poking into the box's side, missed by diagonal probes.
+		let sliver = [vec3(0.4, 0.5, -0.01), vec3(0.4, 0.5, 0.01), vec3(3.0, 0.5, 0.0)];
+		assert!(box_overlaps_triangle(&unit_box(vec3(0.0, 0.0, 0.0)), &sliver));
+		assert!(!box_overlaps_triangle(&unit_box(vec3(-0.2, 0.0, 0.0)), &sliver));
+
+		// near a triangle's diagonal edge, but outside it.
+		let tri = [vec3(0.0, 0.0, 0.0), vec3(10.0, 0.0, 0.0), vec3(0.0, 0.0, 10.0)];
+		assert!(box_overlaps_triangle(&unit_box(vec3(1.0, -0.5, 1.0)), &tri));
+		assert!(!box_overlaps_triangle(&unit_box(vec3(6.0, -0.5, 6.0)), &tri));
+	}
+
+	#[test]
+	fn sweep() {
+		let b = unit_box(vec3(0.0, 0.0, 0.0));
+
+		// head-on
+		let (t, n) = sweep_box_triangle(&b, vec3(3.0, 0.0, 0.0), &WALL).unwrap();
+		assert!((t - 0.5).abs() < 1e-6);
+		assert!((n - vec3(-1.0, 0.0, 0.0)).len() < 1e-6);
+
+		// at an angle: same time of impact along x
+		let (t, n) = sweep_box_triangle(&b, vec3(3.0, 0.0, 3.0), &WALL).unwrap();
+		assert!((t - 0.5).abs() < 1e-6);
+		assert!((n - vec3(-1.0, 0.0, 0.0)).len() < 1e-6);
+
+		// too short, parallel, away
+		assert_eq!(sweep_box_triangle(&b, vec3(1.0, 0.0, 0.0), &WALL), None);
+		assert_eq!(sweep_box_triangle(&b, vec3(0.0, 0.0, 3.0), &WALL), None);
+		assert_eq!(sweep_box_triangle(&b, vec3(-3.0, 0.0, 0.0), &WALL), None);
+
+		// already stuck: ignored
+		assert_eq!(sweep_box_triangle(&unit_box(vec3(2.0, 0.0, 0.0)), vec3(1.0, 0.0, 0.0), &WALL), None);
+
+		// resting on a floor: blocked right away
+		let floor = [vec3(-10.0, 0.0, -10.0), vec3(0.0, 0.0, 10.0), vec3(10.0, 0.0, -10.0)];
+		let (t, n) = sweep_box_triangle(&b, vec3(0.0, -1.0, 0.0), &floor).unwrap();
+		assert_eq!(t, 0.0);
+		assert!((n - vec3::EY).len() < 1e-6);
+	}
+}
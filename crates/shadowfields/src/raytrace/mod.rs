@@ -3,6 +3,7 @@ mod internal;
 mod bounded;
 mod boundingbox;
 mod bvh_tree;
+mod collide;
 mod halton;
 mod hit_record;
 mod integration;
@@ -17,6 +18,7 @@ mod volume;
 pub use bounded::*;
 pub use boundingbox::*;
 pub use bvh_tree::*;
+pub use collide::*;
 pub use halton::*;
 pub use hit_record::*;
 pub use integration::*;
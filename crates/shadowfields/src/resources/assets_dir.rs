@@ -13,6 +13,13 @@ pub fn init_assets_dir(assets_base_name: &str) -> Result<()> {
 	}
 }
 
+/// `init_assets_dir` for tests, which may run in any order (or in parallel).
+#[cfg(test)]
+pub(crate) fn init_test_assets_dir() {
+	static ONCE: std::sync::Once = std::sync::Once::new();
+	ONCE.call_once(|| init_assets_dir("../../assets").unwrap());
+}
+
 /// Absolute path to the "assets" directory.
 /// `init_assets_dir` must be called prior.
 pub fn assets_dir() -> &'static AssetsDir {
@@ -105,7 +112,7 @@ impl AssetsDir {
 	///   find_asset("/path/to/assets/textures", "lava", &["png", "jpg"])? =>  /path/to/assets/textures/lava.jpg
 	fn find_asset(dir: &Path, base: &str, extensions: &[&str]) -> Result<PathBuf> {
 		for ext in extensions {
-			let file = dir.join(base.to_owned() + "." + ext); // note: do not use .with_extension, *replaces* extension.
+			let file = dir.join(base.to_owned() + "." + *ext); // note: do not use .with_extension, *replaces* extension.
 			if file.exists() {
 				return Ok(file);
 			}
@@ -113,6 +120,11 @@ impl AssetsDir {
 		Err(anyhow!("asset not found: {:?} with extension {}", dir.join(base), extensions.join(", ")))
 	}
 
+	/// Server-wide scripts (see `Scripts`).
+	pub fn scripts_dir(&self) -> PathBuf {
+		self.0.join("scripts")
+	}
+
 	pub fn audio_dir(&self) -> PathBuf {
 		self.0.join("audio")
 	}
@@ -156,6 +168,22 @@ impl MapDir {
 		self.0.join("collider_mesh.bincode.gz")
 	}
 
+	/// Meshes of moving geometry (doors, lifts,...), see `MoverDef`.
+	/// Not part of the scene mesh or collider mesh.
+	pub fn mover_mesh_file(&self) -> PathBuf {
+		self.0.join("mover_mesh.bincode.gz")
+	}
+
+	/// Navigation graph for bots (see `NavGraph`).
+	pub fn nav_graph_file(&self) -> PathBuf {
+		self.0.join("nav_graph.bincode.gz")
+	}
+
+	/// Scripts shipped with the map (see `Scripts`).
+	pub fn scripts_dir(&self) -> PathBuf {
+		self.0.join("scripts")
+	}
+
 	pub fn baking_file(&self) -> PathBuf {
 		self.0.join("baking.ron")
 	}
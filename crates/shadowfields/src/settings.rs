@@ -10,6 +10,7 @@ pub struct Settings {
 	pub network: NetworkOpts,
 	pub debug: DebugOpts,
 	pub bot: BotSettings,
+	pub demo: DemoOpts,
 }
 
 impl Settings {
@@ -24,6 +25,7 @@ impl Settings {
 			network: t.network,
 			debug: t.debug,
 			bot: t.bot,
+			demo: t.demo,
 		})
 	}
 }
@@ -41,6 +43,8 @@ struct SettingsToml {
 	pub debug: DebugOpts,
 	#[serde(default)]
 	pub bot: BotSettings,
+	#[serde(default)]
+	pub demo: DemoOpts,
 }
 
 #[derive(Deserialize, Clone)]
@@ -79,6 +83,10 @@ pub struct PlayerOpts {
 	pub team: String,
 	#[serde(default)]
 	pub advantage: bool,
+	/// Identifies the player to servers (lifetime stats, reserved name).
+	/// Generated and stored in `identity.key` if not set.
+	#[serde(default)]
+	pub identity_key: Option<String>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -100,6 +108,18 @@ pub struct NetworkOpts {
 	pub servers: Vec<String>,
 }
 
+/// Demo recording and playback (see `Demo`).
+#[derive(Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DemoOpts {
+	/// Record the game to this file.
+	#[serde(default)]
+	pub record: Option<String>,
+	/// Play back this demo file instead of connecting to a server.
+	#[serde(default)]
+	pub play: Option<String>,
+}
+
 #[derive(Deserialize, Clone)]
 #[serde(default)]
 pub struct DebugOpts {
@@ -109,6 +129,7 @@ pub struct DebugOpts {
 	pub fps_overlay: bool,
 	pub ecs_overlay: bool,
 	pub bb_overlay: bool,
+	pub jump_pad_overlay: bool,
 	pub axes: bool,
 	pub time_passage: f32,
 	pub monitor_mouse: bool,
@@ -123,6 +144,7 @@ impl Default for DebugOpts {
 			fps_overlay: default(),
 			ecs_overlay: default(),
 			bb_overlay: default(),
+			jump_pad_overlay: default(),
 			axes: default(),
 			time_passage: 1.0,
 			monitor_mouse: false,
@@ -1,3 +1,4 @@
+use super::internal::*;
 use rand::Rng;
 
 pub fn pick_random<T>(opts: &[T]) -> Option<&T> {
@@ -7,6 +8,29 @@ pub fn pick_random<T>(opts: &[T]) -> Option<&T> {
 	}
 }
 
+/// Like `pick_random`, but drawing from `rng` (e.g. seeded, for reproducible games).
+pub fn pick_random_with<'a, T>(rng: &mut impl Rng, opts: &'a [T]) -> Option<&'a T> {
+	match opts.len() {
+		0 => None,
+		n => Some(&opts[rng.gen_range(0..n)]),
+	}
+}
+
 pub fn must_pick_random<T: Clone>(opts: &[T]) -> T {
 	pick_random(opts).expect("zero options").clone()
 }
+
+pub fn must_pick_random_with<T: Clone>(rng: &mut impl Rng, opts: &[T]) -> T {
+	pick_random_with(rng, opts).expect("zero options").clone()
+}
+
+/// The elements of `v` in random order.
+pub fn shuffled<T>(v: Vec<T>) -> Vec<T> {
+	shuffled_with(&mut rand::thread_rng(), v)
+}
+
+/// Like `shuffled`, but drawing from `rng`.
+pub fn shuffled_with<T>(rng: &mut impl Rng, v: Vec<T>) -> Vec<T> {
+	use rand::seq::SliceRandom;
+	v.with(|v| v.shuffle(rng))
+}